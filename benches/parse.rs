@@ -0,0 +1,74 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::*;
+
+fn parse(c: &mut Criterion) {
+    let samples = [
+        ("vaccine", VACCINE_SAMPLE_PAYLOAD),
+        ("pcr_test", PCR_TEST_SAMPLE_PAYLOAD),
+        ("antigen_test", ANTIGEN_TEST_SAMPLE_PAYLOAD),
+        ("recovery", RECOVERY_SAMPLE_PAYLOAD),
+    ];
+
+    let mut group = c.benchmark_group("parse");
+
+    for (name, hc1) in samples {
+        group.bench_function(name, |b| {
+            b.iter(|| greenpass::parse(black_box(hc1)).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+// Full parse against the summary, over a batch of certificates as a scanner would process them
+fn parse_summary(c: &mut Criterion) {
+    let batch: Vec<_> = [
+        VACCINE_SAMPLE_PAYLOAD,
+        PCR_TEST_SAMPLE_PAYLOAD,
+        ANTIGEN_TEST_SAMPLE_PAYLOAD,
+        RECOVERY_SAMPLE_PAYLOAD,
+    ]
+    .repeat(250);
+
+    let mut group = c.benchmark_group("parse_summary");
+    group.throughput(Throughput::Elements(batch.len() as u64));
+
+    group.bench_function("parse", |b| {
+        b.iter(|| {
+            for hc1 in &batch {
+                greenpass::parse(black_box(hc1)).unwrap();
+            }
+        })
+    });
+    group.bench_function("parse_summary", |b| {
+        b.iter(|| {
+            for hc1 in &batch {
+                greenpass::parse_summary(black_box(hc1)).unwrap();
+            }
+        })
+    });
+
+    group.finish();
+}
+
+// Decoding of already inflated COSE data, with and without copying the strings
+fn from_cose_bytes(c: &mut Criterion) {
+    let cose = greenpass::decode_payload(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let mut group = c.benchmark_group("from_cose_bytes");
+
+    group.bench_function("owned", |b| {
+        b.iter(|| greenpass::HealthCert::from_cose_bytes(black_box(&cose)).unwrap())
+    });
+    group.bench_function("borrowed", |b| {
+        b.iter(|| greenpass::HealthCertRef::from_cose_bytes(black_box(&cose)).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, parse, parse_summary, from_cose_bytes);
+criterion_main!(benches);
@@ -0,0 +1,2 @@
+// The exported functions are defined by greenpass itself, this crate only links them into a cdylib.
+pub use greenpass::parse_to_json;
@@ -1,206 +1,203 @@
 use std::{
-    cell::RefCell,
-    fs::read,
+    fs::{read, read_to_string},
     io::{self, prelude::*, stdin},
     process::exit,
 };
 
-use clap::Parser;
+use clap::{ArgEnum, Parser};
 
-use greenpass::{CertInfo, GreenPass, HealthCert, Recovery, Test, TestName, Vaccine};
+use greenpass::{HealthCert, TrustList};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Utility to quickly inspect EU Digital Green Certificates. Does not support validation yet.
+/// Utility to quickly inspect EU Digital Green Certificates, optionally verifying their signature.
 #[derive(Debug, Parser)]
 #[clap(version = VERSION)]
 struct Opts {
-    /// File containing a Base45 QR code payload.
-    /// Omit or specify `-` to read from stdin
+    /// File containing one or more Base45 QR code payloads, one per line, or a PNG/JPEG image of the QR code if built
+    /// with the `qr` feature. Omit or specify `-` to read from stdin
     #[clap(default_value = "-")]
     file: String,
-}
 
-fn read_stdin() -> io::Result<Vec<u8>> {
-    let mut buf = Vec::new();
+    /// Output format for the parsed certificate
+    #[clap(short = 'o', long, arg_enum, default_value = "text")]
+    format: OutputFormat,
 
-    stdin().read_to_end(&mut buf)?;
+    /// Indent the JSON output. Ignored for the text format
+    #[clap(long)]
+    pretty: bool,
 
-    Ok(buf)
-}
+    /// Verify the certificate signature using the DSC keys in the given JSON trust list,
+    /// formatted as `{ "<base64 KID>": "<PEM or base64 key>" }`
+    #[clap(long, value_name = "TRUSTLIST")]
+    verify: Option<String>,
 
-thread_local! {
-    static WS: RefCell<String> = RefCell::default();
+    /// Colorize the text output. `auto` only does so when writing to a terminal and `NO_COLOR` is not set
+    #[cfg(feature = "color")]
+    #[clap(long, arg_enum, default_value = "auto")]
+    color: ColorChoice,
 }
 
-fn pad(n: usize) {
-    WS.with(|wsbuf| {
-        if wsbuf.borrow().len() != n {
-            *wsbuf.borrow_mut() = " ".repeat(n);
-        }
-
-        print!("{}", wsbuf.borrow());
-    });
+#[derive(ArgEnum, Clone, Copy, Debug)]
+enum OutputFormat {
+    Text,
+    Json,
 }
 
-macro_rules! padn {
-    () => (println!());
-    ($n:expr, $($arg:tt)*) => ({
-        pad($n);
-        println!($($arg)*);
-    })
+#[cfg(feature = "color")]
+#[derive(ArgEnum, Clone, Copy, Debug)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
 }
 
-macro_rules! pad4 {
-    () => (println!());
-    ($($arg:tt)*) => (padn!(4, $($arg)*));
-}
+#[cfg(feature = "color")]
+fn use_color(opts: &Opts) -> bool {
+    use std::io::IsTerminal;
 
-macro_rules! pad8 {
-    () => (println!());
-    ($($arg:tt)*) => (padn!(8, $($arg)*));
+    match opts.color {
+        ColorChoice::Auto => {
+            std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) && io::stdout().is_terminal()
+        }
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    }
 }
 
-fn dump_recovery(r: &Recovery) {
-    let Recovery {
-        cert_id,
-        country,
-        diagnosed,
-        disease,
-        issuer,
-        valid_from,
-        valid_until,
-    } = r;
-
-    pad4!("Recovery attestation:");
-    pad8!("Cert ID: {}", cert_id);
-    pad8!("Disease: {}", disease);
-    pad8!("Issuer: {}", issuer);
-    pad8!("Country: {}", country);
-    pad8!("Tested positive: {}", diagnosed);
-    pad8!("Valid from: {}", valid_from);
-    pad8!("Valid until: {}", valid_until);
+#[cfg(not(feature = "color"))]
+fn use_color(_opts: &Opts) -> bool {
+    false
 }
 
-fn dump_test(t: &Test) {
-    let Test {
-        cert_id,
-        collect_ts,
-        country,
-        disease,
-        issuer,
-        name,
-        result,
-        test_type,
-        testing_centre,
-    } = t;
-
-    pad4!("Testing attestation:");
-    pad8!("Cert ID: {}", cert_id);
-    pad8!("Disease: {}", disease);
-    pad8!("Result code: {}", result);
-    pad8!("Samples collected at: {}", collect_ts);
-
-    let tn_str = match name {
-        TestName::NAAT { name } => format!("Nucleic Acid Amplification Test ({})", name),
-        TestName::RAT { device_id } => format!("Rapid Antigen Test (device: {})", device_id),
+// Highlights the labels of the text dump, and the expiration date according to the validity of the certificate
+#[cfg(feature = "color")]
+fn colorize(hc: &HealthCert) -> String {
+    use greenpass::{SystemClock, ValidityStatus};
+    use owo_colors::{AnsiColors, OwoColorize};
+
+    let status_color = match hc.validity_status_with(&SystemClock) {
+        ValidityStatus::Valid => AnsiColors::Green,
+        ValidityStatus::Expired => AnsiColors::Red,
+        ValidityStatus::NotYetValid => AnsiColors::Yellow,
     };
 
-    pad8!("Test type: {}, ID: {}", tn_str, test_type);
-    pad8!("Conducted by: {}", testing_centre);
-    pad8!("Issuer: {}", issuer);
-    pad8!("Country: {}", country);
+    hc.to_string()
+        .lines()
+        .map(|line| {
+            let (pad, content) = line.split_at(line.len() - line.trim_start().len());
+
+            match content.split_once(": ") {
+                Some((label @ "Expires at", value)) => format!(
+                    "{}{}: {}",
+                    pad,
+                    label.bold(),
+                    value.color(status_color).bold()
+                ),
+                Some((label, value)) => format!("{}{}: {}", pad, label.bold(), value),
+                None if content.ends_with(':') => format!("{}{}", pad, content.cyan().bold()),
+                None => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-fn dump_vaccination(v: &Vaccine) {
-    let Vaccine {
-        cert_id,
-        country,
-        date,
-        disease,
-        dose_number,
-        dose_total,
-        issuer,
-        market_auth,
-        product,
-        prophylaxis_kind,
-    } = v;
-
-    pad4!("Vaccination data:");
-
-    pad8!("Cert ID: {}", cert_id);
-    pad8!("Disease: {}", disease);
-    pad8!("Issuer: {}", issuer);
-    pad8!("Country: {}", country);
-    pad8!("Vaccination date: {}", date);
-    pad8!("Doses administered: {}/{}", dose_number, dose_total);
-    pad8!("Product ID: {}", product);
-    pad8!("Market Authorization ID: {}", market_auth);
-    pad8!("Vaccine/Prophylaxis ID: {}", prophylaxis_kind);
+#[cfg(not(feature = "color"))]
+fn colorize(hc: &HealthCert) -> String {
+    hc.to_string()
 }
 
-fn dump_greenpass(gp: &GreenPass) {
-    let GreenPass {
-        date_of_birth,
-        surname,
-        givenname,
-        std_surname,
-        std_givenname,
-        ver,
-        entries,
-    } = gp;
-
-    pad4!("Cert version {}", ver);
-
-    pad4!("Emitted to: {} {}", givenname, surname);
-    pad4!("Standardized Name: {} {}", std_givenname, std_surname);
-    pad4!("Date of birth: {}\n", date_of_birth);
-
-    for ci in entries {
-        match ci {
-            CertInfo::Recovery(r) => dump_recovery(r),
-            CertInfo::Test(t) => dump_test(t),
-            CertInfo::Vaccine(v) => dump_vaccination(v),
-        }
-    }
+#[cfg(feature = "qr")]
+fn decode_qr(buf: &[u8]) -> anyhow::Result<Option<String>> {
+    use anyhow::anyhow;
+    use image::ImageFormat;
+
+    const PNG_MAGIC: &[u8] = b"\x89PNG\r\n\x1a\n";
+    const JPEG_MAGIC: &[u8] = b"\xff\xd8\xff";
+
+    let format = if buf.starts_with(PNG_MAGIC) {
+        ImageFormat::Png
+    } else if buf.starts_with(JPEG_MAGIC) {
+        ImageFormat::Jpeg
+    } else {
+        return Ok(None);
+    };
+
+    let img = image::load_from_memory_with_format(buf, format)?.into_luma8();
+
+    let mut prepared = rqrr::PreparedImage::prepare_from_greyscale(
+        img.width() as usize,
+        img.height() as usize,
+        |x, y| img.get_pixel(x as u32, y as u32).0[0],
+    );
+
+    prepared
+        .detect_grids()
+        .into_iter()
+        .filter_map(|grid| grid.decode().ok())
+        .map(|(_, content)| content)
+        .find(|content| content.starts_with("HC1:"))
+        .map(Some)
+        .ok_or_else(|| anyhow!("no QR code containing a health certificate found in image"))
 }
 
-fn dump_hc(hc: &HealthCert) {
-    let HealthCert {
-        created,
-        expires,
-        passes,
-        some_issuer,
-        signature,
-    } = hc;
+#[cfg(not(feature = "qr"))]
+fn decode_qr(_buf: &[u8]) -> anyhow::Result<Option<String>> {
+    Ok(None)
+}
 
-    println!("EU Digital COVID Certificate\n");
+fn read_stdin() -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
 
-    if let Some(issuer) = some_issuer {
-        println!("Issued by: {}", issuer);
-    }
+    stdin().read_to_end(&mut buf)?;
 
-    println!("Created at: {}", created);
-    println!("Expires at: {}", expires);
-    println!();
+    Ok(buf)
+}
 
-    println!("Signature algorithm: {}", signature.algorithm);
-    println!("Signature KID: {}", hex::encode(&signature.kid));
-    println!(
-        "Signature data: ({} bytes) {}",
-        signature.signature.len(),
-        hex::encode(&signature.signature)
-    );
-    println!();
+// Prints a certificate, returning false if its signature could not be verified
+fn show(
+    hc: &HealthCert,
+    format: OutputFormat,
+    pretty: bool,
+    color: bool,
+    trust: Option<&TrustList>,
+) -> anyhow::Result<bool> {
+    match format {
+        OutputFormat::Text if color => println!("{}", colorize(hc)),
+        OutputFormat::Text => println!("{}", hc),
+        OutputFormat::Json if pretty => println!("{}", serde_json::to_string_pretty(hc)?),
+        OutputFormat::Json => println!("{}", serde_json::to_string(hc)?),
+    }
 
-    for (i, pass) in passes.iter().enumerate() {
-        println!("Pass#{}:", i);
-        dump_greenpass(pass);
+    if let Some(trust) = trust {
+        match hc.verify_with(trust) {
+            Ok(_) => println!("SIGNATURE OK"),
+            Err(e) => {
+                println!("SIGNATURE INVALID ({})", e);
+                return Ok(false);
+            }
+        }
     }
+
+    Ok(true)
 }
 
 fn main_do() -> std::result::Result<(), anyhow::Error> {
-    let Opts { file } = Opts::parse();
+    let opts = Opts::parse();
+    let color = use_color(&opts);
+
+    let Opts {
+        file,
+        format,
+        pretty,
+        verify,
+        ..
+    } = opts;
+
+    let trust = verify
+        .map(|path| TrustList::from_json(&read_to_string(path)?))
+        .transpose()?;
 
     let buf = if file == "-" {
         read_stdin()?
@@ -209,9 +206,53 @@ fn main_do() -> std::result::Result<(), anyhow::Error> {
     };
 
     if !buf.is_empty() {
-        let buf_str = String::from_utf8(buf)?;
+        let mut results = match decode_qr(&buf)? {
+            Some(payload) => greenpass::parse_many(&payload),
+
+            // lines are parsed as bytes, as scanners may append framing bytes that are not valid UTF-8
+            None => buf
+                .split(|&b| b == b'\n')
+                .enumerate()
+                .filter(|(_, line)| !line.iter().all(u8::is_ascii_whitespace))
+                .map(|(i, line)| (i + 1, greenpass::parse_bytes(line)))
+                .collect(),
+        };
+        let total = results.len();
+
+        // whitespace, or bytes without any HC1: marker
+        if total == 0 {
+            anyhow::bail!("no HC1 certificate found in input");
+        }
 
-        dump_hc(&greenpass::parse(&buf_str)?);
+        let (mut valid, mut malformed) = (true, 0);
+
+        if total == 1 {
+            let (_, res) = results.remove(0);
+
+            valid = show(&res?, format, pretty, color, trust.as_ref())?;
+        } else {
+            for (line, res) in results {
+                match res {
+                    Ok(hc) => valid &= show(&hc, format, pretty, color, trust.as_ref())?,
+                    Err(e) => {
+                        eprintln!("error: line {}: {}", line, e);
+                        malformed += 1;
+                    }
+                }
+            }
+        }
+
+        if malformed > 0 {
+            anyhow::bail!(
+                "{} of {} certificates could not be parsed",
+                malformed,
+                total
+            );
+        }
+
+        if !valid {
+            exit(1);
+        }
     }
 
     Ok(())
@@ -0,0 +1,577 @@
+// Zero-copy decoding of certificates, for scanners that only inspect a certificate while they hold the inflated COSE
+// buffer. ciborium always copies strings out of its input, so this module has its own minimal CBOR decoder, whose
+// values borrow text and byte strings from the buffer.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use chrono::prelude::*;
+
+use crate::{entry_error, entry_path, timestamp, Error, Result, MAX_CBOR_DEPTH};
+
+/// A certificate decoded without copying its strings, which borrow from the buffer it has been decoded from, see
+/// [HealthCertRef::from_cose_bytes].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HealthCertRef<'a> {
+    /// Member country that issued the bundle, as it appears in the certificate (might be missing)
+    pub some_issuer: Option<&'a str>,
+
+    /// Bundle creation timestamp
+    pub created: DateTime<Utc>,
+
+    /// Bundle expiration timestamp
+    pub expires: DateTime<Utc>,
+
+    /// Time before which the bundle must not be accepted, if set by the issuer
+    pub not_before: Option<DateTime<Utc>>,
+
+    /// Passes contained in this bundle, never empty
+    pub passes: Vec<GreenPassRef<'a>>,
+
+    /// Key id, from the unprotected header or else from the protected one
+    pub kid: &'a [u8],
+
+    /// Algorithm used for signing
+    pub algorithm: i128,
+
+    /// Raw signature
+    pub signature: &'a [u8],
+}
+
+/// Borrowed counterpart of [GreenPass](crate::GreenPass).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GreenPassRef<'a> {
+    /// Date of birth
+    pub date_of_birth: &'a str, // dob
+
+    /// Family name, if present
+    pub surname: Option<&'a str>, // nam/fn
+
+    /// First name, absent for holders with a single name
+    pub givenname: Option<&'a str>, // nam/gn
+
+    /// Family name in standardized form
+    pub std_surname: &'a str, // nam/fnt
+
+    /// First name in standardized form, absent for holders with a single name
+    pub std_givenname: Option<&'a str>, // nam/gnt
+
+    /// Document version
+    pub ver: &'a str, // ver
+
+    /// Attestations held by the pass, never empty
+    pub entries: Vec<CertInfoRef<'a>>, // [v | t | r]
+}
+
+/// Borrowed counterpart of [CertInfo](crate::CertInfo), limited to the entry kinds of the EU specification.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CertInfoRef<'a> {
+    Recovery(RecoveryRef<'a>),
+    Test(TestRef<'a>),
+    Vaccine(VaccineRef<'a>),
+}
+
+/// Borrowed counterpart of [Recovery](crate::Recovery). Dates are left as they appear in the certificate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecoveryRef<'a> {
+    /// Certificate ID
+    pub cert_id: &'a str, // ci
+
+    /// Member State where the test was performed
+    pub country: &'a str, // co
+
+    /// Date of diagnosis
+    pub diagnosed: &'a str, // fr
+
+    /// String that identifies the contracted disease
+    pub disease: &'a str, // tg
+
+    /// Issuing entity
+    pub issuer: &'a str, // is
+
+    /// Recovery attestation validity start date
+    pub valid_from: &'a str, // df
+
+    /// Recovery attestation validity expire date
+    pub valid_until: &'a str, // du
+}
+
+/// Borrowed counterpart of [Test](crate::Test). Timestamps are left as they appear in the certificate, and at least
+/// one of `name` and `device_id` is set.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TestRef<'a> {
+    /// Certificate ID
+    pub cert_id: &'a str, // ci
+
+    /// Date and time when samples where collected
+    pub collect_ts: &'a str, // sc
+
+    /// Member State where the test was performed
+    pub country: &'a str, // co
+
+    /// Target disease
+    pub disease: &'a str, // tg
+
+    /// Issuing entity
+    pub issuer: &'a str, // is
+
+    /// Name of a NAAT test
+    pub name: Option<&'a str>, // nm
+
+    /// Device identifier of a rapid antigen test
+    pub device_id: Option<&'a str>, // ma
+
+    /// Test result, as defined in SNOMED CT GPS
+    pub result: &'a str, // tr
+
+    /// Coded string value identifying the testing method
+    pub test_type: &'a str, // tt
+
+    /// Name of the centre that conducted the test
+    pub testing_centre: Option<&'a str>, // tc
+
+    /// Date and time when the test result was produced, only found in schemas older than 1.3.0
+    pub result_ts: Option<&'a str>, // dr
+}
+
+/// Borrowed counterpart of [Vaccine](crate::Vaccine). The date is left as it appears in the certificate.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VaccineRef<'a> {
+    /// Certificate ID
+    pub cert_id: &'a str, // ci
+
+    /// Vaccination country
+    pub country: &'a str, // co
+
+    /// Vaccination date
+    pub date: &'a str, // dt
+
+    /// Targeted disease
+    pub disease: &'a str, // tg
+
+    /// Number of administered doses
+    pub dose_number: usize, // dn
+
+    /// Total number of doses required by the administered vaccine
+    pub dose_total: usize, // sd
+
+    /// Issuing entity
+    pub issuer: &'a str, // is
+
+    /// EUDCC Gateway market authorization identifier
+    pub market_auth: &'a str, // ma
+
+    /// Product identifier as defined in EUDCC Gateway
+    pub product: &'a str, // mp
+
+    /// Type of vaccine or prophylaxis used as defined in EUDCC Gateway
+    pub prophylaxis_kind: &'a str, // vp
+}
+
+impl<'a> HealthCertRef<'a> {
+    /// Decodes a certificate from the raw COSE_Sign1 structure, borrowing every string from `data`, which can be
+    /// obtained with [decode_payload](crate::decode_payload).
+    ///
+    /// This is meant for high-throughput scanners, and only does the checks needed to extract the data: keys outside
+    /// of the specification are ignored, values are not checked against the value sets and only full certificates
+    /// with vaccine, test or recovery entries are supported. Use [HealthCert::from_cose_bytes](crate::HealthCert::from_cose_bytes)
+    /// to validate a certificate, or to verify its signature.
+    ///
+    /// ```no_run
+    /// let cose = greenpass::decode_payload("HC1:...").unwrap();
+    /// let hc = greenpass::HealthCertRef::from_cose_bytes(&cose).unwrap();
+    ///
+    /// for pass in &hc.passes {
+    ///     println!("{} {}", pass.std_surname, pass.date_of_birth);
+    /// }
+    /// ```
+    pub fn from_cose_bytes(data: &'a [u8]) -> Result<Self> {
+        let mut root = decode(data)?;
+
+        // the COSE_Sign1 (18) and CWT (61) tags are optional, and carry no information we need
+        while let ValueRef::Tag(_, inner) = root {
+            root = *inner;
+        }
+
+        // COSE_Sign1 is [protected, unprotected, payload, signature]
+        let [protected, unprotected, payload, signature] = match root {
+            ValueRef::Array(arr) => <[_; 4]>::try_from(arr).map_err(|_| Error::MalformedCWT)?,
+            _ => return Err(Error::MalformedCWT),
+        };
+
+        let (protected, unprotected, payload, signature) =
+            match (protected, unprotected, payload, signature) {
+                (
+                    ValueRef::Bytes(protected),
+                    ValueRef::Map(unprotected),
+                    ValueRef::Bytes(payload),
+                    ValueRef::Bytes(signature),
+                ) => (decode(protected)?, unprotected, decode(payload)?, signature),
+                _ => return Err(Error::MalformedCWT),
+            };
+
+        let protected = match protected {
+            ValueRef::Map(map) => map,
+            _ => {
+                return Err(Error::InvalidFormatFor {
+                    key: "protected properties".into(),
+                })
+            }
+        };
+
+        // HealthCert keeps the unprotected header as a list and takes the first KID in it
+        let unprotected_kid = unprotected
+            .iter()
+            .find(|(k, _)| *k == ValueRef::Integer(4))
+            .map(|(_, v)| v);
+
+        let kid = match unprotected_kid.or_else(|| label(&protected, 4)) {
+            Some(ValueRef::Bytes(kid)) => *kid,
+            Some(_) => return Err(Error::InvalidFormatFor { key: "KID".into() }),
+            None => return Err(Error::MissingKey("KID".into())),
+        };
+
+        let algorithm = match label(&protected, 1) {
+            Some(ValueRef::Integer(alg)) => *alg,
+            Some(_) => {
+                return Err(Error::InvalidFormatFor {
+                    key: "algorithm".into(),
+                })
+            }
+            None => return Err(Error::MissingKey("algorithm".into())),
+        };
+
+        let claims = match payload {
+            ValueRef::Map(map) => map,
+            _ => {
+                return Err(Error::InvalidFormatFor {
+                    key: "root cert".into(),
+                })
+            }
+        };
+
+        let some_issuer = match label(&claims, 1) {
+            Some(ValueRef::Text(iss)) => Some(*iss),
+            Some(_) => {
+                return Err(Error::InvalidFormatFor {
+                    key: "issuing country".into(),
+                })
+            }
+            None => None,
+        };
+
+        let claim_timestamp = |claim: i128, what: &str| match label(&claims, claim) {
+            Some(ValueRef::Integer(ts)) => timestamp(*ts).map(Some),
+            Some(_) => Err(Error::InvalidFormatFor { key: what.into() }),
+            None => Ok(None),
+        };
+
+        let expires = claim_timestamp(4, "expiration timestamp")?
+            .ok_or_else(|| Error::MissingKey("expiration timestamp".into()))?;
+        let created = claim_timestamp(6, "issue timestamp")?
+            .ok_or_else(|| Error::MissingKey("issue timestamp".into()))?;
+        let not_before = claim_timestamp(5, "not before timestamp")?;
+
+        let hcerts = match label(&claims, -260) {
+            Some(ValueRef::Map(hcerts)) if !hcerts.is_empty() => hcerts,
+            Some(ValueRef::Map(_)) => return Err(Error::MissingKey("hcert entries".into())),
+            Some(_) => {
+                return Err(Error::InvalidFormatFor {
+                    key: "hcert".into(),
+                })
+            }
+            None => return Err(Error::MissingKey("hcert".into())),
+        };
+
+        let passes = hcerts
+            .iter()
+            .map(|(_, pass)| GreenPassRef::from_value(pass))
+            .collect::<Result<_>>()?;
+
+        Ok(HealthCertRef {
+            some_issuer,
+            created,
+            expires,
+            not_before,
+            passes,
+            kid,
+            algorithm,
+            signature,
+        })
+    }
+}
+
+impl<'a> GreenPassRef<'a> {
+    fn from_value(value: &ValueRef<'a>) -> Result<Self> {
+        let pass = Fields::new(value, "hcert")?;
+
+        let (group, entries) = ["r", "t", "v"]
+            .into_iter()
+            .find_map(|group| match pass.get(group) {
+                Some(ValueRef::Array(entries)) => Some((group, entries)),
+                _ => None,
+            })
+            .ok_or_else(|| Error::MissingKey("r, t or v (the actual data)".into()))?;
+
+        let entries = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let fields = Fields::new(entry, "").map_err(|_| Error::InvalidFormatFor {
+                    key: entry_path(group, i),
+                })?;
+
+                match group {
+                    "r" => RecoveryRef::from_fields(&fields).map(CertInfoRef::Recovery),
+                    "t" => TestRef::from_fields(&fields).map(CertInfoRef::Test),
+                    _ => VaccineRef::from_fields(&fields).map(CertInfoRef::Vaccine),
+                }
+                .map_err(|e| entry_error(group, i, e))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if entries.is_empty() {
+            return Err(Error::MissingKey("r, t or v (the actual data)".into()));
+        }
+
+        let nam = Fields::new(
+            pass.get("nam")
+                .ok_or_else(|| Error::MissingKey("nam".into()))?,
+            "nam",
+        )?;
+
+        Ok(GreenPassRef {
+            date_of_birth: pass.text("dob")?,
+            surname: nam.opt_text("fn")?,
+            givenname: nam.opt_text("gn")?,
+            std_surname: nam.text("fnt")?,
+            std_givenname: nam.opt_text("gnt")?,
+            ver: pass.text("ver")?,
+            entries,
+        })
+    }
+}
+
+impl<'a> RecoveryRef<'a> {
+    fn from_fields(fields: &Fields<'_, 'a>) -> Result<Self> {
+        Ok(RecoveryRef {
+            cert_id: fields.text("ci")?,
+            country: fields.text("co")?,
+            diagnosed: fields.text("fr")?,
+            disease: fields.text("tg")?,
+            issuer: fields.text("is")?,
+            valid_from: fields.text("df")?,
+            valid_until: fields.text("du")?,
+        })
+    }
+}
+
+impl<'a> TestRef<'a> {
+    fn from_fields(fields: &Fields<'_, 'a>) -> Result<Self> {
+        let name = fields.opt_text("nm")?;
+        let device_id = fields.opt_text("ma")?;
+
+        if name.is_none() && device_id.is_none() {
+            return Err(Error::MissingKey("ma or nm in test".into()));
+        }
+
+        Ok(TestRef {
+            cert_id: fields.text("ci")?,
+            collect_ts: fields.text("sc")?,
+            country: fields.text("co")?,
+            disease: fields.text("tg")?,
+            issuer: fields.text("is")?,
+            name,
+            device_id,
+            result: fields.text("tr")?,
+            test_type: fields.text("tt")?,
+            testing_centre: fields.opt_text("tc")?,
+            result_ts: fields.opt_text("dr")?,
+        })
+    }
+}
+
+impl<'a> VaccineRef<'a> {
+    fn from_fields(fields: &Fields<'_, 'a>) -> Result<Self> {
+        Ok(VaccineRef {
+            cert_id: fields.text("ci")?,
+            country: fields.text("co")?,
+            date: fields.text("dt")?,
+            disease: fields.text("tg")?,
+            dose_number: fields.uint("dn")?,
+            dose_total: fields.uint("sd")?,
+            issuer: fields.text("is")?,
+            market_auth: fields.text("ma")?,
+            product: fields.text("mp")?,
+            prophylaxis_kind: fields.text("vp")?,
+        })
+    }
+}
+
+// A CBOR data item, borrowing its strings from the buffer it has been decoded from
+#[derive(Clone, Debug, PartialEq)]
+enum ValueRef<'a> {
+    Integer(i128),
+    Bytes(&'a [u8]),
+    Text(&'a str),
+    Array(Vec<ValueRef<'a>>),
+    Map(Vec<(ValueRef<'a>, ValueRef<'a>)>),
+    Tag(u64, Box<ValueRef<'a>>),
+
+    // simple values and floats, which certificates only hold in keys outside of the specification
+    Other,
+}
+
+// Looks up an integer label, as used by COSE headers and CWT claims. Duplicate labels resolve to the last value, as
+// they do in the maps HealthCert collects the protected header and the claims into
+fn label<'v, 'a>(map: &'v [(ValueRef<'a>, ValueRef<'a>)], label: i128) -> Option<&'v ValueRef<'a>> {
+    map.iter()
+        .rfind(|(k, _)| *k == ValueRef::Integer(label))
+        .map(|(_, v)| v)
+}
+
+// The entries of a map with text keys, such as a pass or one of its entries
+struct Fields<'v, 'a>(&'v [(ValueRef<'a>, ValueRef<'a>)]);
+
+impl<'v, 'a> Fields<'v, 'a> {
+    fn new(value: &'v ValueRef<'a>, desc: &str) -> Result<Self> {
+        match value {
+            ValueRef::Map(map) => Ok(Fields(map)),
+            _ => Err(Error::InvalidFormatFor { key: desc.into() }),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&'v ValueRef<'a>> {
+        self.0
+            .iter()
+            // last value wins on duplicate keys, like in the maps HealthCert collects passes and entries into
+            .rfind(|(k, _)| *k == ValueRef::Text(key))
+            .map(|(_, v)| v)
+    }
+
+    fn opt_text(&self, key: &str) -> Result<Option<&'a str>> {
+        match self.get(key) {
+            Some(ValueRef::Text(s)) => Ok(Some(s)),
+            Some(_) => Err(Error::InvalidFormatFor { key: key.into() }),
+            None => Ok(None),
+        }
+    }
+
+    fn text(&self, key: &str) -> Result<&'a str> {
+        self.opt_text(key)?
+            .ok_or_else(|| Error::MissingKey(key.into()))
+    }
+
+    fn uint(&self, key: &str) -> Result<usize> {
+        match self.get(key) {
+            Some(ValueRef::Integer(n)) => {
+                usize::try_from(*n).map_err(|_| Error::InvalidFormatFor { key: key.into() })
+            }
+            Some(_) => Err(Error::InvalidFormatFor { key: key.into() }),
+            None => Err(Error::MissingKey(key.into())),
+        }
+    }
+}
+
+// Decodes the CBOR data item at the start of `data`, bounding its depth like the ciborium decoder does
+fn decode(data: &[u8]) -> Result<ValueRef<'_>> {
+    Decoder { data, pos: 0 }.value(0)
+}
+
+struct Decoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn malformed(&self, offset: usize) -> Error {
+        Error::MalformedCBOR {
+            offset,
+            len: self.data.len(),
+            source: ciborium::de::Error::Syntax(offset),
+        }
+    }
+
+    fn take(&mut self, len: u64) -> Result<&'a [u8]> {
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| self.pos.checked_add(len))
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| self.malformed(self.data.len()))?;
+
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+
+        Ok(bytes)
+    }
+
+    // Reads the argument following an initial byte. Indefinite lengths are rejected, as chunked strings cannot be
+    // borrowed and certificates never use them
+    fn argument(&mut self, info: u8, start: usize) -> Result<u64> {
+        let len = match info {
+            0..=23 => return Ok(info.into()),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => return Err(self.malformed(start)),
+        };
+
+        Ok(self
+            .take(len)?
+            .iter()
+            .fold(0, |arg, &b| (arg << 8) | u64::from(b)))
+    }
+
+    fn value(&mut self, depth: usize) -> Result<ValueRef<'a>> {
+        if depth >= MAX_CBOR_DEPTH {
+            return Err(Error::CborTooDeep);
+        }
+
+        let start = self.pos;
+        let initial = self.take(1)?[0];
+        let (major, info) = (initial >> 5, initial & 0x1f);
+
+        // simple values and floats share the major type, with the argument holding the value itself
+        if major == 7 {
+            return match info {
+                0..=24 => self.take(u64::from(info == 24)),
+                25 => self.take(2),
+                26 => self.take(4),
+                27 => self.take(8),
+                _ => Err(self.malformed(start)),
+            }
+            .map(|_| ValueRef::Other);
+        }
+
+        let arg = self.argument(info, start)?;
+
+        Ok(match major {
+            0 => ValueRef::Integer(arg.into()),
+            1 => ValueRef::Integer(-1 - i128::from(arg)),
+            2 => ValueRef::Bytes(self.take(arg)?),
+            3 => ValueRef::Text(
+                core::str::from_utf8(self.take(arg)?).map_err(|_| self.malformed(start))?,
+            ),
+            4 => {
+                // the length is not trusted to preallocate, as each item takes at least a byte
+                let mut items = Vec::new();
+
+                for _ in 0..arg {
+                    items.push(self.value(depth + 1)?);
+                }
+
+                ValueRef::Array(items)
+            }
+            5 => {
+                let mut entries = Vec::new();
+
+                for _ in 0..arg {
+                    entries.push((self.value(depth + 1)?, self.value(depth + 1)?));
+                }
+
+                ValueRef::Map(entries)
+            }
+            _ => ValueRef::Tag(arg, Box::new(self.value(depth + 1)?)),
+        })
+    }
+}
@@ -0,0 +1,151 @@
+// Builders for certificate entries, only available with the `builder` feature.
+// They are mostly meant to produce fixtures and mock certificates.
+
+use alloc::string::String;
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::{Country, Dated, Error, Recovery, Result, Test, TestName, Vaccine, COVID_19};
+
+// Generates a builder holding every field of `$t` as an Option, with one setter per field.
+// The disease always defaults to COVID-19, while the fields listed in `defaults` are left empty and have no setter.
+// Fields listed in `optional` have a setter, but are left empty if it is not called.
+macro_rules! builder {
+    (
+        $(#[$meta:meta])*
+        $name:ident => $t:ident {
+            $($(#[$fmeta:meta])* $field:ident: $ft:ty = $key:literal,)*
+        }
+        $(optional { $($(#[$ometa:meta])* $opt:ident: $ot:ty,)* })?
+        $(defaults { $($default:ident),* })?
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug)]
+        pub struct $name {
+            $($field: Option<$ft>,)*
+            $($($opt: Option<$ot>,)*)?
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                let mut builder = $name {
+                    $($field: None,)*
+                    $($($opt: None,)*)?
+                };
+
+                builder.disease = Some(COVID_19.into());
+
+                builder
+            }
+        }
+
+        impl $name {
+            $(
+                $(#[$fmeta])*
+                pub fn $field(mut self, $field: impl Into<$ft>) -> Self {
+                    self.$field = Some($field.into());
+                    self
+                }
+            )*
+
+            $($(
+                $(#[$ometa])*
+                pub fn $opt(mut self, $opt: impl Into<$ot>) -> Self {
+                    self.$opt = Some($opt.into());
+                    self
+                }
+            )*)?
+
+            /// Builds the entry, failing with [Error::MissingKey] if any field has not been set.
+            pub fn build(self) -> Result<$t> {
+                Ok($t {
+                    $($field: self.$field.ok_or_else(|| Error::MissingKey($key.into()))?,)*
+                    $($($opt: self.$opt,)*)?
+                    $($($default: Default::default(),)*)?
+                })
+            }
+        }
+
+        impl $t {
+            /// Returns a builder for this kind of entry
+            pub fn builder() -> $name {
+                $name::default()
+            }
+        }
+    };
+}
+
+builder! {
+    /// Builds a [Recovery] attestation field by field.
+    RecoveryBuilder => Recovery {
+        /// Certificate ID (`ci`)
+        cert_id: String = "ci",
+        /// Member State where the test was performed (`co`)
+        country: Country = "co",
+        /// Date of diagnosis (`fr`)
+        diagnosed: Dated = "fr",
+        /// Disease the holder recovered from (`tg`), COVID-19 by default
+        disease: String = "tg",
+        /// Issuing entity (`is`)
+        issuer: String = "is",
+        /// Validity start date (`df`)
+        valid_from: Dated = "df",
+        /// Validity expire date (`du`)
+        valid_until: Dated = "du",
+    }
+}
+
+builder! {
+    /// Builds a [Test] attestation field by field.
+    TestBuilder => Test {
+        /// Certificate ID (`ci`)
+        cert_id: String = "ci",
+        /// Date and time when samples where collected (`sc`)
+        collect_ts: DateTime<FixedOffset> = "sc",
+        /// Member State where the test was performed (`co`)
+        country: Country = "co",
+        /// Target disease (`tg`), COVID-19 by default
+        disease: String = "tg",
+        /// Issuing entity (`is`)
+        issuer: String = "is",
+        /// Name and identifier of the used testing technology (`nm` or `ma`)
+        name: TestName = "nm or ma",
+        /// Test result code (`tr`)
+        result: String = "tr",
+        /// Testing method code (`tt`)
+        test_type: String = "tt",
+    }
+    optional {
+        /// Name of the centre that conducted the test (`tc`)
+        testing_centre: String,
+        /// Date and time when the test result was produced (`dr`), only found in schemas older than 1.3.0
+        result_ts: DateTime<FixedOffset>,
+    }
+}
+
+builder! {
+    /// Builds a [Vaccine] entry field by field.
+    VaccineBuilder => Vaccine {
+        /// Certificate ID (`ci`)
+        cert_id: String = "ci",
+        /// Vaccination country (`co`)
+        country: Country = "co",
+        /// Vaccination date (`dt`)
+        date: Dated = "dt",
+        /// Targeted disease (`tg`), COVID-19 by default
+        disease: String = "tg",
+        /// Number of the dose in the series (`dn`)
+        dose_number: usize = "dn",
+        /// Total number of doses in the series (`sd`)
+        dose_total: usize = "sd",
+        /// Issuing entity (`is`)
+        issuer: String = "is",
+        /// Marketing authorization holder or manufacturer (`ma`)
+        market_auth: String = "ma",
+        /// Vaccine medicinal product (`mp`)
+        product: String = "mp",
+        /// Vaccine or prophylaxis (`vp`)
+        prophylaxis_kind: String = "vp",
+    }
+    defaults { extra }
+}
@@ -0,0 +1,405 @@
+// Evaluation of CertLogic business rules, only available with the `certlogic` feature.
+// See https://github.com/ehn-dcc-development/dgc-business-rules/tree/main/certlogic for the specification.
+
+use alloc::{
+    borrow::ToOwned,
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chrono::{prelude::*, Duration, SecondsFormat};
+use serde_derive::Deserialize;
+use serde_json::{json, Value};
+
+use crate::HealthCert;
+
+/// A CertLogic rule, as distributed by the EU DCC Gateway. Fields other than the identifier and the logic are ignored.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CertLogicRule {
+    /// Rule identifier, i.e. `VR-EU-0001`
+    #[serde(rename = "Identifier")]
+    pub identifier: String,
+
+    /// CertLogic expression, which must evaluate to a boolean
+    #[serde(rename = "Logic")]
+    pub logic: Value,
+}
+
+/// Data provided by the verifier to rules under the `external` key, besides the one taken from the certificate.
+#[derive(Clone, Debug)]
+pub struct ExternalParams {
+    /// Reference time for the validation
+    pub validation_clock: DateTime<Utc>,
+
+    /// Value sets referenced by the rules, by identifier
+    pub value_sets: BTreeMap<String, Vec<String>>,
+
+    /// Country where the certificate is being verified
+    pub country_code: String,
+}
+
+/// Outcome of a single rule
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuleOutcome {
+    /// The rule evaluated to `true`
+    Passed,
+
+    /// The rule evaluated to `false`
+    Failed,
+
+    /// The rule is malformed, or did not evaluate to a boolean
+    Error(String),
+}
+
+/// Result of evaluating a [CertLogicRule] against a certificate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleResult {
+    /// Identifier of the rule
+    pub identifier: String,
+
+    /// Outcome of the rule
+    pub outcome: RuleOutcome,
+}
+
+impl HealthCert {
+    /// Evaluates each of `rules` against this certificate.
+    ///
+    /// Rules are run against the CertLogic data object, holding the pass in the format returned by
+    /// [crate::GreenPass::to_dgc_json] under `payload`, and `external` data built from `external` and the certificate
+    /// metadata. A bundle with several passes only passes a rule if all of its passes do.
+    /// Rules are not filtered by country or certificate type, so only the applicable ones should be supplied.
+    pub fn evaluate_rules(
+        &self,
+        rules: &[CertLogicRule],
+        external: &ExternalParams,
+    ) -> Vec<RuleResult> {
+        let external = json!({
+            "validationClock": rfc3339(&external.validation_clock),
+            "valueSets": external.value_sets,
+            "countryCode": external.country_code,
+            "exp": rfc3339(&self.expires),
+            "iat": rfc3339(&self.created),
+            "issuerCountryCode": self.some_issuer,
+            "kid": BASE64.encode(&self.signature.kid),
+        });
+
+        let data: Vec<_> = self
+            .passes
+            .iter()
+            .map(|gp| json!({ "payload": gp.to_dgc_json(), "external": external }))
+            .collect();
+
+        rules
+            .iter()
+            .map(|rule| {
+                let outcome = data
+                    .iter()
+                    .map(|data| match evaluate(&rule.logic, data)? {
+                        Value::Bool(passed) => Ok(passed),
+                        other => Err(format!("rule evaluated to non-boolean {}", other)),
+                    })
+                    .collect::<core::result::Result<Vec<_>, _>>();
+
+                RuleResult {
+                    identifier: rule.identifier.clone(),
+                    outcome: match outcome {
+                        Ok(passed) if passed.iter().all(|&p| p) => RuleOutcome::Passed,
+                        Ok(_) => RuleOutcome::Failed,
+                        Err(e) => RuleOutcome::Error(e),
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+type EvalResult = core::result::Result<Value, String>;
+
+// Evaluates a CertLogic expression against `data`. Dates and times are represented as RFC 3339 strings; dates
+// without a time are taken to be at midnight UTC.
+fn evaluate(expr: &Value, data: &Value) -> EvalResult {
+    match expr {
+        Value::Object(op) => {
+            let mut ops = op.iter();
+
+            match (ops.next(), ops.next()) {
+                (Some((op, args)), None) => apply(op, args, data),
+                _ => Err(format!("expected a single operation, found {}", expr)),
+            }
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| evaluate(item, data))
+            .collect::<core::result::Result<_, _>>()
+            .map(Value::Array),
+        literal => Ok(literal.clone()),
+    }
+}
+
+fn apply(op: &str, args: &Value, data: &Value) -> EvalResult {
+    let args = match args {
+        Value::Array(args) => &args[..],
+        arg => core::slice::from_ref(arg),
+    };
+
+    let eval_all = || -> core::result::Result<Vec<Value>, String> {
+        args.iter().map(|arg| evaluate(arg, data)).collect()
+    };
+
+    match (op, args) {
+        ("var", [path]) => var(path, data),
+        ("if", [guard, then, otherwise]) => {
+            if is_truthy(&evaluate(guard, data)?) {
+                evaluate(then, data)
+            } else {
+                evaluate(otherwise, data)
+            }
+        }
+        ("and", [_, _, ..]) => {
+            let mut last = Value::Null;
+
+            for arg in args {
+                last = evaluate(arg, data)?;
+
+                if !is_truthy(&last) {
+                    break;
+                }
+            }
+
+            Ok(last)
+        }
+        ("!", [arg]) => Ok(Value::Bool(!is_truthy(&evaluate(arg, data)?))),
+        ("===", [_, _]) => {
+            let vals = eval_all()?;
+
+            Ok(Value::Bool(vals[0] == vals[1]))
+        }
+        ("in", [_, _]) => match &eval_all()?[..] {
+            [item, Value::Array(items)] => Ok(Value::Bool(items.contains(item))),
+            _ => Err("the second operand of \"in\" must be an array".into()),
+        },
+        ("+", [_, _]) => match &eval_all()?[..] {
+            [Value::Number(a), Value::Number(b)] => match (a.as_i64(), b.as_i64()) {
+                (Some(a), Some(b)) => Ok(json!(a + b)),
+                _ => Err("\"+\" only supports integers".into()),
+            },
+            _ => Err("\"+\" only supports integers".into()),
+        },
+        ("<" | ">" | "<=" | ">=", [_, _] | [_, _, _]) => {
+            let ints = eval_all()?
+                .iter()
+                .map(|v| {
+                    v.as_i64()
+                        .ok_or_else(|| format!("\"{}\" only supports integers", op))
+                })
+                .collect::<core::result::Result<Vec<_>, _>>()?;
+
+            Ok(Value::Bool(
+                ints.windows(2).all(|w| compare(op, &w[0], &w[1])),
+            ))
+        }
+        ("after" | "before" | "not-after" | "not-before", [_, _] | [_, _, _]) => {
+            let dates = eval_all()?
+                .iter()
+                .map(|v| {
+                    v.as_str()
+                        .and_then(parse_datetime)
+                        .ok_or_else(|| format!("\"{}\" expects date-times, found {}", op, v))
+                })
+                .collect::<core::result::Result<Vec<_>, _>>()?;
+
+            let op = match op {
+                "after" => ">",
+                "before" => "<",
+                "not-after" => "<=",
+                _ => ">=",
+            };
+
+            Ok(Value::Bool(
+                dates.windows(2).all(|w| compare(op, &w[0], &w[1])),
+            ))
+        }
+        ("plusTime", [_, _, _]) => plus_time(&eval_all()?),
+        ("reduce", [operand, lambda, initial]) => {
+            let mut acc = evaluate(initial, data)?;
+
+            match evaluate(operand, data)? {
+                Value::Array(items) => {
+                    for current in items {
+                        acc = evaluate(lambda, &json!({ "current": current, "accumulator": acc }))?;
+                    }
+
+                    Ok(acc)
+                }
+                Value::Null => Ok(acc),
+                _ => Err("the operand of \"reduce\" must be an array or null".into()),
+            }
+        }
+        ("extractFromUVCI", [_, _]) => match &eval_all()?[..] {
+            [Value::Null, _] => Ok(Value::Null),
+            [Value::String(uvci), Value::Number(index)] => {
+                let uvci = uvci.strip_prefix("URN:UVCI:").unwrap_or(uvci);
+
+                Ok(index
+                    .as_u64()
+                    .and_then(|i| uvci.split(['/', '#', ':']).nth(i as usize))
+                    .map_or(Value::Null, |fragment| json!(fragment)))
+            }
+            _ => Err("\"extractFromUVCI\" expects a UVCI and an index".into()),
+        },
+        ("dccDateOfBirth", [_]) => match &eval_all()?[..] {
+            [Value::String(dob)] => date_of_birth(dob)
+                .map(|date| json!(date.format("%F").to_string()))
+                .ok_or_else(|| format!("malformed date of birth {}", dob)),
+            _ => Err("\"dccDateOfBirth\" expects a string".into()),
+        },
+        _ => Err(format!(
+            "unsupported operation \"{}\" with {} operands",
+            op,
+            args.len()
+        )),
+    }
+}
+
+// Looks up a dot-separated path into `data`, i.e. `payload.v.0.dt`. Missing values are null.
+fn var(path: &Value, data: &Value) -> EvalResult {
+    let path = match path {
+        Value::String(path) => path.to_owned(),
+        Value::Number(n) => n.to_string(),
+        _ => return Err(format!("invalid path {}", path)),
+    };
+
+    if path.is_empty() {
+        return Ok(data.clone());
+    }
+
+    let mut value = data;
+
+    for fragment in path.split('.') {
+        let next = match value {
+            Value::Object(m) => m.get(fragment),
+            Value::Array(a) => fragment.parse::<usize>().ok().and_then(|i| a.get(i)),
+            _ => None,
+        };
+
+        match next {
+            Some(next) => value = next,
+            None => return Ok(Value::Null),
+        }
+    }
+
+    Ok(value.clone())
+}
+
+fn is_truthy(v: &Value) -> bool {
+    match v {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(m) => !m.is_empty(),
+    }
+}
+
+fn compare<T: PartialOrd>(op: &str, a: &T, b: &T) -> bool {
+    match op {
+        "<" => a < b,
+        ">" => a > b,
+        "<=" => a <= b,
+        _ => a >= b,
+    }
+}
+
+fn rfc3339<Tz: TimeZone>(dt: &DateTime<Tz>) -> String
+where
+    Tz::Offset: core::fmt::Display,
+{
+    dt.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+fn parse_datetime(s: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .or_else(|| DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f%z").ok())
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(s, "%F").ok()?;
+
+            Some(Utc.from_utc_datetime(&date.and_hms(0, 0, 0)).into())
+        })
+}
+
+fn plus_time(args: &[Value]) -> EvalResult {
+    let (dt, amount, unit) = match args {
+        [Value::String(dt), amount, Value::String(unit)] => (
+            parse_datetime(dt).ok_or_else(|| format!("malformed date-time {}", dt))?,
+            amount
+                .as_i64()
+                .ok_or("the amount of \"plusTime\" must be an integer")?,
+            unit,
+        ),
+        _ => return Err("\"plusTime\" expects a date-time, an amount and a unit".into()),
+    };
+
+    let shifted = match unit.as_str() {
+        "hour" => dt.checked_add_signed(Duration::hours(amount)),
+        "day" => dt.checked_add_signed(Duration::days(amount)),
+        "month" => add_months(dt, amount),
+        "year" => amount
+            .checked_mul(12)
+            .and_then(|months| add_months(dt, months)),
+        _ => return Err(format!("unsupported time unit {}", unit)),
+    };
+
+    shifted
+        .map(|dt| json!(rfc3339(&dt)))
+        .ok_or_else(|| "date-time out of range".into())
+}
+
+// Adds calendar months, clamping the day to the length of the resulting month
+fn add_months(dt: DateTime<FixedOffset>, months: i64) -> Option<DateTime<FixedOffset>> {
+    let total = i64::from(dt.year()) * 12 + i64::from(dt.month0()) + months;
+
+    let year = i32::try_from(total.div_euclid(12)).ok()?;
+    let month = total.rem_euclid(12) as u32 + 1;
+
+    let day = dt.day().min(days_in_month(year, month)?);
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+
+    dt.timezone()
+        .from_local_datetime(&date.and_time(dt.time()))
+        .single()
+}
+
+fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+
+    let first_of_next = NaiveDate::from_ymd_opt(next_year, next_month, 1)?;
+
+    Some(first_of_next.pred().day())
+}
+
+// Partial dates of birth are taken to be at the end of the period they denote
+fn date_of_birth(dob: &str) -> Option<NaiveDate> {
+    let mut parts = dob.split('-');
+
+    let year = parts.next()?.parse().ok()?;
+
+    match (parts.next(), parts.next(), parts.next()) {
+        (None, _, _) => NaiveDate::from_ymd_opt(year, 12, 31),
+        (Some(month), None, _) => {
+            let month = month.parse().ok()?;
+
+            NaiveDate::from_ymd_opt(year, month, days_in_month(year, month)?)
+        }
+        (Some(_), Some(_), None) => NaiveDate::parse_from_str(dob, "%F").ok(),
+        _ => None,
+    }
+}
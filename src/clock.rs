@@ -0,0 +1,57 @@
+// Sources of the current time for validity checks. The crate never reads the system clock by itself: callers pick a
+// clock, which can be the system one, a fixed time for tests, or a trusted time source.
+
+use chrono::prelude::*;
+
+use crate::{HealthCert, ValidityStatus, ValidityWindow};
+
+/// A source of the current time
+pub trait Clock {
+    /// Returns the current time
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Reads the time from the system clock
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        let elapsed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("the system clock is set before 1970");
+
+        Utc.timestamp(elapsed.as_secs() as i64, elapsed.subsec_nanos())
+    }
+}
+
+/// Always returns the same time, i.e. for tests or to check certificates at a given point in time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+impl HealthCert {
+    /// Same as [HealthCert::is_expired], at the time returned by `clock`.
+    pub fn is_expired_with(&self, clock: &dyn Clock) -> bool {
+        self.is_expired(clock.now())
+    }
+
+    /// Same as [HealthCert::validity_status], at the time returned by `clock`.
+    pub fn validity_status_with(&self, clock: &dyn Clock) -> ValidityStatus {
+        self.validity_status(clock.now())
+    }
+}
+
+impl ValidityWindow {
+    /// Returns true if the time returned by `clock` falls within the window
+    pub fn is_current(&self, clock: &dyn Clock) -> bool {
+        self.contains(clock.now())
+    }
+}
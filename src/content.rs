@@ -0,0 +1,51 @@
+// Hashing of the logical contents of a certificate, used to recognise repeated scans of the same certificate.
+
+use alloc::vec::Vec;
+
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{Country, GreenPass, HealthCert};
+
+// Everything in a HealthCert except for the signature and the raw COSE data
+#[derive(Serialize)]
+struct Content<'a> {
+    some_issuer: &'a Option<Country>,
+    created: &'a DateTime<Utc>,
+    expires: &'a DateTime<Utc>,
+    not_before: &'a Option<DateTime<Utc>>,
+    #[serde(serialize_with = "crate::serialize_opt_hex")]
+    cwt_id: &'a Option<Vec<u8>>,
+    passes: &'a [GreenPass],
+}
+
+impl HealthCert {
+    /// Computes a SHA-256 hash of the contents of the certificate, excluding its signature.
+    ///
+    /// The hash is computed over the compact JSON serialization of the issuer, timestamps, CWT ID and passes, so it
+    /// does not depend on how the issuer encoded the CBOR payload (i.e. the order of map keys or integer widths):
+    ///
+    /// - fields appear in the order in which they are declared in the model, and absent optional fields are `null`;
+    /// - timestamps are RFC 3339 strings in UTC;
+    /// - entry dates keep their original representation (see [crate::Dated]), while strings are hashed as decoded,
+    ///   without any normalization.
+    ///
+    /// Two scans of the same certificate always produce the same hash, even if one of them has been re-signed.
+    /// Hashes are only guaranteed to be stable between releases that do not change the certificate model.
+    pub fn content_hash(&self) -> [u8; 32] {
+        let content = Content {
+            some_issuer: &self.some_issuer,
+            created: &self.created,
+            expires: &self.expires,
+            not_before: &self.not_before,
+            cwt_id: &self.cwt_id,
+            passes: &self.passes,
+        };
+
+        // serializing the model can't fail, as it contains no maps with non-string keys
+        let json = serde_json::to_vec(&content).expect("failed to serialize certificate");
+
+        Sha256::digest(json).into()
+    }
+}
@@ -0,0 +1,152 @@
+// Revocation checks of Document Signer Certificates (DSCs) against the CRLs published by their CSCA, only available
+// with the `revocation-http` feature. Unlike the lists in revocation.rs, which revoke single health certificates, a
+// revoked DSC invalidates every certificate it has signed.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use reqwest::Client;
+use x509_cert::{
+    crl::CertificateList,
+    der::{Decode, Encode},
+    ext::pkix::{
+        name::{DistributionPointName, GeneralName},
+        CrlDistributionPoints,
+    },
+    Certificate,
+};
+
+use crate::{Error, Result, VerificationKey};
+
+/// CRLs downloaded from the distribution points of DSCs, each kept in memory until its `nextUpdate`.
+///
+/// A single cache can check DSCs from any number of CSCAs, as CRLs are cached by URL.
+#[derive(Debug, Default)]
+pub struct CrlCache {
+    client: Client,
+    crls: Mutex<BTreeMap<String, Arc<CertificateList>>>,
+}
+
+impl CrlCache {
+    /// Creates an empty cache. Nothing is downloaded until a DSC is checked.
+    pub fn new() -> Self {
+        CrlCache::default()
+    }
+
+    /// Returns whether `dsc`, a DER-encoded DSC, has been revoked by the CSCA holding the `csca` key.
+    ///
+    /// The CRL is downloaded from the first HTTP(S) URL among the CRL distribution points of the DSC, unless a copy
+    /// is cached and its `nextUpdate` has not passed yet; CRLs without a `nextUpdate` are downloaded at every check.
+    /// The CRL must name the issuer of the DSC and be signed by `csca`, otherwise the check fails with
+    /// [Error::InvalidCrl], as does a DSC not signed by `csca` with [Error::UntrustedChain].
+    pub async fn is_dsc_revoked(&self, dsc: &[u8], csca: &VerificationKey) -> Result<bool> {
+        let cert =
+            Certificate::from_der(dsc).map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+
+        if !csca.verifies_certificate(&cert) {
+            return Err(Error::UntrustedChain(
+                "DSC is not signed by the CSCA".into(),
+            ));
+        }
+
+        let url = distribution_point(&cert).ok_or_else(|| {
+            Error::InvalidCertificate("DSC has no HTTP CRL distribution point".into())
+        })?;
+
+        let crl = self.get(&url).await?;
+        let tbs = &crl.tbs_cert_list;
+
+        if tbs.issuer != cert.tbs_certificate.issuer {
+            return Err(Error::InvalidCrl("CRL has a different issuer".into()));
+        }
+
+        let tbs_der = tbs.to_der().map_err(|e| Error::InvalidCrl(e.to_string()))?;
+        let signature = crl
+            .signature
+            .as_bytes()
+            .ok_or_else(|| Error::InvalidCrl("signature is not byte aligned".into()))?;
+
+        if !csca.verifies_x509(crl.signature_algorithm.oid, &tbs_der, signature) {
+            return Err(Error::InvalidCrl("CRL is not signed by the CSCA".into()));
+        }
+
+        let serial = &cert.tbs_certificate.serial_number;
+
+        Ok(tbs
+            .revoked_certificates
+            .iter()
+            .flatten()
+            .any(|revoked| &revoked.serial_number == serial))
+    }
+
+    // Returns the CRL at `url`, downloading it if it is not cached or its next update is due
+    async fn get(&self, url: &str) -> Result<Arc<CertificateList>> {
+        if let Some(crl) = self.crls.lock().unwrap().get(url) {
+            if is_current(crl) {
+                return Ok(crl.clone());
+            }
+        }
+
+        let body = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        // CRLs are usually served as DER, but some CSCAs publish them as PEM
+        let der = match pem::parse(&body) {
+            Ok(pem) if pem.tag() == "X509 CRL" => pem.into_contents(),
+            _ => body.to_vec(),
+        };
+
+        let crl = Arc::new(
+            CertificateList::from_der(&der).map_err(|e| Error::InvalidCrl(e.to_string()))?,
+        );
+
+        let mut crls = self.crls.lock().unwrap();
+
+        if crl.tbs_cert_list.next_update.is_some() {
+            crls.insert(url.into(), crl.clone());
+        } else {
+            crls.remove(url);
+        }
+
+        Ok(crl)
+    }
+}
+
+fn is_current(crl: &CertificateList) -> bool {
+    crl.tbs_cert_list
+        .next_update
+        .is_some_and(|next| SystemTime::now() < next.to_system_time())
+}
+
+// Returns the first HTTP(S) URL among the CRL distribution points of `cert`
+fn distribution_point(cert: &Certificate) -> Option<String> {
+    let (_, CrlDistributionPoints(points)) =
+        cert.tbs_certificate.get::<CrlDistributionPoints>().ok()??;
+
+    points
+        .into_iter()
+        .filter_map(|point| match point.distribution_point {
+            Some(DistributionPointName::FullName(names)) => Some(names),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|name| match name {
+            GeneralName::UniformResourceIdentifier(uri) => {
+                let uri = uri.to_string();
+                let scheme = uri.split(':').next().unwrap_or_default();
+
+                (scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https"))
+                    .then_some(uri)
+            }
+            _ => None,
+        })
+}
@@ -0,0 +1,84 @@
+// Dates keeping the exact string they were parsed from, so that certificates can be audited or re-encoded without
+// losing how the issuer wrote them.
+
+use alloc::string::{String, ToString};
+use core::{fmt, ops::Deref};
+
+use chrono::NaiveDate;
+use serde::{Serialize, Serializer};
+
+use crate::{Error, Result};
+
+/// A date found in a certificate, together with its original representation.
+///
+/// Dates are parsed leniently, so a value such as `2021-2-18` is accepted even though its canonical form is
+/// `2021-02-18`. Comparisons with [NaiveDate] only consider the parsed date, while serialization always emits `raw`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dated {
+    /// The date exactly as it appears in the certificate
+    pub raw: String,
+
+    /// The parsed date
+    pub parsed: NaiveDate,
+}
+
+impl Dated {
+    /// Parses an ISO 8601 date, keeping the original string
+    pub fn parse(raw: impl Into<String>) -> Result<Self> {
+        let raw = raw.into();
+
+        match NaiveDate::parse_from_str(&raw, "%F") {
+            Ok(parsed) => Ok(Dated { raw, parsed }),
+            Err(e) => Err(Error::MalformedDate {
+                value: raw,
+                cause: Some(e),
+            }),
+        }
+    }
+
+    /// Returns true if `raw` is the canonical `YYYY-MM-DD` form of the date
+    pub fn is_canonical(&self) -> bool {
+        self.raw == self.parsed.format("%F").to_string()
+    }
+}
+
+impl From<NaiveDate> for Dated {
+    fn from(parsed: NaiveDate) -> Self {
+        Dated {
+            raw: parsed.format("%F").to_string(),
+            parsed,
+        }
+    }
+}
+
+impl From<Dated> for NaiveDate {
+    fn from(date: Dated) -> Self {
+        date.parsed
+    }
+}
+
+impl Deref for Dated {
+    type Target = NaiveDate;
+
+    fn deref(&self) -> &NaiveDate {
+        &self.parsed
+    }
+}
+
+impl PartialEq<NaiveDate> for Dated {
+    fn eq(&self, other: &NaiveDate) -> bool {
+        self.parsed == *other
+    }
+}
+
+impl fmt::Display for Dated {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.parsed.fmt(f)
+    }
+}
+
+impl Serialize for Dated {
+    fn serialize<S: Serializer>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
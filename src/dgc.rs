@@ -0,0 +1,129 @@
+// Conversion of passes back to the JSON structure defined by the EU DGC schema, as consumed by CertLogic.
+// See https://github.com/ehn-dcc-development/ehn-dcc-schema
+
+use alloc::vec::Vec;
+
+use chrono::SecondsFormat;
+use serde_json::{json, Map, Value};
+
+use crate::{CertInfo, Exemption, GreenPass, Recovery, Test, TestName, Vaccine};
+
+impl GreenPass {
+    /// Converts this pass into the JSON structure defined by the EU DGC schema, using the short field names of the
+    /// specification (`dob`, `nam`, `v`, ...). This is the input expected by CertLogic rule engines, and differs from
+    /// the [serde::Serialize] implementation.
+    ///
+    /// Certificates light have no representation in the schema, so only their holder data is converted; unknown entries
+    /// are left out.
+    pub fn to_dgc_json(&self) -> Value {
+        let mut dgc = Map::new();
+
+        dgc.insert("ver".into(), json!(self.ver));
+
+        let mut nam = Map::new();
+
+        for (key, name) in [
+            ("fn", &self.surname),
+            ("gn", &self.givenname),
+            ("gnt", &self.std_givenname),
+        ] {
+            if let Some(name) = name {
+                nam.insert(key.into(), json!(name));
+            }
+        }
+
+        nam.insert("fnt".into(), json!(self.std_surname));
+
+        dgc.insert("nam".into(), Value::Object(nam));
+        dgc.insert("dob".into(), json!(self.date_of_birth));
+
+        for ci in &self.entries {
+            let (key, entry) = match ci {
+                CertInfo::Exemption(e) => ("e", exemption_json(e)),
+                CertInfo::Light(_) | CertInfo::Unknown { .. } => continue,
+                CertInfo::Recovery(r) => ("r", recovery_json(r)),
+                CertInfo::Test(t) => ("t", test_json(t)),
+                CertInfo::Vaccine(v) => ("v", vaccine_json(v)),
+            };
+
+            if let Value::Array(entries) =
+                dgc.entry(key).or_insert_with(|| Value::Array(Vec::new()))
+            {
+                entries.push(entry);
+            }
+        }
+
+        Value::Object(dgc)
+    }
+}
+
+fn exemption_json(e: &Exemption) -> Value {
+    let mut entry = json!({
+        "tg": e.disease,
+        "co": e.country,
+        "is": e.issuer,
+        "df": e.valid_from,
+        "du": e.valid_until,
+        "ci": e.cert_id,
+    });
+
+    if let Some(reason) = &e.reason {
+        entry["rs"] = json!(reason);
+    }
+
+    entry
+}
+
+fn recovery_json(r: &Recovery) -> Value {
+    json!({
+        "tg": r.disease,
+        "fr": r.diagnosed,
+        "co": r.country,
+        "is": r.issuer,
+        "df": r.valid_from,
+        "du": r.valid_until,
+        "ci": r.cert_id,
+    })
+}
+
+fn test_json(t: &Test) -> Value {
+    let mut entry = json!({
+        "tg": t.disease,
+        "tt": t.test_type,
+        "sc": t.collect_ts.to_rfc3339_opts(SecondsFormat::AutoSi, true),
+        "tr": t.result,
+        "co": t.country,
+        "is": t.issuer,
+        "ci": t.cert_id,
+    });
+
+    match &t.name {
+        TestName::NAAT { name } => entry["nm"] = json!(name),
+        TestName::RAT { device_id } => entry["ma"] = json!(device_id),
+    }
+
+    if let Some(tc) = &t.testing_centre {
+        entry["tc"] = json!(tc);
+    }
+
+    if let Some(dr) = &t.result_ts {
+        entry["dr"] = json!(dr.to_rfc3339_opts(SecondsFormat::AutoSi, true));
+    }
+
+    entry
+}
+
+fn vaccine_json(v: &Vaccine) -> Value {
+    json!({
+        "tg": v.disease,
+        "vp": v.prophylaxis_kind,
+        "mp": v.product,
+        "ma": v.market_auth,
+        "dn": v.dose_number,
+        "sd": v.dose_total,
+        "dt": v.date,
+        "co": v.country,
+        "is": v.issuer,
+        "ci": v.cert_id,
+    })
+}
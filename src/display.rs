@@ -0,0 +1,436 @@
+// Human readable formatting for certificates.
+// The width of the formatter (i.e. `{:4}`) is used as the indentation level of the block being written, and blocks
+// never end with a newline so they can be freely composed.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt::{self, Write};
+
+use chrono::Duration;
+
+use crate::{
+    CertInfo, Exemption, GreenPass, HealthCert, Light, Manufacturer, Recovery, Test, TestName,
+    TestResult, TestType, Vaccine, VaccineMedicinalProduct, ValidityPolicy,
+};
+
+const INDENT: usize = 4;
+
+// Joins the given name and the surname, skipping whichever is missing
+fn full_name(givenname: Option<&str>, surname: Option<&str>) -> String {
+    let parts: Vec<_> = [givenname, surname].into_iter().flatten().collect();
+
+    parts.join(" ")
+}
+
+macro_rules! indented {
+    ($f:expr, $pad:expr, $($arg:tt)*) => ({
+        write!($f, "{:pad$}", "", pad = $pad)?;
+        writeln!($f, $($arg)*)
+    })
+}
+
+macro_rules! indented_last {
+    ($f:expr, $pad:expr, $($arg:tt)*) => ({
+        write!($f, "{:pad$}", "", pad = $pad)?;
+        write!($f, $($arg)*)
+    })
+}
+
+impl fmt::Display for Exemption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Exemption {
+            cert_id,
+            country,
+            disease,
+            issuer,
+            reason,
+            valid_from,
+            valid_until,
+        } = self;
+
+        let pad = f.width().unwrap_or(0);
+        let inner = pad + INDENT;
+
+        indented!(f, pad, "Vaccination exemption:")?;
+        indented!(f, inner, "Cert ID: {}", cert_id)?;
+        indented!(f, inner, "Disease: {}", disease)?;
+
+        if let Some(reason) = reason {
+            indented!(f, inner, "Reason: {}", reason)?;
+        }
+
+        indented!(f, inner, "Issuer: {}", issuer)?;
+        indented!(f, inner, "Country: {}", country)?;
+        indented!(f, inner, "Valid from: {}", valid_from)?;
+        indented_last!(f, inner, "Valid until: {}", valid_until)
+    }
+}
+
+impl fmt::Display for Light {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Light {
+            valid_from,
+            valid_until,
+        } = self;
+
+        let pad = f.width().unwrap_or(0);
+        let inner = pad + INDENT;
+
+        indented!(f, pad, "Certificate light:")?;
+        indented!(f, inner, "Valid from: {}", valid_from)?;
+        indented_last!(f, inner, "Valid until: {}", valid_until)
+    }
+}
+
+impl fmt::Display for Recovery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Recovery {
+            cert_id,
+            country,
+            diagnosed,
+            disease,
+            issuer,
+            valid_from,
+            valid_until,
+        } = self;
+
+        let pad = f.width().unwrap_or(0);
+        let inner = pad + INDENT;
+
+        indented!(f, pad, "Recovery attestation:")?;
+        indented!(f, inner, "Cert ID: {}", cert_id)?;
+        indented!(f, inner, "Disease: {}", disease)?;
+        indented!(f, inner, "Issuer: {}", issuer)?;
+        indented!(f, inner, "Country: {}", country)?;
+        indented!(f, inner, "Tested positive: {}", diagnosed)?;
+        indented!(f, inner, "Valid from: {}", valid_from)?;
+        indented_last!(f, inner, "Valid until: {}", valid_until)
+    }
+}
+
+impl fmt::Display for TestName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestName::NAAT { name } => write!(f, "Nucleic Acid Amplification Test ({})", name),
+            TestName::RAT { device_id } => write!(f, "Rapid Antigen Test (device: {})", device_id),
+        }
+    }
+}
+
+impl fmt::Display for TestResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestResult::Detected => write!(f, "Positive"),
+            TestResult::NotDetected => write!(f, "Negative"),
+            TestResult::Other(code) => write!(f, "Unknown (code {})", code),
+        }
+    }
+}
+
+impl fmt::Display for Test {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Test {
+            cert_id,
+            collect_ts,
+            country,
+            disease,
+            issuer,
+            name,
+            result,
+            test_type,
+            testing_centre,
+            result_ts,
+        } = self;
+
+        let pad = f.width().unwrap_or(0);
+        let inner = pad + INDENT;
+
+        indented!(f, pad, "Testing attestation:")?;
+        indented!(f, inner, "Cert ID: {}", cert_id)?;
+        indented!(f, inner, "Disease: {}", disease)?;
+        indented!(f, inner, "Result: {}", TestResult::from_code(result))?;
+
+        // the UTC time is only worth repeating if the certificate uses another offset
+        if collect_ts.offset().local_minus_utc() == 0 {
+            indented!(f, inner, "Samples collected at: {}", collect_ts)?;
+        } else {
+            indented!(
+                f,
+                inner,
+                "Samples collected at: {} ({})",
+                collect_ts,
+                self.collect_ts_utc()
+            )?;
+        }
+
+        if let Some(result_ts) = result_ts {
+            indented!(f, inner, "Result produced at: {}", result_ts)?;
+        }
+
+        indented!(f, inner, "Test type: {}, ID: {}", name, test_type)?;
+
+        if let Some(testing_centre) = testing_centre {
+            indented!(f, inner, "Conducted by: {}", testing_centre)?;
+        }
+
+        indented!(f, inner, "Issuer: {}", issuer)?;
+        indented_last!(f, inner, "Country: {}", country)
+    }
+}
+
+impl fmt::Display for Vaccine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Vaccine {
+            cert_id,
+            country,
+            date,
+            disease,
+            dose_number,
+            dose_total,
+            issuer,
+            market_auth,
+            product,
+            prophylaxis_kind,
+            extra,
+        } = self;
+
+        let pad = f.width().unwrap_or(0);
+        let inner = pad + INDENT;
+
+        indented!(f, pad, "Vaccination data:")?;
+        indented!(f, inner, "Cert ID: {}", cert_id)?;
+        indented!(f, inner, "Disease: {}", disease)?;
+        indented!(f, inner, "Issuer: {}", issuer)?;
+        indented!(f, inner, "Country: {}", country)?;
+        indented!(f, inner, "Vaccination date: {}", date)?;
+        let doses = match self.dose_info() {
+            Ok(info) if info.is_booster => " (booster)",
+            Ok(info) if info.is_complete => " (complete)",
+            _ => "",
+        };
+
+        indented!(
+            f,
+            inner,
+            "Doses administered: {}/{}{}",
+            dose_number,
+            dose_total,
+            doses
+        )?;
+
+        match VaccineMedicinalProduct::from_code(product) {
+            Some(known) => indented!(f, inner, "Product ID: {} ({})", product, known.values().1)?,
+            None => indented!(f, inner, "Product ID: {}", product)?,
+        }
+
+        match Manufacturer::from_code(market_auth) {
+            Some(known) => indented!(
+                f,
+                inner,
+                "Market Authorization ID: {} ({})",
+                market_auth,
+                known.values().1
+            )?,
+            None => indented!(f, inner, "Market Authorization ID: {}", market_auth)?,
+        }
+
+        indented_last!(f, inner, "Vaccine/Prophylaxis ID: {}", prophylaxis_kind)?;
+
+        for (key, value) in extra {
+            writeln!(f)?;
+            indented_last!(f, inner, "Extra key {}: {}", key, hex::encode(value))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for CertInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pad = f.width().unwrap_or(0);
+
+        match self {
+            CertInfo::Exemption(e) => write!(f, "{:pad$}", e, pad = pad),
+            CertInfo::Light(l) => write!(f, "{:pad$}", l, pad = pad),
+            CertInfo::Recovery(r) => write!(f, "{:pad$}", r, pad = pad),
+            CertInfo::Test(t) => write!(f, "{:pad$}", t, pad = pad),
+            CertInfo::Unknown { key, value } => {
+                indented!(f, pad, "Unknown entry `{}`:", key)?;
+                indented_last!(f, pad + INDENT, "Data: {}", hex::encode(value))
+            }
+            CertInfo::Vaccine(v) => write!(f, "{:pad$}", v, pad = pad),
+        }
+    }
+}
+
+impl fmt::Display for GreenPass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let GreenPass {
+            date_of_birth,
+            surname,
+            givenname,
+            std_surname,
+            std_givenname,
+            ver,
+            entries,
+        } = self;
+
+        let pad = f.width().unwrap_or(0);
+
+        indented!(f, pad, "Cert version {}", ver)?;
+
+        let name = full_name(givenname.as_deref(), surname.as_deref());
+
+        if !name.is_empty() {
+            indented!(f, pad, "Emitted to: {}", name)?;
+        }
+
+        indented!(
+            f,
+            pad,
+            "Standardized Name: {}",
+            full_name(std_givenname.as_deref(), Some(std_surname))
+        )?;
+        indented_last!(f, pad, "Date of birth: {}\n", date_of_birth)?;
+
+        for ci in entries {
+            write!(f, "\n{:pad$}", ci, pad = pad)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for HealthCert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let HealthCert {
+            created,
+            expires,
+            not_before,
+            passes,
+            some_issuer,
+            signature,
+            ..
+        } = self;
+
+        writeln!(f, "EU Digital COVID Certificate\n")?;
+
+        if let Some(issuer) = some_issuer {
+            writeln!(f, "Issued by: {}", issuer)?;
+        }
+
+        writeln!(f, "Created at: {}", created)?;
+        writeln!(f, "Expires at: {}", expires)?;
+
+        if let Some(nbf) = not_before {
+            writeln!(f, "Not valid before: {}", nbf)?;
+        }
+
+        writeln!(f)?;
+
+        writeln!(f, "Signature algorithm: {}", signature.algorithm)?;
+        writeln!(f, "Signature KID: {}", hex::encode(&signature.kid))?;
+        writeln!(
+            f,
+            "Signature data: ({} bytes) {}",
+            signature.signature.len(),
+            hex::encode(&signature.signature)
+        )?;
+
+        if let Some(cs) = &signature.counter_signature {
+            writeln!(
+                f,
+                "Countersigned by KID {} (algorithm {})",
+                hex::encode(&cs.kid),
+                cs.algorithm
+            )?;
+        }
+
+        writeln!(f)?;
+
+        for (i, pass) in passes.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "Pass#{}:\n{:pad$}", i, pass, pad = INDENT)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GreenPass {
+    /// Returns a one-line description of the [primary entry](GreenPass::primary_entry) of this pass, for logs and
+    /// list views, i.e. `Vaccine 2/2 Comirnaty (AT) exp 2022-07-02`.
+    ///
+    /// Known products and diseases are written by name, and the validity is computed with [ValidityPolicy::default]:
+    /// the line ends with the last day the entry is valid, `no exp` if it never expires or `not valid` if it is never
+    /// valid. Passes holding no dated entries are described as `No entries`.
+    pub fn summary_line(&self) -> String {
+        let mut line = String::new();
+
+        // writing to a String cannot fail
+        let _ = self.write_summary_line(&mut line);
+
+        line
+    }
+
+    fn write_summary_line(&self, line: &mut String) -> fmt::Result {
+        let ci = match self.primary_entry() {
+            Some(ci) => ci,
+            None => return write!(line, "No entries"),
+        };
+
+        match ci {
+            CertInfo::Exemption(e) => write!(line, "Exemption ({})", e.country)?,
+            CertInfo::Recovery(r) => write!(
+                line,
+                "Recovery from {} ({})",
+                r.target_disease().name(),
+                r.country
+            )?,
+            CertInfo::Test(t) => {
+                let kind = match t.test_type_kind() {
+                    TestType::NAAT => "NAAT",
+                    TestType::RAT => "RAT",
+                    TestType::Unknown(_) => "unknown type",
+                };
+
+                write!(
+                    line,
+                    "Test {} {} ({})",
+                    kind,
+                    TestResult::from_code(&t.result),
+                    t.country
+                )?
+            }
+            CertInfo::Vaccine(v) => {
+                write!(line, "Vaccine {}/{} ", v.dose_number, v.dose_total)?;
+
+                match VaccineMedicinalProduct::from_code(&v.product) {
+                    Some(known) => line.push_str(known.values().1),
+                    None => line.push_str(&v.product),
+                }
+
+                write!(line, " ({})", v.country)?
+            }
+            // never primary entries
+            CertInfo::Light(_) | CertInfo::Unknown { .. } => {}
+        }
+
+        match ci.validity_window(&ValidityPolicy::default()) {
+            // the end of the window is excluded, so the last valid day is the one before it ends
+            Some(window) => match window.until {
+                Some(until) => {
+                    let last = until
+                        .checked_sub_signed(Duration::seconds(1))
+                        .unwrap_or(until);
+
+                    write!(line, " exp {}", last.format("%F"))
+                }
+                None => write!(line, " no exp"),
+            },
+            None => write!(line, " not valid"),
+        }
+    }
+}
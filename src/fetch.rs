@@ -0,0 +1,99 @@
+// Download of trust lists over HTTPS, only available with the `trustlist-http` feature.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use reqwest::{header, Client, StatusCode};
+
+use crate::{Result, TrustList};
+
+impl TrustList {
+    /// Downloads a trust list from `url`. The response must be a JSON object in the format accepted by
+    /// [TrustList::from_json].
+    pub async fn fetch(url: &str) -> Result<Self> {
+        let body = reqwest::get(url).await?.error_for_status()?.text().await?;
+
+        TrustList::from_json(&body)
+    }
+}
+
+/// Trust list downloaded from a URL and kept in memory until its time to live elapses.
+///
+/// The list is refreshed when requested after the TTL has passed. If the server sent an ETag, the refresh is a
+/// conditional request and an unchanged list is not downloaded again.
+#[derive(Debug)]
+pub struct TrustListCache {
+    client: Client,
+    url: String,
+    ttl: Duration,
+    cached: Mutex<Option<Cached>>,
+}
+
+#[derive(Debug)]
+struct Cached {
+    trust: Arc<TrustList>,
+    etag: Option<header::HeaderValue>,
+    fetched: Instant,
+}
+
+impl TrustListCache {
+    /// Creates an empty cache for the trust list at `url`. Nothing is downloaded until [TrustListCache::get] is called.
+    pub fn new(url: impl Into<String>, ttl: Duration) -> Self {
+        TrustListCache {
+            client: Client::new(),
+            url: url.into(),
+            ttl,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached trust list, refreshing it first if it has never been downloaded or its TTL has elapsed.
+    pub async fn get(&self) -> Result<Arc<TrustList>> {
+        if let Some(cached) = &*self.cached.lock().unwrap() {
+            if cached.fetched.elapsed() < self.ttl {
+                return Ok(cached.trust.clone());
+            }
+        }
+
+        self.refresh().await
+    }
+
+    /// Refreshes the trust list regardless of its age.
+    pub async fn refresh(&self) -> Result<Arc<TrustList>> {
+        let etag = self
+            .cached
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|cached| cached.etag.clone());
+
+        let mut req = self.client.get(&self.url);
+
+        if let Some(etag) = etag {
+            req = req.header(header::IF_NONE_MATCH, etag);
+        }
+
+        let resp = req.send().await?.error_for_status()?;
+
+        if resp.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = &mut *self.cached.lock().unwrap() {
+                cached.fetched = Instant::now();
+
+                return Ok(cached.trust.clone());
+            }
+        }
+
+        let etag = resp.headers().get(header::ETAG).cloned();
+        let trust = Arc::new(TrustList::from_json(&resp.text().await?)?);
+
+        *self.cached.lock().unwrap() = Some(Cached {
+            trust: trust.clone(),
+            etag,
+            fetched: Instant::now(),
+        });
+
+        Ok(trust)
+    }
+}
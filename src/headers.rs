@@ -0,0 +1,113 @@
+// Named access to the COSE header parameters of a certificate (RFC 8152, section 3.1), merging the protected and
+// unprotected buckets.
+
+use alloc::{collections::BTreeMap, string::String};
+
+use ciborium::value::Value;
+
+use crate::{from_cbor, HealthCert, RawHeader};
+
+const ALG: i128 = 1;
+const CRIT: i128 = 2;
+const CONTENT_TYPE: i128 = 3;
+const KID: i128 = 4;
+
+/// Content type of the payload, as stated in a COSE header
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ContentType {
+    /// CoAP Content-Format identifier
+    Format(u16),
+
+    /// Media type, i.e. `application/cwt`
+    Media(String),
+}
+
+/// The COSE header parameters of a certificate, as returned by [HealthCert::headers].
+///
+/// Parameters found in both buckets are taken from the protected one, which is covered by the signature; note that
+/// parsing looks for the KID of [Signature](crate::Signature) in the unprotected bucket first, as some issuers only
+/// put it there. Parameters with text labels, which certificates do not use, are skipped.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CoseHeaders {
+    // the parameters with a getter, as found in the header
+    known: BTreeMap<i128, Value>,
+
+    /// Parameters without a getter, by label
+    pub rest: BTreeMap<i128, Value>,
+}
+
+impl CoseHeaders {
+    fn from_params(params: impl IntoIterator<Item = (i128, Value)>) -> Self {
+        let mut headers = CoseHeaders::default();
+
+        for (label, value) in params {
+            match label {
+                ALG | CRIT | CONTENT_TYPE | KID => headers.known.insert(label, value),
+                _ => headers.rest.insert(label, value),
+            };
+        }
+
+        headers
+    }
+
+    /// Signature algorithm (label 1), as a COSE identifier; see [CoseAlgorithm::from_id](crate::CoseAlgorithm::from_id)
+    pub fn alg(&self) -> Option<i128> {
+        match self.known.get(&ALG) {
+            Some(Value::Integer(alg)) => Some((*alg).into()),
+            _ => None,
+        }
+    }
+
+    /// Labels of the parameters the recipient must understand (label 2)
+    pub fn crit(&self) -> Option<&[Value]> {
+        match self.known.get(&CRIT) {
+            Some(Value::Array(crit)) => Some(crit),
+            _ => None,
+        }
+    }
+
+    /// Content type of the payload (label 3)
+    pub fn content_type(&self) -> Option<ContentType> {
+        match self.known.get(&CONTENT_TYPE) {
+            Some(Value::Integer(id)) => {
+                u16::try_from(i128::from(*id)).ok().map(ContentType::Format)
+            }
+            Some(Value::Text(media)) => Some(ContentType::Media(media.clone())),
+            _ => None,
+        }
+    }
+
+    /// Key ID (label 4)
+    pub fn kid(&self) -> Option<&[u8]> {
+        match self.known.get(&KID) {
+            Some(Value::Bytes(kid)) => Some(kid),
+            _ => None,
+        }
+    }
+}
+
+impl HealthCert {
+    /// Returns the header parameters of the COSE structure this certificate has been decoded from.
+    ///
+    /// Certificates that have not been obtained through parsing have no headers.
+    pub fn headers(&self) -> CoseHeaders {
+        let protected = if self.raw.protected.is_empty() {
+            BTreeMap::new()
+        } else {
+            // the protected header has already been decoded once while parsing
+            from_cbor(&self.raw.protected)
+                .map(|RawHeader(header)| header)
+                .unwrap_or_default()
+        };
+
+        let unprotected = self.raw.unprotected.iter().filter_map(|(k, v)| match k {
+            Value::Integer(label) => Some((i128::from(*label), v.clone())),
+            _ => None,
+        });
+
+        // protected parameters come last, so that they replace the unprotected ones
+        CoseHeaders::from_params(
+            unprotected.chain(protected.into_iter().map(|(label, v)| (label as i128, v))),
+        )
+    }
+}
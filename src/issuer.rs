@@ -0,0 +1,51 @@
+// Best-effort matching of the free-form issuer (`is`) of entries against the country they declare, for anomaly
+// detection. Issuers name themselves however they like, so only a few well-known authorities are recognized.
+
+use alloc::string::String;
+
+use crate::{Country, Recovery};
+
+// Fragments of issuer names, lowercase, and the country of the authority they identify. Fragments are matched anywhere
+// in the issuer, so they must be specific enough not to appear in the names of other authorities.
+const KNOWN_ISSUERS: &[(&str, &str)] = &[
+    ("austria", "AT"),
+    ("bmsgpk", "AT"),
+    ("bundesamt für gesundheit", "CH"),
+    ("bundesministerium für gesundheit", "DE"),
+    ("ministerio de sanidad", "ES"),
+    ("ministero della salute", "IT"),
+    ("national public health center", "HU"),
+    ("nhs digital", "GB"),
+    ("österreich", "AT"),
+    ("rivm", "NL"),
+    ("robert koch", "DE"),
+    ("santé publique france", "FR"),
+];
+
+// Returns the country of the authority named by `issuer`, if it is a known one
+fn issuer_country(issuer: &str) -> Option<&'static str> {
+    let issuer = issuer.trim().to_lowercase();
+
+    KNOWN_ISSUERS
+        .iter()
+        .find(|(fragment, _)| issuer.contains(fragment))
+        .map(|&(_, country)| country)
+}
+
+fn matches_country(issuer: &str, country: &Country) -> Option<bool> {
+    let declared = String::from(country.alpha2().trim()).to_ascii_uppercase();
+
+    issuer_country(issuer).map(|known| known == Country::from(declared).canonical())
+}
+
+impl Recovery {
+    /// Returns whether the issuer is an authority of the country the recovery was recorded in, if the issuer is one of
+    /// a small set of well-known health authorities, i.e. `Ministry of Health, Austria` for `AT`.
+    ///
+    /// This is a heuristic meant to flag suspicious certificates and never fails: it returns `None` for issuers it does
+    /// not recognize, which are the vast majority. A mismatch does not make a certificate invalid either, as some
+    /// authorities issue certificates for recoveries recorded abroad.
+    pub fn issuer_matches_country(&self) -> Option<bool> {
+        matches_country(&self.issuer, &self.country)
+    }
+}
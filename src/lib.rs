@@ -1,24 +1,150 @@
-use std::{
-    collections::BTreeMap,
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::{
     convert::TryFrom,
     fmt,
+    hash::{Hash, Hasher},
+};
+
+#[cfg(feature = "std")]
+use std::{
+    borrow::Cow,
     io::{self, Read},
 };
 
 use chrono::prelude::*;
 use ciborium::value::Value;
-use flate2::read::ZlibDecoder;
-use serde_derive::Deserialize;
+#[cfg(feature = "std")]
+use flate2::bufread::{DeflateDecoder, ZlibDecoder};
+use serde_derive::{Deserialize, Serialize};
 use thiserror::Error;
 
+mod borrowed;
+pub use borrowed::*;
+
+#[cfg(feature = "builder")]
+mod builder;
+#[cfg(feature = "builder")]
+pub use builder::*;
+
+#[cfg(feature = "certlogic")]
+mod certlogic;
+#[cfg(feature = "certlogic")]
+pub use certlogic::*;
+
+mod clock;
+pub use clock::*;
+
+mod content;
+
+mod country;
+pub use country::*;
+
+#[cfg(feature = "revocation-http")]
+mod crl;
+#[cfg(feature = "revocation-http")]
+pub use crl::*;
+
+mod dated;
+pub use dated::*;
+
+mod dgc;
+
+mod display;
+
+#[cfg(feature = "trustlist-http")]
+mod fetch;
+#[cfg(feature = "trustlist-http")]
+pub use fetch::*;
+
+mod headers;
+pub use headers::*;
+
+mod issuer;
+
+mod normalize;
+
+mod presence;
+
+#[cfg(feature = "qr")]
+mod qr;
+
+mod redact;
+pub use redact::*;
+
+mod revocation;
+pub use revocation::*;
+
+mod schema;
+pub use schema::*;
+
+#[cfg(feature = "std")]
+mod shc;
+#[cfg(feature = "std")]
+pub use shc::*;
+
+mod summary;
+pub use summary::*;
+
+mod uvci;
+pub use uvci::*;
+
+mod validity;
+pub use validity::*;
+
 mod values;
 pub use values::*;
 
-type Result<T> = std::result::Result<T, Error>;
+mod verify;
+pub use verify::*;
+
+mod warning;
+pub use warning::*;
+
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
+
+type Result<T> = core::result::Result<T, Error>;
+
+// Error ciborium returns when reading from a byte slice, which is io::Error only when std is available
+type CborError = ciborium::de::Error<<&'static [u8] as ciborium_io::Read>::Error>;
 
-#[derive(Deserialize)]
 struct Cwt(Vec<Value>);
 
+impl TryFrom<Value> for Cwt {
+    type Error = Error;
+
+    fn try_from(mut root: Value) -> Result<Self> {
+        // the COSE_Sign1 (18) and CWT (61) tags are optional, and carry no information we need
+        while let Value::Tag(_, inner) = root {
+            root = *inner;
+        }
+
+        let arr = match root {
+            Value::Array(arr) => arr,
+            _ => return Err(Error::MalformedCWT),
+        };
+
+        // COSE_Sign1 is [protected, unprotected, payload, signature]; COSE_Sign has an array of signatures in place of
+        // the signature, while detached payloads are nil
+        match arr.as_slice() {
+            [Value::Bytes(_), Value::Map(_), Value::Bytes(_), Value::Bytes(_)] => Ok(Cwt(arr)),
+            _ => Err(Error::UnsupportedCoseStructure { len: arr.len() }),
+        }
+    }
+}
+
 impl fmt::Display for Cwt {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Cwt {{ values: [")?;
@@ -36,6 +162,49 @@ impl fmt::Display for Cwt {
     }
 }
 
+fn serialize_opt_hex<S: serde::Serializer>(
+    bytes: &Option<Vec<u8>>,
+    serializer: S,
+) -> core::result::Result<S::Ok, S::Error> {
+    match bytes {
+        Some(bytes) => serializer.serialize_some(&hex::encode(bytes)),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn serialize_hex_map<S: serde::Serializer>(
+    map: &BTreeMap<String, Vec<u8>>,
+    serializer: S,
+) -> core::result::Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().map(|(k, v)| (k, hex::encode(v))))
+}
+
+/// Maximum nesting of the CBOR structures decoded from a certificate. Certificates never get close to it, while deeper
+/// data is rejected with [Error::CborTooDeep] before it can exhaust the stack.
+pub const MAX_CBOR_DEPTH: usize = 32;
+
+// Decodes CBOR from untrusted input, bounding the recursion depth of the deserializer
+fn from_cbor<T: serde::de::DeserializeOwned>(data: &[u8]) -> Result<T> {
+    use ciborium::de::Error as De;
+
+    // the reader is advanced as the data is decoded, so what is left of it tells where decoding stopped
+    let mut reader = data;
+
+    let res = ciborium::de::from_reader_with_recursion_limit(&mut reader, MAX_CBOR_DEPTH);
+
+    res.map_err(|e| match e {
+        De::RecursionLimitExceeded => Error::CborTooDeep,
+        e => Error::MalformedCBOR {
+            offset: match e {
+                De::Syntax(offset) | De::Semantic(Some(offset), _) => offset,
+                _ => data.len() - reader.len(),
+            },
+            len: data.len(),
+            source: e,
+        },
+    })
+}
+
 #[derive(Deserialize)]
 struct RawCert(BTreeMap<isize, Value>);
 
@@ -45,38 +214,248 @@ struct RawHeader(BTreeMap<isize, Value>);
 /// Error type that represents every possible error condition encountered while loading a certificate
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("invalid base45 in input")]
-    InvalidBase45(#[from] base45::DecodeError),
+    #[cfg(any(feature = "trustlist-http", feature = "revocation-http"))]
+    #[error("failed to download trust list or CRL")]
+    Http(#[from] reqwest::Error),
+
+    #[cfg(feature = "qr")]
+    #[error("failed to render QR code: {0}")]
+    QrCode(String),
 
+    #[cfg(feature = "std")]
+    #[error("invalid base45 in input: {0}")]
+    InvalidBase45(#[from] Base45Error),
+
+    #[cfg(feature = "std")]
     #[error(transparent)]
     IOError(#[from] io::Error),
 
+    #[error("algorithm {algorithm:?} requires {:?} keys, but the trust list holds a {key:?} key", algorithm.key_family())]
+    AlgorithmKeyMismatch {
+        algorithm: CoseAlgorithm,
+        key: KeyFamily,
+    },
+
+    #[error("CBOR data is nested deeper than {MAX_CBOR_DEPTH} levels")]
+    CborTooDeep,
+
+    #[error("disease or agent `{0}` is not allowed")]
+    DiseaseNotAllowed(String),
+
+    #[error("duplicate key `{0}` in payload")]
+    DuplicateKey(String),
+
+    #[error("invalid certificate or public key: {0}")]
+    InvalidCertificate(String),
+
+    #[error("invalid certificate revocation list: {0}")]
+    InvalidCrl(String),
+
     #[error("invalid key in document: {0}")]
     InvalidKey(String),
 
+    #[error("invalid dose count {number}/{total}")]
+    InvalidDoses { number: usize, total: usize },
+
     #[error("invalid format for `{key}`")]
     InvalidFormatFor { key: String },
 
-    #[error("failed to parse a payload as CBOR")]
-    MalformedCBOR(#[from] ciborium::de::Error<std::io::Error>),
+    #[error("signature does not match the certificate contents")]
+    InvalidSignature,
+
+    #[error("no key for KID {} in trust list", hex::encode(.0))]
+    KeyNotFound(Vec<u8>),
+
+    /// `offset` is the position in the payload reached when decoding failed, which is right after the offending item
+    /// unless the decoder reports it more precisely, and `len` the length of the payload, which for the outer COSE
+    /// structure is the size of the inflated data
+    #[error("failed to parse a payload as CBOR at byte {offset} of {len}")]
+    MalformedCBOR {
+        offset: usize,
+        len: usize,
+        source: CborError,
+    },
 
     #[error("the root structure for the certificate is malformed")]
     MalformedCWT,
 
-    #[error("malformed date: {0}")]
-    MalformedDate(String),
+    #[error("malformed date: {value}")]
+    MalformedDate {
+        value: String,
+        #[cfg_attr(feature = "std", source)]
+        cause: Option<chrono::ParseError>,
+    },
+
+    #[error("malformed schema version: {0}")]
+    MalformedSchemaVersion(String),
 
     #[error("found unexpected non-string keys in map")]
     MalformedStringMap,
 
+    #[error("malformed trust list")]
+    MalformedTrustList(#[from] serde_json::Error),
+
+    #[error("malformed SMART Health Card: {0}")]
+    MalformedShc(String),
+
+    #[error("malformed UVCI: {0}")]
+    MalformedUvci(String),
+
     #[error("missing initial HC string from input")]
     MissingHCID,
 
+    #[error("expected a single pass, found {0}")]
+    MultiplePasses(usize),
+
+    #[error("the certificate contains no passes")]
+    NoPasses,
+
     #[error("invalid key in document: {0}")]
     MissingKey(String),
 
     #[error("spurious leftover data detected: {0:?}")]
     SpuriousData(BTreeMap<String, Value>),
+
+    #[error("unknown country code: {0}")]
+    UnknownCountry(String),
+
+    #[error("unsupported input format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("unsupported signature algorithm: {0}")]
+    UnsupportedAlgorithm(i128),
+
+    #[error("unsupported critical COSE header parameter: {0}")]
+    UnsupportedCriticalParam(i128),
+
+    #[error("certificate chain is not trusted: {0}")]
+    UntrustedChain(String),
+
+    #[error("unsupported COSE structure with {len} elements, expected a COSE_Sign1 message")]
+    UnsupportedCoseStructure { len: usize },
+
+    #[error("UVCI checksum mismatch: expected {expected}, found {found}")]
+    UvciChecksumMismatch { expected: char, found: char },
+}
+
+/// Describes why the Base45 payload of a certificate could not be decoded
+#[cfg(feature = "std")]
+#[derive(Debug, Error)]
+pub enum Base45Error {
+    #[error("invalid character {found:?} at position {position}")]
+    InvalidCharacter { position: usize, found: char },
+
+    #[error("payload is truncated, {len} characters is not a valid Base45 length")]
+    Truncated { len: usize },
+
+    #[error(transparent)]
+    Decode(#[from] base45::DecodeError),
+}
+
+/// Broad category of an [Error], stable across releases so that callers can branch on it without matching every
+/// variant or the error message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input is not a valid HC1 string, or its Base45, zlib or CBOR encoding is broken
+    InvalidEncoding,
+
+    /// The certificate was decoded, but its contents do not follow the schema
+    MalformedStructure,
+
+    /// A mandatory field is missing from the certificate
+    MissingField,
+
+    /// The signature could not be verified, or the keys needed to verify it are invalid or missing
+    Signature,
+
+    /// Reading the input or downloading data failed
+    Io,
+}
+
+impl Error {
+    /// Returns the category this error belongs to
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            #[cfg(any(feature = "trustlist-http", feature = "revocation-http"))]
+            Error::Http(_) => ErrorKind::Io,
+            #[cfg(feature = "qr")]
+            Error::QrCode(_) => ErrorKind::InvalidEncoding,
+            #[cfg(feature = "std")]
+            Error::InvalidBase45(_) => ErrorKind::InvalidEncoding,
+            // flate2 reports corrupted streams as I/O errors
+            #[cfg(feature = "std")]
+            Error::IOError(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::InvalidData
+                        | io::ErrorKind::InvalidInput
+                        | io::ErrorKind::UnexpectedEof
+                ) =>
+            {
+                ErrorKind::InvalidEncoding
+            }
+            #[cfg(feature = "std")]
+            Error::IOError(_) => ErrorKind::Io,
+            Error::CborTooDeep
+            | Error::MalformedCBOR { .. }
+            | Error::MalformedShc(_)
+            | Error::MissingHCID
+            | Error::UnsupportedFormat(_) => ErrorKind::InvalidEncoding,
+            Error::DiseaseNotAllowed(_)
+            | Error::DuplicateKey(_)
+            | Error::InvalidKey(_)
+            | Error::InvalidDoses { .. }
+            | Error::InvalidFormatFor { .. }
+            | Error::MalformedCWT
+            | Error::MalformedDate { .. }
+            | Error::MalformedSchemaVersion(_)
+            | Error::MalformedStringMap
+            | Error::MalformedUvci(_)
+            | Error::MultiplePasses(_)
+            | Error::NoPasses
+            | Error::SpuriousData(_)
+            | Error::UnknownCountry(_)
+            | Error::UnsupportedCoseStructure { .. }
+            | Error::UnsupportedCriticalParam(_)
+            | Error::UvciChecksumMismatch { .. } => ErrorKind::MalformedStructure,
+            Error::MissingKey(_) => ErrorKind::MissingField,
+            Error::AlgorithmKeyMismatch { .. }
+            | Error::InvalidCertificate(_)
+            | Error::InvalidCrl(_)
+            | Error::InvalidSignature
+            | Error::KeyNotFound(_)
+            | Error::UntrustedChain(_)
+            | Error::MalformedTrustList(_)
+            | Error::UnsupportedAlgorithm(_) => ErrorKind::Signature,
+        }
+    }
+}
+
+// Evaluates `$body`, a parsing stage returning a Result, within a debug span; failures are logged at debug level and
+// successes at trace level. Without the `tracing` feature this is just `$body`.
+#[cfg(feature = "tracing")]
+macro_rules! stage {
+    ($name:literal, $field:ident = $value:expr, $body:expr) => {{
+        let span = tracing::debug_span!($name, $field = $value);
+        let _guard = span.enter();
+
+        let res = $body;
+
+        match &res {
+            Ok(_) => tracing::trace!("stage completed"),
+            Err(e) => tracing::debug!(error = %e, "stage failed"),
+        }
+
+        res
+    }};
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! stage {
+    ($name:literal, $field:ident = $value:expr, $body:expr) => {
+        $body
+    };
 }
 
 macro_rules! map_empty {
@@ -101,56 +480,308 @@ macro_rules! gen_extract {
 
 gen_extract!(extract_array, Value::Array, Vec<Value>);
 
-fn extract_date(m: &mut BTreeMap<String, Value>, k: &str) -> Result<NaiveDate> {
-    extract_string(m, k)
-        .and_then(|ds| NaiveDate::parse_from_str(&ds, "%F").map_err(|_| Error::MalformedDate(ds)))
+fn extract_date(m: &mut BTreeMap<String, Value>, k: &str) -> Result<Dated> {
+    extract_string(m, k).and_then(Dated::parse)
 }
 
+// Formats tried after RFC 3339, in order. `%#z` accepts `Z` as well as offsets written without a colon or without
+// minutes, and some issuers also leave out the seconds
+const LOOSE_DATETIME_FORMATS: &[&str] = &[
+    "%+",
+    "%Y-%m-%dT%H:%M:%S%.f%#z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+    "%Y-%m-%dT%H:%M%#z",
+    "%Y-%m-%dT%H:%M%z",
+];
+
+// RFC 3339 timestamps are tried first, so `Z` and `+00:00` are handled alike; if no format matches, the error reported
+// is the one from RFC 3339 parsing
 fn extract_isodatetime(m: &mut BTreeMap<String, Value>, k: &str) -> Result<DateTime<FixedOffset>> {
     extract_string(m, k).and_then(|ds| {
-        DateTime::parse_from_str(&ds, "%+")
-            .or_else(|_| DateTime::parse_from_str(&ds, "%Y-%m-%dT%H:%M:%S%.f%#z"))
-            .or_else(|_| DateTime::parse_from_str(&ds, "%Y-%m-%dT%H:%M:%S%.f%z"))
-            .map_err(|_| Error::MalformedDate(ds))
+        DateTime::parse_from_rfc3339(&ds)
+            .or_else(|e| {
+                LOOSE_DATETIME_FORMATS
+                    .iter()
+                    .find_map(|fmt| DateTime::parse_from_str(&ds, fmt).ok())
+                    .ok_or(e)
+            })
+            .map_err(|e| malformed_date(ds, e))
+    })
+}
+
+// With `coerce`, integers encoded as text by non-conforming issuers (i.e. `"1"`) are parsed as well
+fn extract_int(m: &mut BTreeMap<String, Value>, k: &str, coerce: bool) -> Result<i128> {
+    extract_key(m, k).and_then(|v| match v {
+        Value::Integer(n) => Ok(n.into()),
+        Value::Text(s) if coerce => s
+            .trim()
+            .parse()
+            .map_err(|_| Error::InvalidFormatFor { key: k.into() }),
+        _ => Err(Error::InvalidFormatFor { key: k.into() }),
     })
 }
 
-gen_extract!(extract_int, Value::Integer, i128);
+// CWT timestamps are seconds since the epoch; values chrono can't represent are rejected instead of panicking
+fn timestamp(ts: i128) -> Result<DateTime<Utc>> {
+    i64::try_from(ts)
+        .ok()
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        .ok_or_else(|| Error::MalformedDate {
+            value: ts.to_string(),
+            cause: None,
+        })
+}
+
+fn malformed_date(value: String, cause: chrono::ParseError) -> Error {
+    Error::MalformedDate {
+        value,
+        cause: Some(cause),
+    }
+}
 
 fn extract_key(m: &mut BTreeMap<String, Value>, k: &str) -> Result<Value> {
     m.remove(k).ok_or_else(|| Error::MissingKey(k.into()))
 }
 
+// Like extract_string, but a missing key is not an error
+fn extract_opt_string(m: &mut BTreeMap<String, Value>, k: &str) -> Result<Option<String>> {
+    if m.contains_key(k) {
+        extract_string(m, k).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
 gen_extract!(extract_string, Value::Text, String);
 
 fn extract_string_map(m: &mut BTreeMap<String, Value>, k: &str) -> Result<BTreeMap<String, Value>> {
     to_strmap(k, extract_key(m, k)?)
 }
 
-#[derive(Debug, PartialEq)]
+// Extracts a non-negative integer, rejecting values that do not fit in T instead of wrapping them
+fn extract_uint<T: TryFrom<u128>>(
+    m: &mut BTreeMap<String, Value>,
+    k: &str,
+    coerce: bool,
+) -> Result<T> {
+    let n = extract_int(m, k, coerce)?;
+
+    u128::try_from(n)
+        .ok()
+        .and_then(|n| T::try_from(n).ok())
+        .ok_or_else(|| Error::InvalidFormatFor { key: k.into() })
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum CertInfo {
+    Exemption(Exemption),
+    Light(Light),
     Recovery(Recovery),
     Test(Test),
+
+    /// An entry under a key this crate does not know, i.e. one added by a later revision of the schema. Such entries
+    /// are only kept outside of [strict](ParseOptions::strict) mode.
+    Unknown {
+        /// Key of the array holding the entry in the pass
+        key: String,
+
+        /// The entry, encoded as CBOR
+        #[serde(serialize_with = "hex::serialize")]
+        value: Vec<u8>,
+    },
+
     Vaccine(Vaccine),
 }
 
+impl CertInfo {
+    /// Certificate ID (UVCI) of the entry. Certificates light and unknown entries have none.
+    pub fn cert_id(&self) -> Option<&str> {
+        match self {
+            CertInfo::Exemption(e) => Some(&e.cert_id),
+            CertInfo::Light(_) | CertInfo::Unknown { .. } => None,
+            CertInfo::Recovery(r) => Some(&r.cert_id),
+            CertInfo::Test(t) => Some(&t.cert_id),
+            CertInfo::Vaccine(v) => Some(&v.cert_id),
+        }
+    }
+
+    /// Disease or agent targeted by the entry. Certificates light and unknown entries have none.
+    pub fn target_disease(&self) -> Option<Disease> {
+        match self {
+            CertInfo::Exemption(e) => Some(e.target_disease()),
+            CertInfo::Light(_) | CertInfo::Unknown { .. } => None,
+            CertInfo::Recovery(r) => Some(r.target_disease()),
+            CertInfo::Test(t) => Some(t.target_disease()),
+            CertInfo::Vaccine(v) => Some(v.target_disease()),
+        }
+    }
+
+    /// Member State the entry refers to. Certificates light and unknown entries have none.
+    pub fn country(&self) -> Option<&Country> {
+        match self {
+            CertInfo::Exemption(e) => Some(&e.country),
+            CertInfo::Light(_) | CertInfo::Unknown { .. } => None,
+            CertInfo::Recovery(r) => Some(&r.country),
+            CertInfo::Test(t) => Some(&t.country),
+            CertInfo::Vaccine(v) => Some(&v.country),
+        }
+    }
+}
+
+// Generates the is_*, as_* and into_* accessors for each kind of entry
+macro_rules! cert_info_kinds {
+    ($($variant:ident => $is:ident, $as:ident, $into:ident;)*) => {
+        impl CertInfo {
+            $(
+                #[doc = concat!("Returns true if this is a [", stringify!($variant), "] entry")]
+                pub fn $is(&self) -> bool {
+                    matches!(self, CertInfo::$variant(_))
+                }
+
+                #[doc = concat!("Returns the [", stringify!($variant), "] held by this entry, if any")]
+                pub fn $as(&self) -> Option<&$variant> {
+                    match self {
+                        CertInfo::$variant(e) => Some(e),
+                        _ => None,
+                    }
+                }
+
+                #[doc = concat!("Converts this entry into a [", stringify!($variant), "], if it holds one")]
+                pub fn $into(self) -> Option<$variant> {
+                    match self {
+                        CertInfo::$variant(e) => Some(e),
+                        _ => None,
+                    }
+                }
+            )*
+        }
+    };
+}
+
+// Resolves the `tg` code of each kind of entry
+macro_rules! impl_target_disease {
+    ($($t:ty),*) => {
+        $(
+            impl $t {
+                /// Disease or agent targeted by this entry
+                pub fn target_disease(&self) -> Disease {
+                    Disease::from_code(&self.disease)
+                }
+            }
+        )*
+    };
+}
+
+impl_target_disease!(Exemption, Recovery, Test, Vaccine);
+
+cert_info_kinds! {
+    Exemption => is_exemption, as_exemption, into_exemption;
+    Light => is_light, as_light, into_light;
+    Recovery => is_recovery, as_recovery, into_recovery;
+    Test => is_test, as_test, into_test;
+    Vaccine => is_vaccine, as_vaccine, into_vaccine;
+}
+
+/// Options controlling how certificates are parsed. The default options only accept standard certificates.
+#[derive(Clone, Debug, Default)]
+pub struct ParseOptions {
+    /// Accept the vaccination exemption entries some member states issue under the non-standard `e` key
+    pub exemptions: bool,
+
+    /// Keep the keys outside of the specification found in vaccine entries, such as the national annotations added
+    /// by some member states, in [Vaccine::extra] instead of failing with [Error::SpuriousData]. Ignored when
+    /// `strict` is set.
+    pub extra_keys: bool,
+
+    /// Accept dose counts (`dn` and `sd`) encoded as text strings, such as `"1"`, as some non-conforming issuers do,
+    /// instead of failing with [Error::InvalidFormatFor]. Ignored when `strict` is set.
+    pub coerce_types: bool,
+
+    /// Reject values outside of the specification value sets, such as unknown country codes, instead of keeping them
+    /// as they are. Payloads containing maps with duplicate keys, and passes without a `nam` map, are also rejected.
+    pub strict: bool,
+
+    /// Codes of the diseases or agents (`tg`) entries may target, i.e. [COVID_19] only. Entries targeting any other
+    /// disease fail with [Error::DiseaseNotAllowed]; `None`, the default, accepts any disease. Certificates light and
+    /// unknown entries have no disease and are not affected.
+    pub allowed_diseases: Option<BTreeSet<String>>,
+}
+
+// Standardized names only use the ICAO Doc 9303 charset
+fn is_icao_name(name: &str) -> bool {
+    name.bytes()
+        .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'<')
+}
+
+// Only the allowed diseases are accepted, if any are given
+fn check_disease(ci: &CertInfo, opts: &ParseOptions) -> Result<()> {
+    match (&opts.allowed_diseases, ci.target_disease()) {
+        (Some(allowed), Some(disease)) if !allowed.contains(disease.code()) => {
+            Err(Error::DiseaseNotAllowed(disease.code().into()))
+        }
+        _ => Ok(()),
+    }
+}
+
+// In strict mode, only known country codes are accepted
+fn check_country(country: &Country, opts: &ParseOptions) -> Result<()> {
+    if opts.strict && !country.is_known() {
+        return Err(Error::UnknownCountry(country.alpha2().into()));
+    }
+
+    Ok(())
+}
+
+/// CBOR map keys understood in a [GreenPass]. `e` is only accepted with [ParseOptions::exemptions].
+pub const PASS_KEYS: &[&str] = &["dob", "e", "nam", "r", "t", "v", "ver"];
+
+// Takes every array found under a key outside of PASS_KEYS, which may hold entries of a kind this crate does not know
+fn extract_unknown_entries(values: &mut BTreeMap<String, Value>) -> Vec<CertInfo> {
+    let keys: Vec<_> = values
+        .iter()
+        .filter(|(k, v)| !PASS_KEYS.contains(&k.as_str()) && matches!(v, Value::Array(_)))
+        .map(|(k, _)| k.clone())
+        .collect();
+
+    let mut entries = Vec::new();
+
+    for key in keys {
+        if let Some(Value::Array(items)) = values.remove(&key) {
+            entries.extend(items.iter().map(|item| {
+                let mut value = Vec::new();
+                ciborium::ser::into_writer(item, &mut value).expect("writing to a Vec cannot fail");
+
+                CertInfo::Unknown {
+                    key: key.clone(),
+                    value,
+                }
+            }));
+        }
+    }
+
+    entries
+}
+
+/// CBOR map keys understood in the `nam` map of a [GreenPass]
+pub const NAME_KEYS: &[&str] = &["fn", "fnt", "gn", "gnt"];
+
 /// Structure that represents a Green Pass entry.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct GreenPass {
     /// Date of birth
     pub date_of_birth: String, // dob can have weird formats
 
-    /// Family name
-    pub surname: String, // nam/fn
+    /// Family name, if present
+    pub surname: Option<String>, // nam/fn
 
-    /// First name
-    pub givenname: String, // nam/gn
+    /// First name, absent for holders with a single name
+    pub givenname: Option<String>, // nam/gn
 
-    /// Family name in standardized form (see docs)
+    /// Family name in standardized form (see docs), empty if the certificate has no `nam` map
     pub std_surname: String, // nam/fnt
 
-    /// First name in standardized form
-    pub std_givenname: String, // nam/gnt
+    /// First name in standardized form, absent for holders with a single name
+    pub std_givenname: Option<String>, // nam/gnt
 
     /// Document version
     pub ver: String, // ver
@@ -162,44 +793,96 @@ pub struct GreenPass {
 impl TryFrom<BTreeMap<String, Value>> for GreenPass {
     type Error = Error;
 
-    fn try_from(mut values: BTreeMap<String, Value>) -> std::result::Result<Self, Self::Error> {
-        let date_of_birth = extract_string(&mut values, "dob")?;
-        let ver = extract_string(&mut values, "ver")?;
+    fn try_from(values: BTreeMap<String, Value>) -> core::result::Result<Self, Self::Error> {
+        GreenPass::from_map(values, &ParseOptions::default())
+    }
+}
 
-        let entries = if let Ok(rs) = extract_array(&mut values, "r") {
-            rs.into_iter()
-                .map(|v| {
-                    to_strmap("recovery entry", v)
-                        .and_then(Recovery::try_from)
-                        .map(CertInfo::Recovery)
-                })
-                .collect::<Result<_>>()?
-        } else if let Ok(ts) = extract_array(&mut values, "t") {
-            ts.into_iter()
-                .map(|v| {
-                    to_strmap("test entry", v)
-                        .and_then(Test::try_from)
-                        .map(CertInfo::Test)
-                })
-                .collect::<Result<_>>()?
-        } else if let Ok(vs) = extract_array(&mut values, "v") {
-            vs.into_iter()
-                .map(|v| {
-                    to_strmap("vaccine entry", v)
-                        .and_then(Vaccine::try_from)
-                        .map(CertInfo::Vaccine)
-                })
-                .collect::<Result<_>>()?
+impl GreenPass {
+    /// Builds a pass from its decoded CBOR map, using the given options.
+    pub fn from_map(mut values: BTreeMap<String, Value>, opts: &ParseOptions) -> Result<Self> {
+        // entries depend on the schema version, which is only extracted with the holder data
+        let version = match values.get("ver") {
+            Some(Value::Text(ver)) => SchemaVersion::parse(ver).unwrap_or(SchemaVersion::LATEST),
+            _ => SchemaVersion::LATEST,
+        };
+
+        // the groups are tried in turn, so a missing one is not an error and needs no error value
+        let mut take_array = |k: &str| match values.remove(k) {
+            Some(Value::Array(arr)) => Some(arr),
+            _ => None,
+        };
+
+        let mut entries = if let Some(rs) = take_array("r") {
+            parse_entries("r", rs, |m| Recovery::try_from(m).map(CertInfo::Recovery))?
+        } else if let Some(ts) = take_array("t") {
+            parse_entries("t", ts, |m| Test::from_map(m, &version).map(CertInfo::Test))?
+        } else if let Some(vs) = take_array("v") {
+            parse_entries("v", vs, |m| {
+                Vaccine::from_map(m, opts).map(CertInfo::Vaccine)
+            })?
+        } else if opts.exemptions && values.contains_key("e") {
+            parse_entries("e", extract_array(&mut values, "e")?, |m| {
+                Exemption::try_from(m).map(CertInfo::Exemption)
+            })?
         } else {
-            return Err(Error::MissingKey("r, t or v (the actual data)".into()));
+            Vec::new()
         };
 
-        let mut nam = extract_string_map(&mut values, "nam")?;
+        // entry kinds added by later revisions of the schema are kept as they are, so that the pass can still be
+        // parsed together with its known data
+        if !opts.strict {
+            entries.extend(extract_unknown_entries(&mut values));
+        }
 
-        let surname = extract_string(&mut nam, "fn")?;
-        let givenname = extract_string(&mut nam, "gn")?;
-        let std_surname = extract_string(&mut nam, "fnt")?;
-        let std_givenname = extract_string(&mut nam, "gnt")?;
+        if entries.is_empty() {
+            return Err(Error::MissingKey("r, t or v (the actual data)".into()));
+        }
+
+        for ci in &entries {
+            if let Some(country) = ci.country() {
+                check_country(country, opts)?;
+            }
+
+            check_disease(ci, opts)?;
+        }
+
+        GreenPass::with_entries(values, entries, opts)
+    }
+
+    /// Builds a pass from the decoded CBOR map of a certificate light, which only holds the personal data, using the
+    /// given options.
+    pub fn from_light_map(
+        values: BTreeMap<String, Value>,
+        light: Light,
+        opts: &ParseOptions,
+    ) -> Result<Self> {
+        GreenPass::with_entries(values, vec![CertInfo::Light(light)], opts)
+    }
+
+    // Extracts the holder data, which must be all that is left in `values`. Some minimal certificates have no `nam`
+    // map, which is only accepted outside of strict mode and leaves the names empty
+    fn with_entries(
+        mut values: BTreeMap<String, Value>,
+        entries: Vec<CertInfo>,
+        opts: &ParseOptions,
+    ) -> Result<Self> {
+        let date_of_birth = extract_string(&mut values, "dob")?;
+        let ver = extract_string(&mut values, "ver")?;
+
+        let (surname, givenname, std_surname, std_givenname) =
+            if opts.strict || values.contains_key("nam") {
+                let mut nam = extract_string_map(&mut values, "nam")?;
+
+                (
+                    extract_opt_string(&mut nam, "fn")?,
+                    extract_opt_string(&mut nam, "gn")?,
+                    extract_string(&mut nam, "fnt")?,
+                    extract_opt_string(&mut nam, "gnt")?,
+                )
+            } else {
+                (None, None, String::new(), None)
+            };
 
         let gp = GreenPass {
             date_of_birth,
@@ -213,53 +896,516 @@ impl TryFrom<BTreeMap<String, Value>> for GreenPass {
 
         map_empty!(values);
 
-        Ok(gp)
+        Ok(gp)
+    }
+
+    /// Returns the most recent vaccination in this pass, by vaccination date. Ties are won by the first entry.
+    pub fn latest_vaccine(&self) -> Option<&Vaccine> {
+        latest(self.entries.iter().filter_map(CertInfo::as_vaccine), |v| {
+            v.date.parsed
+        })
+    }
+
+    /// Returns the most recent test in this pass, by sample collection time. Ties are won by the first entry.
+    pub fn latest_test(&self) -> Option<&Test> {
+        latest(self.entries.iter().filter_map(CertInfo::as_test), |t| {
+            t.collect_ts_utc()
+        })
+    }
+
+    /// Returns the most relevant entry of this pass, that is the most recent one by vaccination date, test sample
+    /// collection time, recovery diagnosis date or exemption start date. Dates are taken at midnight UTC, so a test
+    /// taken on the day of a vaccination wins over it; remaining ties are won by the first entry.
+    ///
+    /// Certificates light and unknown entries carry no date and are never returned.
+    pub fn primary_entry(&self) -> Option<&CertInfo> {
+        let midnight = |date: &Dated| Utc.from_utc_datetime(&date.parsed.and_hms(0, 0, 0));
+
+        let entries = self.entries.iter().filter_map(|ci| {
+            let time = match ci {
+                CertInfo::Exemption(e) => midnight(&e.valid_from),
+                CertInfo::Light(_) | CertInfo::Unknown { .. } => return None,
+                CertInfo::Recovery(r) => midnight(&r.diagnosed),
+                CertInfo::Test(t) => t.collect_ts_utc(),
+                CertInfo::Vaccine(v) => midnight(&v.date),
+            };
+
+            Some((ci, time))
+        });
+
+        latest(entries, |&(_, time)| time).map(|(ci, _)| ci)
+    }
+
+    /// Returns whether this pass and `other` belong to the same holder, by comparing their dates of birth and the
+    /// standardized forms of their names (`fnt` and `gnt`).
+    ///
+    /// Standardized names are transliterated as per ICAO Doc 9303, so that the same name written by different issuers
+    /// (i.e. `Gößinger` and `Goessinger`) compares equal; they are still compared ignoring case and surrounding
+    /// whitespace. Dates of birth are compared as dates when they are full dates, as trimmed strings otherwise. Passes without
+    /// a standardized family name, such as the ones lacking a `nam` map, never match.
+    pub fn same_holder(&self, other: &GreenPass) -> bool {
+        let std_name = |name: &str| name.trim().to_ascii_uppercase();
+        // as in HealthCert::normalize, full dates are rewritten so that i.e. zero padding does not matter
+        let dob = |pass: &GreenPass| {
+            let dob = pass.date_of_birth.trim();
+
+            match NaiveDate::parse_from_str(dob, "%F") {
+                Ok(date) => date.format("%F").to_string(),
+                Err(_) => dob.to_string(),
+            }
+        };
+
+        !self.std_surname.trim().is_empty()
+            && std_name(&self.std_surname) == std_name(&other.std_surname)
+            && self.std_givenname.as_deref().map(std_name)
+                == other.std_givenname.as_deref().map(std_name)
+            && dob(self) == dob(other)
+    }
+
+    /// Returns true if the standardized names (`fnt` and `gnt`) only use the characters allowed by the ICAO Doc 9303
+    /// transliteration, that is uppercase letters from `A` to `Z`, digits and `<` as a filler. Empty names are valid.
+    ///
+    /// Certificates with invalid standardized names are still accepted, but their names may fail to match the travel
+    /// documents of the holder; see [HealthCert::warnings].
+    pub fn standardized_names_valid(&self) -> bool {
+        is_icao_name(&self.std_surname) && self.std_givenname.as_deref().is_none_or(is_icao_name)
+    }
+}
+
+// Picks the item with the greatest key, preferring the first one among equals
+fn latest<T, K: Ord>(items: impl Iterator<Item = T>, key: impl Fn(&T) -> K) -> Option<T> {
+    items.fold(None, |best, item| match best {
+        Some(best) if key(&best) >= key(&item) => Some(best),
+        _ => Some(item),
+    })
+}
+
+/// Represents the signature and signature metadata for a [HealthCert].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Signature {
+    /// Key id
+    #[serde(with = "hex::serde")]
+    pub kid: Vec<u8>,
+
+    /// Algorithm used for signing
+    pub algorithm: i128,
+
+    /// Raw signature
+    #[serde(with = "hex::serde")]
+    pub signature: Vec<u8>,
+
+    /// Countersignature found in the unprotected header, usually added by a gateway re-signing the certificate
+    pub counter_signature: Option<CounterSignature>,
+}
+
+/// A COSE countersignature (header label 7) over a certificate. It is only decoded, and never verified.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct CounterSignature {
+    /// Key id, empty if the countersignature doesn't specify one
+    #[serde(with = "hex::serde")]
+    pub kid: Vec<u8>,
+
+    /// Algorithm used for signing
+    pub algorithm: i128,
+
+    /// Serialized protected header of the countersignature
+    #[serde(with = "hex::serde")]
+    pub protected: Vec<u8>,
+
+    /// Raw signature
+    #[serde(with = "hex::serde")]
+    pub signature: Vec<u8>,
+}
+
+impl CounterSignature {
+    // Decodes a COSE_Signature structure. When label 7 holds more than one countersignature, only the first is kept.
+    fn from_cbor(v: &Value) -> Result<Self> {
+        let malformed = || Error::InvalidFormatFor {
+            key: "countersignature".into(),
+        };
+
+        let items = match v {
+            Value::Array(sigs) => match sigs.first() {
+                Some(Value::Array(first)) => first,
+                _ => sigs,
+            },
+            _ => return Err(malformed()),
+        };
+
+        let (protected, unprotected, signature) = match &items[..] {
+            [Value::Bytes(p), Value::Map(u), Value::Bytes(s)] => (p, u, s),
+            _ => return Err(malformed()),
+        };
+
+        let RawHeader(mut protected_properties) = if protected.is_empty() {
+            RawHeader(BTreeMap::new())
+        } else {
+            from_cbor(&protected[..])?
+        };
+
+        let mut header = |label: isize| {
+            protected_properties
+                .remove(&label)
+                .or_else(|| header_value(unprotected, label).cloned())
+        };
+
+        let algorithm = match header(1) {
+            Some(Value::Integer(i)) => i.into(),
+            Some(_) => {
+                return Err(Error::InvalidFormatFor {
+                    key: "countersignature algorithm".into(),
+                })
+            }
+            None => return Err(Error::MissingKey("countersignature algorithm".into())),
+        };
+
+        let kid = match header(4) {
+            Some(Value::Bytes(kid)) => kid,
+            Some(_) => {
+                return Err(Error::InvalidFormatFor {
+                    key: "countersignature KID".into(),
+                })
+            }
+            None => Vec::new(),
+        };
+
+        Ok(CounterSignature {
+            kid,
+            algorithm,
+            protected: protected.clone(),
+            signature: signature.clone(),
+        })
+    }
+}
+
+// Fails if any map within `v` holds the same key more than once, as decoding it into a BTreeMap would silently keep
+// only the last value. Keys are reported with their full path from `path`, as in `-260.1.v[0].ci`.
+fn check_duplicates(v: &Value, path: &str) -> Result<()> {
+    match v {
+        Value::Map(m) => {
+            for (i, (k, v)) in m.iter().enumerate() {
+                let key = match k {
+                    Value::Text(s) => s.clone(),
+                    Value::Integer(n) => i128::from(*n).to_string(),
+                    k => format!("{:?}", k),
+                };
+
+                let key = if path.is_empty() {
+                    key
+                } else {
+                    format!("{}.{}", path, key)
+                };
+
+                if m[..i].iter().any(|(prev, _)| prev == k) {
+                    return Err(Error::DuplicateKey(key));
+                }
+
+                check_duplicates(v, &key)?;
+            }
+        }
+        Value::Array(a) => {
+            for (i, v) in a.iter().enumerate() {
+                check_duplicates(v, &format!("{}[{}]", path, i))?;
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+// COSE header labels this crate understands: algorithm, crit, content type, KID, countersignature and x5chain
+const KNOWN_HEADERS: &[i128] = &[1, 2, 3, 4, 7, 33];
+
+// Fails if the `crit` parameter (label 2) of a protected header lists a label this crate does not understand, as
+// required by RFC 8152. Protected headers with text labels cannot be decoded anyway, so they are rejected as malformed
+fn check_critical(protected: &BTreeMap<isize, Value>) -> Result<()> {
+    let malformed = || Error::InvalidFormatFor { key: "crit".into() };
+
+    let labels = match protected.get(&2) {
+        Some(Value::Array(labels)) if !labels.is_empty() => labels,
+        Some(_) => return Err(malformed()),
+        None => return Ok(()),
+    };
+
+    for label in labels {
+        match label {
+            Value::Integer(label) if KNOWN_HEADERS.contains(&i128::from(*label)) => {}
+            Value::Integer(label) => return Err(Error::UnsupportedCriticalParam((*label).into())),
+            _ => return Err(malformed()),
+        }
+    }
+
+    Ok(())
+}
+
+// Looks up an integer label in a decoded COSE header map
+fn header_value(header: &[(Value, Value)], label: isize) -> Option<&Value> {
+    header
+        .iter()
+        .find(|(k, _)| *k == Value::Integer(label.into()))
+        .map(|(_, v)| v)
+}
+
+/// Raw COSE_Sign1 data a [HealthCert] has been decoded from, required to verify its signature.
+/// All fields are empty for certificates that have not been obtained through parsing.
+#[derive(Clone, Debug, Default)]
+pub struct RawCose {
+    /// Serialized protected header
+    pub protected: Vec<u8>,
+
+    /// Unprotected header, with its entries in the order they were found
+    pub unprotected: Vec<(Value, Value)>,
+
+    /// CWT payload, exactly as signed by the issuer
+    pub payload: Vec<u8>,
+}
+
+/// The four components of the COSE_Sign1 structure of a [HealthCert], as returned by [HealthCert::cose_sign1].
+///
+/// This allows handing certificates over to a dedicated COSE implementation instead of verifying them with
+/// [HealthCert::verify_with].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseSign1 {
+    /// Serialized protected header, exactly as received
+    pub protected_raw: Vec<u8>,
+
+    /// Unprotected header, with its entries in the order they were found
+    pub unprotected: Vec<(Value, Value)>,
+
+    /// CWT payload, exactly as received
+    pub payload: Vec<u8>,
+
+    /// Signature
+    pub signature: Vec<u8>,
+}
+
+impl CoseSign1 {
+    /// Encodes the structure as an untagged COSE_Sign1 array. The byte strings are kept as they are, while the
+    /// unprotected header, which is not covered by the signature, is re-encoded.
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let arr = Value::Array(vec![
+            Value::Bytes(self.protected_raw.clone()),
+            Value::Map(self.unprotected.clone()),
+            Value::Bytes(self.payload.clone()),
+            Value::Bytes(self.signature.clone()),
+        ]);
+
+        let mut buf = Vec::new();
+        ciborium::ser::into_writer(&arr, &mut buf).expect("writing to a Vec cannot fail");
+
+        buf
+    }
+}
+
+/// Represents the whole certificate blob
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthCert {
+    // Member country that issued the bundle (might be missing)
+    pub some_issuer: Option<Country>,
+
+    /// Bundle creation timestamp
+    pub created: DateTime<Utc>,
+
+    /// Bundle expiration timestamp
+    pub expires: DateTime<Utc>,
+
+    /// Time before which the bundle must not be accepted, if set by the issuer
+    pub not_before: Option<DateTime<Utc>>,
+
+    /// Unique identifier of the CBOR Web Token, if set by the issuer
+    #[serde(serialize_with = "serialize_opt_hex")]
+    pub cwt_id: Option<Vec<u8>>,
+
+    /// List of passes contained in this bundle. Parsing fails on bundles without passes, so this is only empty for
+    /// certificates built by hand or parsed leniently with no valid pass.
+    pub passes: Vec<GreenPass>,
+
+    /// Raw signature
+    pub signature: Signature,
+
+    /// Raw COSE data, ignored when comparing or hashing certificates
+    #[serde(skip)]
+    pub raw: RawCose,
+}
+
+impl PartialEq for HealthCert {
+    fn eq(&self, other: &Self) -> bool {
+        self.some_issuer == other.some_issuer
+            && self.created == other.created
+            && self.expires == other.expires
+            && self.not_before == other.not_before
+            && self.cwt_id == other.cwt_id
+            && self.passes == other.passes
+            && self.signature == other.signature
+    }
+}
+
+impl Eq for HealthCert {}
+
+impl Hash for HealthCert {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.some_issuer.hash(state);
+        self.created.hash(state);
+        self.expires.hash(state);
+        self.not_before.hash(state);
+        self.cwt_id.hash(state);
+        self.passes.hash(state);
+        self.signature.hash(state);
+    }
+}
+
+/// Validity of a [HealthCert] bundle at a given point in time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidityStatus {
+    /// The bundle has been created after the reference time
+    NotYetValid,
+
+    /// The reference time is within the bundle validity window
+    Valid,
+
+    /// The bundle expired at or before the reference time
+    Expired,
+}
+
+impl HealthCert {
+    /// Iterates over the entries of every pass in the bundle.
+    pub fn entries(&self) -> impl Iterator<Item = &CertInfo> {
+        self.passes.iter().flat_map(|gp| gp.entries.iter())
+    }
+
+    /// Returns true if the bundle holds exactly one pass, as most certificates do.
+    pub fn is_single(&self) -> bool {
+        self.passes.len() == 1
+    }
+
+    /// Returns the only pass in the bundle, failing with [Error::NoPasses] if it is empty, or with
+    /// [Error::MultiplePasses] if it holds more than one.
+    pub fn single_pass(&self) -> Result<&GreenPass> {
+        match &self.passes[..] {
+            [pass] => Ok(pass),
+            [] => Err(Error::NoPasses),
+            passes => Err(Error::MultiplePasses(passes.len())),
+        }
+    }
+
+    /// Iterates over all recovery attestations in the bundle.
+    pub fn recoveries(&self) -> impl Iterator<Item = &Recovery> {
+        self.entries().filter_map(CertInfo::as_recovery)
+    }
+
+    /// Iterates over all test attestations in the bundle.
+    pub fn tests(&self) -> impl Iterator<Item = &Test> {
+        self.entries().filter_map(CertInfo::as_test)
+    }
+
+    /// Iterates over all vaccinations in the bundle.
+    pub fn vaccines(&self) -> impl Iterator<Item = &Vaccine> {
+        self.entries().filter_map(CertInfo::as_vaccine)
+    }
+
+    /// Returns true if the bundle is expired at the time `now`.
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        now >= self.expires
+    }
+
+    /// Checks the bundle creation, not before and expiration timestamps against `now`.
+    ///
+    /// The reference time is supplied by the caller, this crate never reads the system clock; see
+    /// [HealthCert::validity_status_with] to use a [Clock] instead.
+    pub fn validity_status(&self, now: DateTime<Utc>) -> ValidityStatus {
+        if now < self.created || self.not_before.is_some_and(|nbf| now < nbf) {
+            ValidityStatus::NotYetValid
+        } else if self.is_expired(now) {
+            ValidityStatus::Expired
+        } else {
+            ValidityStatus::Valid
+        }
+    }
+}
+
+/// CBOR map keys understood in [Exemption] entries
+pub const EXEMPTION_KEYS: &[&str] = &["ci", "co", "df", "du", "is", "rs", "tg"];
+
+/// Attests that an individual is exempted from vaccination for a given disease.
+/// This is a national extension to the EU specification, only parsed when [ParseOptions::exemptions] is set.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Exemption {
+    /// Certificate ID
+    pub cert_id: String, // ci
+
+    /// Member State which issued the exemption
+    pub country: Country, // co
+
+    /// Targeted disease
+    pub disease: String, // tg
+
+    /// Issuing entity
+    pub issuer: String, // is
+
+    /// Reason for the exemption, if stated
+    pub reason: Option<String>, // rs
+
+    /// Exemption validity start date
+    pub valid_from: Dated, // df
+
+    /// Exemption validity expire date
+    pub valid_until: Dated, // du
+}
+
+impl TryFrom<BTreeMap<String, Value>> for Exemption {
+    type Error = Error;
+
+    fn try_from(mut values: BTreeMap<String, Value>) -> core::result::Result<Self, Self::Error> {
+        let cert_id = extract_string(&mut values, "ci")?;
+        let country = extract_string(&mut values, "co")?.into();
+        let disease = extract_string(&mut values, "tg")?;
+        let issuer = extract_string(&mut values, "is")?;
+        let reason = extract_opt_string(&mut values, "rs")?;
+        let valid_from = extract_date(&mut values, "df")?;
+        let valid_until = extract_date(&mut values, "du")?;
+
+        let ex = Exemption {
+            cert_id,
+            country,
+            disease,
+            issuer,
+            reason,
+            valid_from,
+            valid_until,
+        };
+
+        map_empty!(values);
+
+        Ok(ex)
     }
 }
 
-/// Represents the signature and signature metadata for a [HealthCert].
-#[derive(Debug, PartialEq)]
-pub struct Signature {
-    /// Key id
-    pub kid: Vec<u8>,
-
-    /// Algorithm used for signing
-    pub algorithm: i128,
+/// Marks a Swiss certificate light, which carries no health data and only attests that the holder had a valid
+/// certificate when it was issued. Its validity window is the one of the whole bundle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Light {
+    /// Certificate light validity start
+    pub valid_from: DateTime<Utc>, // iat
 
-    /// Raw signature
-    pub signature: Vec<u8>,
+    /// Certificate light validity end
+    pub valid_until: DateTime<Utc>, // exp
 }
 
-/// Represents the whole certificate blob
-#[derive(Debug, PartialEq)]
-pub struct HealthCert {
-    // Member country that issued the bundle (might be missing)
-    pub some_issuer: Option<String>,
-
-    /// Bundle creation timestamp
-    pub created: DateTime<Utc>,
-
-    /// Bundle expiration timestamp
-    pub expires: DateTime<Utc>,
-
-    /// List of passes contained in this bundle
-    pub passes: Vec<GreenPass>,
-
-    /// Raw signature
-    pub signature: Signature,
-}
+/// CBOR map keys understood in [Recovery] entries
+pub const RECOVERY_KEYS: &[&str] = &["ci", "co", "df", "du", "fr", "is", "tg"];
 
 /// Attests the full recovery from a given disease
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct Recovery {
     /// Certificate ID
     pub cert_id: String, // ci
 
     /// Member State where the test was performed
-    pub country: String, // co
+    pub country: Country, // co
 
     /// Date of diagnosis
-    pub diagnosed: NaiveDate, // fr
+    pub diagnosed: Dated, // fr
 
     /// String that identifies the contracted disease
     pub disease: String, // tg
@@ -268,18 +1414,18 @@ pub struct Recovery {
     pub issuer: String, // is
 
     /// Recovery attestation validity start date
-    pub valid_from: NaiveDate, // df
+    pub valid_from: Dated, // df
 
     /// Recovery attestation validity expire date
-    pub valid_until: NaiveDate, // du
+    pub valid_until: Dated, // du
 }
 
 impl TryFrom<BTreeMap<String, Value>> for Recovery {
     type Error = Error;
 
-    fn try_from(mut values: BTreeMap<String, Value>) -> std::result::Result<Self, Self::Error> {
+    fn try_from(mut values: BTreeMap<String, Value>) -> core::result::Result<Self, Self::Error> {
         let cert_id = extract_string(&mut values, "ci")?;
-        let country = extract_string(&mut values, "co")?;
+        let country = extract_string(&mut values, "co")?.into();
         let diagnosed = extract_date(&mut values, "fr")?;
         let disease = extract_string(&mut values, "tg")?;
         let issuer = extract_string(&mut values, "is")?;
@@ -302,8 +1448,19 @@ impl TryFrom<BTreeMap<String, Value>> for Recovery {
     }
 }
 
+impl Recovery {
+    /// Returns true if `date` falls within the validity window of this attestation, bounds included.
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.valid_from.parsed <= date && date <= self.valid_until.parsed
+    }
+}
+
+/// CBOR map keys understood in [Test] entries. Only one of `ma` and `nm` is expected in each entry.
+/// Schemas older than 1.3.0 also allow `dr`, see [SchemaVersion::allows_test_result_date].
+pub const TEST_KEYS: &[&str] = &["ci", "co", "is", "ma", "nm", "sc", "tc", "tg", "tr", "tt"];
+
 /// Attests that a test for a given disease has been conducted.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct Test {
     /// Certificate ID
     pub cert_id: String, // ci
@@ -312,7 +1469,7 @@ pub struct Test {
     pub collect_ts: DateTime<FixedOffset>, // sc
 
     /// Member State where the test was performed
-    pub country: String, // co
+    pub country: Country, // co
 
     /// Target disease
     pub disease: String, // tg
@@ -329,17 +1486,27 @@ pub struct Test {
     /// Coded string value identifying the testing method
     pub test_type: String, // tt
 
-    /// Name of the centre that conducted the test
-    pub testing_centre: String, // tc
+    /// Name of the centre that conducted the test, always present before schema 1.3.0
+    pub testing_centre: Option<String>, // tc
+
+    /// Date and time when the test result was produced, only found in schemas older than 1.3.0
+    pub result_ts: Option<DateTime<FixedOffset>>, // dr
 }
 
 impl TryFrom<BTreeMap<String, Value>> for Test {
     type Error = Error;
 
-    fn try_from(mut values: BTreeMap<String, Value>) -> std::result::Result<Self, Self::Error> {
+    fn try_from(values: BTreeMap<String, Value>) -> core::result::Result<Self, Self::Error> {
+        Test::from_map(values, &SchemaVersion::LATEST)
+    }
+}
+
+impl Test {
+    /// Builds a test from its decoded CBOR map, following the field requirements of the given schema version.
+    pub fn from_map(mut values: BTreeMap<String, Value>, version: &SchemaVersion) -> Result<Self> {
         let cert_id = extract_string(&mut values, "ci")?;
         let collect_ts = extract_isodatetime(&mut values, "sc")?;
-        let country = extract_string(&mut values, "co")?;
+        let country = extract_string(&mut values, "co")?.into();
         let disease = extract_string(&mut values, "tg")?;
         let issuer = extract_string(&mut values, "is")?;
 
@@ -353,7 +1520,18 @@ impl TryFrom<BTreeMap<String, Value>> for Test {
 
         let result = extract_string(&mut values, "tr")?;
         let test_type = extract_string(&mut values, "tt")?;
-        let testing_centre = extract_string(&mut values, "tc")?;
+        let testing_centre = if version.requires_testing_centre() {
+            Some(extract_string(&mut values, "tc")?)
+        } else {
+            extract_opt_string(&mut values, "tc")?
+        };
+
+        // left in the map for newer schemas, so that it is rejected as spurious data
+        let result_ts = if version.allows_test_result_date() && values.contains_key("dr") {
+            Some(extract_isodatetime(&mut values, "dr")?)
+        } else {
+            None
+        };
 
         let ts = Test {
             cert_id,
@@ -365,25 +1543,59 @@ impl TryFrom<BTreeMap<String, Value>> for Test {
             result,
             test_type,
             testing_centre,
+            result_ts,
         };
 
         map_empty!(values);
 
         Ok(ts)
     }
+
+    /// Classifies the result code of this test.
+    pub fn outcome(&self) -> TestResult {
+        TestResult::from_code(&self.result)
+    }
+
+    /// Classifies the testing method of this test from its `tt` code, which is authoritative over [Test::name].
+    pub fn test_type_kind(&self) -> TestType {
+        TestType::from_code(&self.test_type)
+    }
+
+    /// Sample collection time, converted to UTC.
+    pub fn collect_ts_utc(&self) -> DateTime<Utc> {
+        self.collect_ts.with_timezone(&Utc)
+    }
+
+    /// Returns false if the timestamps of this test are impossible for a certificate issued at `issued` (usually
+    /// [HealthCert::created]), which hints at a forged or corrupted certificate.
+    ///
+    /// The samples must have been collected no later than the issue time, and the result, if stated, produced between
+    /// the collection and the issue time. Timestamps are compared in UTC and exactly, without any allowance for clock
+    /// skew.
+    pub fn is_timestamp_plausible(&self, issued: DateTime<Utc>) -> bool {
+        let collected = self.collect_ts_utc();
+
+        collected <= issued
+            && self
+                .result_ts
+                .is_none_or(|result| collected <= result && result <= issued)
+    }
 }
 
+/// CBOR map keys understood in [Vaccine] entries
+pub const VACCINE_KEYS: &[&str] = &["ci", "co", "dn", "dt", "is", "ma", "mp", "sd", "tg", "vp"];
+
 /// Attests that an individual has been vaccinated for a given disease.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub struct Vaccine {
     /// Certificate ID
     pub cert_id: String, // ci
 
     /// Vaccination country
-    pub country: String, // co
+    pub country: Country, // co
 
     /// Vaccination date
-    pub date: NaiveDate, // dt
+    pub date: Dated, // dt
 
     /// Targeted disease
     pub disease: String, // tg
@@ -405,24 +1617,54 @@ pub struct Vaccine {
 
     /// Type of vaccine or prophylaxis used as defined in EUDCC Gateway
     pub prophylaxis_kind: String, // vp
+
+    /// Keys outside of the specification with their CBOR-encoded values, only kept with [ParseOptions::extra_keys]
+    #[serde(
+        serialize_with = "serialize_hex_map",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub extra: BTreeMap<String, Vec<u8>>,
 }
 
 impl TryFrom<BTreeMap<String, Value>> for Vaccine {
     type Error = Error;
 
-    fn try_from(mut values: BTreeMap<String, Value>) -> std::result::Result<Self, Self::Error> {
+    fn try_from(values: BTreeMap<String, Value>) -> core::result::Result<Self, Self::Error> {
+        Vaccine::from_map(values, &ParseOptions::default())
+    }
+}
+
+impl Vaccine {
+    /// Builds a vaccine entry from its decoded CBOR map, using the given options.
+    pub fn from_map(mut values: BTreeMap<String, Value>, opts: &ParseOptions) -> Result<Self> {
         let cert_id = extract_string(&mut values, "ci")?;
-        let country = extract_string(&mut values, "co")?;
+        let country = extract_string(&mut values, "co")?.into();
         let date = extract_date(&mut values, "dt")?;
         let disease = extract_string(&mut values, "tg")?;
-        let dose_number = extract_int(&mut values, "dn")? as usize;
-        let dose_total = extract_int(&mut values, "sd")? as usize;
+        let coerce = opts.coerce_types && !opts.strict;
+
+        let dose_number = extract_uint(&mut values, "dn", coerce)?;
+        let dose_total = extract_uint(&mut values, "sd", coerce)?;
         let issuer = extract_string(&mut values, "is")?;
         let market_auth = extract_string(&mut values, "ma")?;
         let product = extract_string(&mut values, "mp")?;
         let prophylaxis_kind = extract_string(&mut values, "vp")?;
 
-        let gp = Vaccine {
+        if !opts.extra_keys || opts.strict {
+            map_empty!(values);
+        }
+
+        let extra = values
+            .into_iter()
+            .map(|(k, v)| {
+                let mut buf = Vec::new();
+                ciborium::ser::into_writer(&v, &mut buf).expect("writing to a Vec cannot fail");
+
+                (k, buf)
+            })
+            .collect();
+
+        Ok(Vaccine {
             cert_id,
             country,
             date,
@@ -433,11 +1675,144 @@ impl TryFrom<BTreeMap<String, Value>> for Vaccine {
             market_auth,
             product,
             prophylaxis_kind,
+            extra,
+        })
+    }
+}
+
+/// Interpretation of the dose counters of a [Vaccine] entry
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DoseInfo {
+    /// Number of the dose in the series
+    pub number: usize,
+
+    /// Total number of doses in the series
+    pub total: usize,
+
+    /// The primary series has been completed
+    pub is_complete: bool,
+
+    /// The dose is a booster, given after the primary series has been completed
+    pub is_booster: bool,
+}
+
+impl Vaccine {
+    /// Interprets the dose counters of this entry.
+    ///
+    /// A dose number greater than the total is valid and denotes a booster; a zero dose number or total is not, and
+    /// results in [Error::InvalidDoses].
+    pub fn dose_info(&self) -> Result<DoseInfo> {
+        let (number, total) = (self.dose_number, self.dose_total);
+
+        if number == 0 || total == 0 {
+            return Err(Error::InvalidDoses { number, total });
+        }
+
+        Ok(DoseInfo {
+            number,
+            total,
+            is_complete: number >= total,
+            is_booster: number > total,
+        })
+    }
+
+    /// Cross-checks the medicinal product against the manufacturer and the kind of prophylaxis, returning the fields
+    /// that do not match it. Unknown products are never reported, nor are the fields of products whose value sets
+    /// carry no information about them.
+    pub fn inconsistencies(&self) -> Vec<VaccineMismatch> {
+        let mut mismatches = Vec::new();
+
+        let product = match VaccineMedicinalProduct::from_code(&self.product) {
+            Some(product) => product,
+            None => return mismatches,
         };
 
-        map_empty!(values);
+        let manufacturers = product.manufacturers();
 
-        Ok(gp)
+        if !manufacturers.is_empty()
+            && !Manufacturer::from_code(&self.market_auth)
+                .is_some_and(|ma| manufacturers.contains(&ma))
+        {
+            mismatches.push(VaccineMismatch::Manufacturer(self.market_auth.clone()));
+        }
+
+        // J07BX03 just means "covid-19 vaccines", and is accepted for any product
+        let prophylaxis_ok = match VaccineProphylaxis::from_code(&self.prophylaxis_kind) {
+            Some(VaccineProphylaxis::Vaccine) => true,
+            Some(VaccineProphylaxis::MRNA) => product.is_mrna(),
+            Some(VaccineProphylaxis::Antigen) => !product.is_mrna(),
+            None => false,
+        };
+
+        if !prophylaxis_ok {
+            mismatches.push(VaccineMismatch::Prophylaxis(self.prophylaxis_kind.clone()));
+        }
+
+        mismatches
+    }
+
+    /// Returns true if the medicinal product, manufacturer and kind of prophylaxis agree with each other.
+    /// See [Vaccine::inconsistencies] for details.
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies().is_empty()
+    }
+
+    /// Returns whether the medicinal product is centrally authorized in the EU, see
+    /// [VaccineAuthorizationStatus::is_eu_authorized], or `None` if the product is not in the value set.
+    pub fn is_eu_authorized(&self) -> Option<bool> {
+        VaccineMedicinalProduct::from_code(&self.product).map(|mp| mp.values().2.is_eu_authorized())
+    }
+}
+
+/// A field of a [Vaccine] entry that does not match its medicinal product
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VaccineMismatch {
+    /// The manufacturer (`ma`) does not produce the medicinal product
+    Manufacturer(String),
+
+    /// The kind of prophylaxis (`vp`) does not match the medicinal product
+    Prophylaxis(String),
+}
+
+// Parses the entries of the `group` array (i.e. `v`). Keys in errors are prefixed with the position of the entry, as in
+// `v[1].dn`, to tell which entry is malformed.
+fn parse_entries(
+    group: &str,
+    entries: Vec<Value>,
+    parse: impl Fn(BTreeMap<String, Value>) -> Result<CertInfo>,
+) -> Result<Vec<CertInfo>> {
+    entries
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let values = match v {
+                Value::Map(_) => to_strmap(group, v)?,
+                _ => {
+                    return Err(Error::InvalidFormatFor {
+                        key: entry_path(group, i),
+                    })
+                }
+            };
+
+            parse(values).map_err(|e| entry_error(group, i, e))
+        })
+        .collect()
+}
+
+// Path of the `i`-th entry of a group in errors, i.e. `v[0]`. Paths are only needed for errors, so they are not
+// formatted for valid entries
+pub(crate) fn entry_path(group: &str, i: usize) -> String {
+    format!("{}[{}]", group, i)
+}
+
+// Prefixes the key an error about a field of an entry refers to with the path of the entry, i.e. `v[0].dn`
+pub(crate) fn entry_error(group: &str, i: usize, e: Error) -> Error {
+    let field = |key| format!("{}.{}", entry_path(group, i), key);
+
+    match e {
+        Error::InvalidFormatFor { key } => Error::InvalidFormatFor { key: field(key) },
+        Error::MissingKey(key) => Error::MissingKey(field(key)),
+        e => e,
     }
 }
 
@@ -454,33 +1829,293 @@ fn to_strmap(desc: &str, v: Value) -> Result<BTreeMap<String, Value>> {
     }
 }
 
+// Characters allowed in a Base45 payload. Payloads are checked one character at a time, so this is a match rather
+// than a lookup in the charset string
+fn is_base45(b: u8) -> bool {
+    matches!(b, b'0'..=b'9' | b'A'..=b'Z' | b' ' | b'$' | b'%' | b'*' | b'+' | b'-' | b'.' | b'/' | b':')
+}
+
+#[cfg(feature = "std")]
+fn is_base45_char(c: char) -> bool {
+    u8::try_from(c).is_ok_and(is_base45)
+}
+
+/// Locates a `HC1:` string within arbitrary bytes, such as the framed output of a QR scanner, without requiring the
+/// rest of the buffer to be valid UTF-8.
+///
+/// The returned string starts with the first `HC1:` marker and ends right before the first byte that cannot be part of
+/// a Base45 payload, with trailing spaces removed.
+///
+/// ```
+/// let framed = b"\x02\xffHC1:NCFOXN%TS3DH3ZSUZK+.V0ET\r\n\x03";
+///
+/// assert_eq!(greenpass::extract_hc1(framed), Some("HC1:NCFOXN%TS3DH3ZSUZK+.V0ET"));
+/// ```
+pub fn extract_hc1(data: &[u8]) -> Option<&str> {
+    const HCID: &[u8] = b"HC1:";
+
+    let start = data.windows(HCID.len()).position(|w| w == HCID)?;
+    let rest = &data[start + HCID.len()..];
+
+    let len = rest
+        .iter()
+        .position(|&b| !is_base45(b))
+        .unwrap_or(rest.len());
+
+    // the marker and the Base45 charset are ASCII, so this can't fail
+    let hc1 = core::str::from_utf8(&data[start..start + HCID.len() + len]).ok()?;
+
+    Some(hc1.trim_end_matches(' '))
+}
+
+// Base45 decoding only reports that the payload is invalid, so check for common issues beforehand
+#[cfg(feature = "std")]
+fn check_base45(payload: &str, offset: usize) -> Result<()> {
+    if let Some((i, found)) = payload
+        .chars()
+        .enumerate()
+        .find(|&(_, c)| !is_base45_char(c))
+    {
+        return Err(Base45Error::InvalidCharacter {
+            position: offset + i,
+            found,
+        }
+        .into());
+    }
+
+    // every 3 characters encode 2 bytes, and a trailing byte is encoded with 2
+    if payload.len() % 3 == 1 {
+        return Err(Base45Error::Truncated { len: payload.len() }.into());
+    }
+
+    Ok(())
+}
+
+// Undoes the mangling payloads go through when pasted from URLs or chat apps.
+// Both `%` and spaces are part of the Base45 charset, so a payload is only percent-decoded if its prefix is encoded as
+// `HC1%3A`, which a valid payload can never start with, and line breaks and tabs are removed while spaces are kept.
+#[cfg(feature = "std")]
+fn normalize_hc1(data: &str) -> Cow<'_, str> {
+    let trimmed = data.trim_start_matches(|c: char| c == '\u{feff}' || c.is_whitespace());
+
+    let data = match trimmed.get(..6) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("HC1%3A") => {
+            Cow::Owned(percent_decode(trimmed))
+        }
+        _ => Cow::Borrowed(data),
+    };
+
+    let is_break = |c: char| c.is_whitespace() && c != ' ';
+    let payload = data.trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+
+    if payload.contains(is_break) {
+        Cow::Owned(payload.replace(is_break, ""))
+    } else {
+        data
+    }
+}
+
+// Decodes `%XX` sequences, leaving malformed ones untouched. `+` is kept as is, as it is a Base45 character.
+#[cfg(feature = "std")]
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| core::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(b) => {
+                decoded.push(b);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+// Strips the HC1 prefix, then decodes and inflates the Base45 payload into the raw COSE data.
+// Scanners may prepend a BOM or whitespace to the prefix, and append whitespace or control characters such as NUL
+// padding or a group separator to the payload, so anything outside of the Base45 charset is ignored at the end.
+#[cfg(feature = "std")]
+fn decode_hc1(data: &str) -> Result<Vec<u8>> {
+    const HCID: &str = "HC1:";
+
+    let normalized = normalize_hc1(data);
+    let data = &*normalized;
+
+    let mut rest = data
+        .trim_start_matches(|c: char| c == '\u{feff}' || c.is_whitespace())
+        .strip_prefix(HCID)
+        .ok_or(Error::MissingHCID)?;
+
+    // scanners and copy-pasting sometimes repeat the prefix, which no zlib stream starts with once Base45 encoded
+    while let Some(stripped) = rest.trim_start().strip_prefix(HCID) {
+        rest = stripped;
+    }
+
+    let payload = rest
+        .trim_end_matches(|c: char| c.is_whitespace() || !is_base45_char(c))
+        .trim_start();
+
+    // position of the first payload character in the input, so that errors point to the original string
+    let offset = data[..data.len() - rest.trim_start().len()].chars().count();
+
+    check_base45(payload, offset)?;
+
+    let defl = stage!(
+        "base45",
+        input_len = payload.len(),
+        base45::decode(payload).map_err(Base45Error::from)
+    )?;
+
+    let data = stage!("inflate", input_len = defl.len(), {
+        // the sample certificates inflate to about their compressed size, so twice that is only an initial guess which
+        // payloads that compress better, such as bundles with many passes, grow past as needed; the bufread decoders
+        // read from the slice directly, without copying it through a buffer of their own
+        let mut data = Vec::with_capacity(defl.len() * 2);
+
+        // some issuers omit the zlib header, which always starts with 0x78 for the window size used by deflate
+        if defl.first() == Some(&0x78) {
+            ZlibDecoder::new(&defl[..]).read_to_end(&mut data)
+        } else {
+            DeflateDecoder::new(&defl[..]).read_to_end(&mut data)
+        }
+        .map(|_| data)
+    })?;
+
+    Ok(data)
+}
+
+// Same as decode_hc1, for data that may not be valid UTF-8 outside of the certificate, such as the raw contents of
+// a QR code encoded in byte mode
+#[cfg(feature = "std")]
+fn decode_hc1_bytes(data: &[u8]) -> Result<Vec<u8>> {
+    const BOM: &[u8] = "\u{feff}".as_bytes();
+
+    let data = data.strip_prefix(BOM).unwrap_or(data);
+
+    let start = data
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(data.len());
+    let end = data
+        .iter()
+        .rposition(|&b| b != b' ' && is_base45(b))
+        .map_or(start, |i| i + 1);
+
+    let data = &data[start..end.max(start)];
+
+    // a valid payload is always ASCII, any other byte is reported as an invalid Base45 character. The prefix is checked
+    // by decode_hc1, after the same normalization as the str path, so that percent-encoded payloads are accepted too
+    decode_hc1(&String::from_utf8_lossy(data))
+}
+
+#[cfg(feature = "std")]
 impl TryFrom<&str> for HealthCert {
     type Error = Error;
 
-    fn try_from(data: &str) -> std::result::Result<Self, Self::Error> {
-        const HCID: &str = "HC1:";
+    fn try_from(data: &str) -> core::result::Result<Self, Self::Error> {
+        HealthCert::from_cose_bytes(&decode_hc1(data)?)
+    }
+}
 
-        if !data.starts_with(HCID) {
-            return Err(Error::MissingHCID);
-        }
+#[cfg(feature = "std")]
+impl core::str::FromStr for HealthCert {
+    type Err = Error;
 
-        let defl = base45::decode(data[HCID.len()..].trim())?;
+    fn from_str(s: &str) -> Result<Self> {
+        HealthCert::try_from(s)
+    }
+}
 
-        let mut dec = ZlibDecoder::new(&defl as &[u8]);
+#[cfg(feature = "std")]
+impl TryFrom<&[u8]> for HealthCert {
+    type Error = Error;
 
-        let mut data = Vec::new();
-        dec.read_to_end(&mut data)?;
+    fn try_from(data: &[u8]) -> core::result::Result<Self, Self::Error> {
+        HealthCert::from_cose_bytes(&decode_hc1_bytes(data)?)
+    }
+}
 
-        let cwt = ciborium::de::from_reader(&data[..])?;
+#[cfg(feature = "std")]
+impl HealthCert {
+    /// Encodes the certificate back into an `HC1:` string, from its COSE_Sign1 components (see
+    /// [HealthCert::cose_sign1]). The signed data is kept as is, so the result verifies whenever the original did, but
+    /// it is not necessarily identical to the string the certificate was parsed from.
+    pub fn to_hc1(&self) -> String {
+        use flate2::{write::ZlibEncoder, Compression};
+        use std::io::Write;
+
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::best());
+        zlib.write_all(&self.cose_sign1().to_cbor())
+            .and_then(|_| zlib.finish())
+            .map(|defl| format!("HC1:{}", base45::encode_from_buffer(defl)))
+            .expect("compressing to memory cannot fail")
+    }
+}
 
-        let Cwt(cwt_arr) = cwt;
+impl HealthCert {
+    /// Decodes a certificate from the raw COSE_Sign1 structure, i.e. the data left after stripping the `HC1:` prefix,
+    /// decoding the Base45 payload and inflating it.
+    /// This is useful when the COSE bytes are obtained directly, i.e. from an NFC read, and is the only entry point
+    /// available without the `std` feature.
+    pub fn from_cose_bytes(data: &[u8]) -> Result<Self> {
+        HealthCert::from_cose_bytes_with(data, &ParseOptions::default())
+    }
 
-        if cwt_arr.len() != 4 {
-            return Err(Error::MalformedCWT);
+    /// Returns the COSE_Sign1 structure this certificate has been decoded from, with the protected header and payload
+    /// exactly as received. Certificates that have not been obtained through parsing have empty headers and payload.
+    pub fn cose_sign1(&self) -> CoseSign1 {
+        CoseSign1 {
+            protected_raw: self.raw.protected.clone(),
+            unprotected: self.raw.unprotected.clone(),
+            payload: self.raw.payload.clone(),
+            signature: self.signature.signature.clone(),
         }
+    }
+
+    /// Same as [HealthCert::from_cose_bytes], using the given options.
+    pub fn from_cose_bytes_with(data: &[u8], opts: &ParseOptions) -> Result<Self> {
+        HealthCert::decode(data, opts, None)
+    }
+
+    /// Same as [HealthCert::from_cose_bytes_with], but malformed passes are skipped instead of failing the whole
+    /// bundle. The returned certificate only holds the valid passes, and the errors for the others are returned
+    /// alongside it.
+    pub fn from_cose_bytes_lenient(data: &[u8], opts: &ParseOptions) -> Result<(Self, Vec<Error>)> {
+        let mut errors = Vec::new();
 
-        let protected_properties: RawHeader = match &cwt_arr[0] {
-            Value::Bytes(_bys) => ciborium::de::from_reader(&_bys[..])?,
+        HealthCert::decode(data, opts, Some(&mut errors)).map(|hc| (hc, errors))
+    }
+
+    // Decodes a bundle; if `pass_errors` is set, errors in single passes are collected there instead of being returned
+    fn decode(
+        data: &[u8],
+        opts: &ParseOptions,
+        pass_errors: Option<&mut Vec<Error>>,
+    ) -> Result<Self> {
+        let root: Value = stage!("cose", input_len = data.len(), from_cbor(data))?;
+        let Cwt(mut cwt_arr) = Cwt::try_from(root)?;
+
+        // the raw fields are moved out of the array rather than cloned, as it is dropped once decoding is done
+        let (protected_raw, protected_properties): (_, RawHeader) = match &mut cwt_arr[0] {
+            Value::Bytes(bys) => {
+                let header = from_cbor(&bys[..])?;
+
+                (core::mem::take(bys), header)
+            }
             _ => {
                 return Err(Error::InvalidFormatFor {
                     key: "protected properties".into(),
@@ -488,8 +2123,10 @@ impl TryFrom<&str> for HealthCert {
             }
         };
 
-        let unprotected_properties = match &cwt_arr[1] {
-            Value::Map(map) => map,
+        check_critical(&protected_properties.0)?;
+
+        let unprotected_properties = match &mut cwt_arr[1] {
+            Value::Map(map) => core::mem::take(map),
             _ => {
                 return Err(Error::InvalidFormatFor {
                     key: "unprotected properties".into(),
@@ -497,8 +2134,12 @@ impl TryFrom<&str> for HealthCert {
             }
         };
 
-        let RawCert(mut cert_map) = match &cwt_arr[2] {
-            Value::Bytes(bys) => ciborium::de::from_reader(&bys[..])?,
+        let (payload_raw, RawCert(mut cert_map)) = match &mut cwt_arr[2] {
+            Value::Bytes(bys) => {
+                let cert = stage!("cwt", input_len = bys.len(), from_cbor(&bys[..]))?;
+
+                (core::mem::take(bys), cert)
+            }
             _ => {
                 return Err(Error::InvalidFormatFor {
                     key: "root cert".into(),
@@ -506,9 +2147,20 @@ impl TryFrom<&str> for HealthCert {
             }
         };
 
+        if opts.strict {
+            let payload: Value = from_cbor(&payload_raw[..])?;
+
+            check_duplicates(&payload, "")?;
+        }
+
         let some_issuer = if let Some(iss_v) = cert_map.remove(&1) {
             match iss_v {
-                Value::Text(iss) => Some(iss),
+                Value::Text(iss) => {
+                    let iss = Country::from(iss);
+                    check_country(&iss, opts)?;
+
+                    Some(iss)
+                }
                 _ => {
                     return Err(Error::InvalidFormatFor {
                         key: "issuing country".into(),
@@ -523,7 +2175,7 @@ impl TryFrom<&str> for HealthCert {
             .remove(&4isize)
             .ok_or_else(|| Error::MissingKey("expiration timestamp".into()))?
         {
-            Value::Integer(ts) => Utc.timestamp(i128::from(ts) as i64, 0),
+            Value::Integer(ts) => timestamp(ts.into())?,
             _ => {
                 return Err(Error::InvalidFormatFor {
                     key: "expiration timestamp".into(),
@@ -535,7 +2187,7 @@ impl TryFrom<&str> for HealthCert {
             .remove(&6isize)
             .ok_or_else(|| Error::MissingKey("issue timestamp".into()))?
         {
-            Value::Integer(ts) => Utc.timestamp(i128::from(ts) as i64, 0),
+            Value::Integer(ts) => timestamp(ts.into())?,
             _ => {
                 return Err(Error::InvalidFormatFor {
                     key: "issue timestamp".into(),
@@ -543,14 +2195,37 @@ impl TryFrom<&str> for HealthCert {
             }
         };
 
-        let hcerts = match cert_map
-            .remove(&-260isize)
-            .ok_or_else(|| Error::MissingKey("hcert".into()))?
-        {
-            Value::Map(hcmap) => hcmap
-                .into_iter()
-                .map(|(_, v)| to_strmap("hcert", v))
-                .collect::<Result<Vec<_>>>()?,
+        let not_before = match cert_map.remove(&5isize) {
+            Some(Value::Integer(ts)) => Some(timestamp(ts.into())?),
+            Some(_) => {
+                return Err(Error::InvalidFormatFor {
+                    key: "not before timestamp".into(),
+                })
+            }
+            None => None,
+        };
+
+        let cwt_id = match cert_map.remove(&7isize) {
+            Some(Value::Bytes(cti)) => Some(cti),
+            Some(_) => {
+                return Err(Error::InvalidFormatFor {
+                    key: "CWT ID".into(),
+                })
+            }
+            None => None,
+        };
+
+        // Swiss certificates light store their passes under their own claim
+        let (hcerts, light) = match (cert_map.remove(&-260isize), cert_map.remove(&-250isize)) {
+            (Some(Value::Map(hcmap)), None) => (hcmap, None),
+            (None, Some(Value::Map(lightmap))) => (
+                lightmap,
+                Some(Light {
+                    valid_from: created,
+                    valid_until: expires,
+                }),
+            ),
+            (None, None) => return Err(Error::MissingKey("hcert".into())),
             _ => {
                 return Err(Error::InvalidFormatFor {
                     key: "hcert".into(),
@@ -558,13 +2233,28 @@ impl TryFrom<&str> for HealthCert {
             }
         };
 
-        let passes = hcerts
-            .into_iter()
-            .map(GreenPass::try_from)
-            .collect::<Result<Vec<_>>>()?;
+        if hcerts.is_empty() {
+            return Err(Error::MissingKey("hcert entries".into()));
+        }
+
+        let passes = stage!("mapping", passes = hcerts.len(), {
+            let passes = hcerts.into_iter().map(|(_, v)| {
+                to_strmap("hcert", v).and_then(|values| match light {
+                    Some(light) => GreenPass::from_light_map(values, light, opts),
+                    None => GreenPass::from_map(values, opts),
+                })
+            });
+
+            match pass_errors {
+                Some(errors) => Ok(passes
+                    .filter_map(|pass| pass.map_err(|e| errors.push(e)).ok())
+                    .collect()),
+                None => passes.collect::<Result<Vec<_>>>(),
+            }
+        })?;
 
-        let signature = match &cwt_arr[3] {
-            Value::Bytes(bys) => bys.clone(),
+        let signature = match &mut cwt_arr[3] {
+            Value::Bytes(bys) => core::mem::take(bys),
             _ => {
                 return Err(Error::InvalidFormatFor {
                     key: "signature".into(),
@@ -587,7 +2277,7 @@ impl TryFrom<&str> for HealthCert {
                     Err(Error::InvalidFormatFor { key: "KID".into() })
                 }
             });
-            
+
         // If the unprotected properties don't contain a KID, try with the protected properties
         let kid = kid.or_else(|_| {
             match protected_properties
@@ -611,24 +2301,36 @@ impl TryFrom<&str> for HealthCert {
             }
         };
 
+        let counter_signature = header_value(&unprotected_properties, 7)
+            .map(CounterSignature::from_cbor)
+            .transpose()?;
+
         let signature = Signature {
             kid,
             algorithm,
             signature,
+            counter_signature,
         };
 
         Ok(HealthCert {
             some_issuer,
             created,
             expires,
+            not_before,
+            cwt_id,
             passes,
             signature,
+            raw: RawCose {
+                protected: protected_raw,
+                unprotected: unprotected_properties,
+                payload: payload_raw,
+            },
         })
     }
 }
 
-/// Parses a Base45 CBOR Web Token containing a EU Health Certificate. No signature validation is currently performed by
-/// this crate.
+/// Parses a Base45 CBOR Web Token containing a EU Health Certificate. The signature is not checked; use
+/// [HealthCert::verify_with] to validate it against a [TrustList].
 ///
 /// ```no_run
 /// use std::{error::Error, fs::read_to_string};
@@ -644,6 +2346,94 @@ impl TryFrom<&str> for HealthCert {
 ///     Ok(())
 /// }
 /// ```
+#[cfg(feature = "std")]
 pub fn parse(data: &str) -> Result<HealthCert> {
     HealthCert::try_from(data)
 }
+
+/// Decodes the `HC1:` string in `data` into the raw COSE_Sign1 bytes it holds, stripping the prefix, decoding the
+/// Base45 payload and inflating it, without parsing the result as CBOR.
+///
+/// This is the first half of [parse], useful to inspect certificates with external tools or to handle the CBOR data
+/// separately; the bytes can be parsed later with [HealthCert::from_cose_bytes].
+///
+/// ```no_run
+/// let cose = greenpass::decode_payload("HC1:...").unwrap();
+///
+/// std::fs::write("cert.cbor", cose).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn decode_payload(data: &str) -> Result<Vec<u8>> {
+    decode_hc1(data)
+}
+
+/// Same as [parse], for input that may not be valid UTF-8, such as the contents of a QR code encoded in byte mode.
+///
+/// Leading whitespace and BOMs are skipped, as are trailing NUL bytes. Error positions are relative to the start of the
+/// `HC1:` prefix.
+///
+/// ```no_run
+/// let health_cert = greenpass::parse_bytes(b"HC1:...\0\0");
+/// ```
+#[cfg(feature = "std")]
+pub fn parse_bytes(data: &[u8]) -> Result<HealthCert> {
+    HealthCert::try_from(data)
+}
+
+/// Same as [parse], using the given options to accept non-standard certificates.
+///
+/// ```no_run
+/// use greenpass::ParseOptions;
+///
+/// let opts = ParseOptions {
+///     exemptions: true,
+///     ..Default::default()
+/// };
+///
+/// let health_cert = greenpass::parse_with("HC1:...", &opts);
+/// ```
+#[cfg(feature = "std")]
+pub fn parse_with(data: &str, opts: &ParseOptions) -> Result<HealthCert> {
+    HealthCert::from_cose_bytes_with(&decode_hc1(data)?, opts)
+}
+
+/// Same as [parse], but a malformed pass does not cause the whole bundle to be rejected.
+///
+/// The returned certificate only contains the passes that could be parsed, together with the errors encountered
+/// parsing the others. Errors in the bundle itself, such as invalid Base45 or a missing signature, are still fatal.
+///
+/// ```no_run
+/// let (health_cert, errors) = greenpass::parse_lenient("HC1:...").unwrap();
+///
+/// for e in errors {
+///     eprintln!("skipped malformed pass: {}", e);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn parse_lenient(data: &str) -> Result<(HealthCert, Vec<Error>)> {
+    HealthCert::from_cose_bytes_lenient(&decode_hc1(data)?, &ParseOptions::default())
+}
+
+/// Parses every `HC1:` string in `input`, one per line.
+///
+/// Blank lines are skipped and surrounding whitespace is trimmed. Each result is paired with the line number (starting
+/// from 1) it was read from, so that a malformed payload does not prevent the others from being parsed.
+///
+/// ```no_run
+/// for (line, res) in greenpass::parse_many("HC1:...\nHC1:...") {
+///     match res {
+///         Ok(hc) => println!("{}: {}", line, hc),
+///         Err(e) => eprintln!("{}: {}", line, e),
+///     }
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn parse_many(input: &str) -> Vec<(usize, Result<HealthCert>)> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(n, line)| (n, parse(line)))
+        .collect()
+}
@@ -0,0 +1,91 @@
+// Opt-in cleanup of the values found in certificates, for consumers that compare or store them and do not care about
+// how the issuer wrote them.
+
+use alloc::string::{String, ToString};
+
+use chrono::NaiveDate;
+
+use crate::{CertInfo, Country, Dated, HealthCert};
+
+impl HealthCert {
+    /// Rewrites the values of this certificate in canonical form: country codes are uppercased and aliases replaced
+    /// with their official code (see [Country::canonical]), dates are written as `YYYY-MM-DD` and names and the issuers
+    /// of entries are trimmed of surrounding whitespace.
+    ///
+    /// Dates of birth which are not full dates (i.e. just the year) are only trimmed. The raw COSE data is left
+    /// untouched, so the certificate can still be verified; callers that need the values exactly as issued should
+    /// simply not call this.
+    pub fn normalize(&mut self) {
+        if let Some(issuer) = &mut self.some_issuer {
+            normalize_country(issuer);
+        }
+
+        for pass in &mut self.passes {
+            let dob = pass.date_of_birth.trim();
+
+            pass.date_of_birth = match NaiveDate::parse_from_str(dob, "%F") {
+                Ok(date) => date.format("%F").to_string(),
+                Err(_) => dob.to_string(),
+            };
+
+            for name in [
+                &mut pass.surname,
+                &mut pass.givenname,
+                &mut pass.std_givenname,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                trim(name);
+            }
+
+            trim(&mut pass.std_surname);
+
+            for ci in &mut pass.entries {
+                match ci {
+                    CertInfo::Exemption(e) => {
+                        normalize_country(&mut e.country);
+                        trim(&mut e.issuer);
+                        normalize_date(&mut e.valid_from);
+                        normalize_date(&mut e.valid_until);
+                    }
+                    CertInfo::Light(_) | CertInfo::Unknown { .. } => {}
+                    CertInfo::Recovery(r) => {
+                        normalize_country(&mut r.country);
+                        trim(&mut r.issuer);
+                        normalize_date(&mut r.diagnosed);
+                        normalize_date(&mut r.valid_from);
+                        normalize_date(&mut r.valid_until);
+                    }
+                    CertInfo::Test(t) => {
+                        normalize_country(&mut t.country);
+                        trim(&mut t.issuer);
+                    }
+                    CertInfo::Vaccine(v) => {
+                        normalize_country(&mut v.country);
+                        trim(&mut v.issuer);
+                        normalize_date(&mut v.date);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn normalize_country(country: &mut Country) {
+    let upper = Country::from(country.alpha2().trim().to_ascii_uppercase());
+
+    *country = upper.canonical().into();
+}
+
+fn normalize_date(date: &mut Dated) {
+    *date = date.parsed.into();
+}
+
+fn trim(s: &mut String) {
+    let trimmed = s.trim();
+
+    if trimmed.len() != s.len() {
+        *s = trimmed.to_string();
+    }
+}
@@ -0,0 +1,81 @@
+// Reports which DGC keys a pass was parsed from. Parsing rejects unknown keys and fails on missing required ones, so
+// the source keys can be told from the typed fields alone.
+
+use alloc::collections::BTreeSet;
+
+use crate::{CertInfo, GreenPass, TestName};
+
+const HOLDER_PATHS: &[&str] = &["dob", "ver"];
+
+const EXEMPTION_PATHS: &[&str] = &["e", "e.ci", "e.co", "e.df", "e.du", "e.is", "e.tg"];
+
+const RECOVERY_PATHS: &[&str] = &["r", "r.ci", "r.co", "r.df", "r.du", "r.fr", "r.is", "r.tg"];
+
+const TEST_PATHS: &[&str] = &["t", "t.ci", "t.co", "t.is", "t.sc", "t.tg", "t.tr", "t.tt"];
+
+const VACCINE_PATHS: &[&str] = &[
+    "v", "v.ci", "v.co", "v.dn", "v.dt", "v.is", "v.ma", "v.mp", "v.sd", "v.tg", "v.vp",
+];
+
+impl GreenPass {
+    /// Lists the DGC keys found in the source map of this pass, as paths such as `nam.gn` or `t.ma`.
+    ///
+    /// Required keys are always listed, while optional ones only if they were present. Entry keys are listed once
+    /// per kind of entry, however many entries hold them; certificates light only report the holder keys, and unknown
+    /// entries are not reported.
+    pub fn present_keys(&self) -> BTreeSet<&'static str> {
+        let mut keys: BTreeSet<_> = HOLDER_PATHS.iter().copied().collect();
+
+        // passes parsed without a `nam` map have no names at all
+        if self.surname.is_some()
+            || self.givenname.is_some()
+            || self.std_givenname.is_some()
+            || !self.std_surname.is_empty()
+        {
+            keys.extend(["nam", "nam.fnt"]);
+        }
+
+        for (key, present) in [
+            ("nam.fn", self.surname.is_some()),
+            ("nam.gn", self.givenname.is_some()),
+            ("nam.gnt", self.std_givenname.is_some()),
+        ] {
+            if present {
+                keys.insert(key);
+            }
+        }
+
+        for ci in &self.entries {
+            match ci {
+                CertInfo::Exemption(e) => {
+                    keys.extend(EXEMPTION_PATHS);
+
+                    if e.reason.is_some() {
+                        keys.insert("e.rs");
+                    }
+                }
+                CertInfo::Light(_) | CertInfo::Unknown { .. } => {}
+                CertInfo::Recovery(_) => keys.extend(RECOVERY_PATHS),
+                CertInfo::Test(t) => {
+                    keys.extend(TEST_PATHS);
+
+                    keys.insert(match t.name {
+                        TestName::NAAT { .. } => "t.nm",
+                        TestName::RAT { .. } => "t.ma",
+                    });
+
+                    if t.testing_centre.is_some() {
+                        keys.insert("t.tc");
+                    }
+
+                    if t.result_ts.is_some() {
+                        keys.insert("t.dr");
+                    }
+                }
+                CertInfo::Vaccine(_) => keys.extend(VACCINE_PATHS),
+            }
+        }
+
+        keys
+    }
+}
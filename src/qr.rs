@@ -0,0 +1,70 @@
+// Rendering of certificates as QR codes, i.e. to produce scannable mock certificates for tests and demos.
+
+use std::io::Cursor;
+
+use image::{GrayImage, ImageFormat, Luma};
+use qrcode::{bits::Bits, types::QrError, Color, EcLevel, QrCode, Version};
+
+use crate::{Error, HealthCert, Result};
+
+// Width of the blank margin around the code, in modules, as required by ISO/IEC 18004
+const QUIET_ZONE: u32 = 4;
+
+impl HealthCert {
+    /// Renders the certificate as a PNG image of a QR code holding its `HC1:` string, see [HealthCert::to_hc1].
+    ///
+    /// The image is `size` pixels wide and high, or as small as possible if `size` cannot fit the code at one pixel per
+    /// module. The code uses error correction level Q, as recommended for EU Digital COVID Certificates, and is
+    /// encoded in alphanumeric mode, which holds Base45 data without waste.
+    pub fn to_qr_png(&self, size: u32) -> Result<Vec<u8>> {
+        let code = alphanumeric_qr(&self.to_hc1())?;
+
+        let modules = code.width() as u32;
+        let scale = (size / (modules + 2 * QUIET_ZONE)).max(1);
+        let side = size.max((modules + 2 * QUIET_ZONE) * scale);
+        let margin = (side - modules * scale) / 2;
+
+        let colors = code.to_colors();
+        let img = GrayImage::from_fn(side, side, |x, y| {
+            let module = |p: u32| {
+                p.checked_sub(margin)
+                    .map(|p| p / scale)
+                    .filter(|&m| m < modules)
+            };
+
+            let dark = match (module(x), module(y)) {
+                (Some(x), Some(y)) => colors[(y * modules + x) as usize] == Color::Dark,
+                _ => false,
+            };
+
+            Luma([if dark { 0 } else { 255 }])
+        });
+
+        let mut png = Cursor::new(Vec::new());
+        img.write_to(&mut png, ImageFormat::Png)
+            .map_err(|e| Error::QrCode(e.to_string()))?;
+
+        Ok(png.into_inner())
+    }
+}
+
+// Encodes `data` in a single alphanumeric segment, in the smallest version that fits it
+fn alphanumeric_qr(data: &str) -> Result<QrCode> {
+    for version in 1..=40 {
+        let mut bits = Bits::new(Version::Normal(version));
+
+        match bits
+            .push_alphanumeric_data(data.as_bytes())
+            .and_then(|_| bits.push_terminator(EcLevel::Q))
+        {
+            Ok(()) => {
+                return QrCode::with_bits(bits, EcLevel::Q)
+                    .map_err(|e| Error::QrCode(e.to_string()))
+            }
+            Err(QrError::DataTooLong) => continue,
+            Err(e) => return Err(Error::QrCode(e.to_string())),
+        }
+    }
+
+    Err(Error::QrCode(QrError::DataTooLong.to_string()))
+}
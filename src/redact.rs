@@ -0,0 +1,69 @@
+// Removal of personal data from certificates, i.e. before storing or logging them.
+
+use alloc::string::{String, ToString};
+
+use sha2::{Digest, Sha256};
+
+use crate::{CertInfo, HealthCert, RawCose};
+
+/// Options controlling how [HealthCert::redacted] handles the data it does not remove outright.
+#[derive(Clone, Debug, Default)]
+pub struct RedactOptions {
+    /// Replace certificate IDs with the hex-encoded SHA-256 hash of the UVCI instead of clearing them, so that
+    /// certificates can still be told apart and matched against revocation lists
+    pub hash_cert_ids: bool,
+}
+
+impl HealthCert {
+    /// Returns a copy of this certificate without personal data.
+    ///
+    /// Names are cleared and the date of birth is reduced to the birth year; health data and validity are kept.
+    /// Certificate IDs are cleared or hashed according to `opts`, while unknown entries and the extra keys of vaccine
+    /// entries, which may hold anything, are emptied. The raw COSE data, which contains everything, is dropped, so
+    /// the returned certificate cannot be verified anymore.
+    pub fn redacted(&self, opts: &RedactOptions) -> HealthCert {
+        let mut hc = self.clone();
+
+        hc.raw = RawCose::default();
+
+        for pass in &mut hc.passes {
+            pass.surname = None;
+            pass.givenname = None;
+            pass.std_surname.clear();
+            pass.std_givenname = None;
+
+            // the date of birth may also be just a year, or empty
+            pass.date_of_birth = pass
+                .date_of_birth
+                .split('-')
+                .next()
+                .unwrap_or_default()
+                .to_string();
+
+            for ci in &mut pass.entries {
+                let cert_id = match ci {
+                    CertInfo::Exemption(e) => &mut e.cert_id,
+                    CertInfo::Light(_) => continue,
+                    CertInfo::Unknown { value, .. } => {
+                        value.clear();
+                        continue;
+                    }
+                    CertInfo::Recovery(r) => &mut r.cert_id,
+                    CertInfo::Test(t) => &mut t.cert_id,
+                    CertInfo::Vaccine(v) => {
+                        v.extra.clear();
+                        &mut v.cert_id
+                    }
+                };
+
+                *cert_id = if opts.hash_cert_ids {
+                    hex::encode(Sha256::digest(&cert_id))
+                } else {
+                    String::new()
+                };
+            }
+        }
+
+        hc
+    }
+}
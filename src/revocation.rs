@@ -0,0 +1,117 @@
+// Revocation lists, following the hashing scheme used by the EU DCC Gateway revocation lists.
+// See https://github.com/eu-digital-green-certificates/dgc-overview/blob/main/guides/revocation.md
+
+use alloc::collections::BTreeSet;
+
+use sha2::{Digest, Sha256};
+
+use crate::{CertInfo, CoseAlgorithm, HealthCert};
+
+// Revocation lists only store the first 128 bits of each SHA-256 hash
+const HASH_LEN: usize = 16;
+
+/// Identifies which data is hashed to build the entries of a [RevocationList]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RevocationHashType {
+    /// SHA-256 of the COSE signature. For ECDSA signatures, only the `r` half of the signature is hashed.
+    Signature,
+
+    /// SHA-256 of the UVCI (the `cert_id` of each entry), as it appears in the certificate
+    Uci,
+
+    /// SHA-256 of the entry country code (`co`) concatenated with the UVCI
+    CountryCodeUci,
+}
+
+/// Set of hashes identifying revoked certificates.
+#[derive(Debug)]
+pub struct RevocationList {
+    hash_type: RevocationHashType,
+    hashes: BTreeSet<[u8; HASH_LEN]>,
+}
+
+impl RevocationList {
+    /// Creates an empty list, holding hashes of the given type
+    pub fn new(hash_type: RevocationHashType) -> Self {
+        RevocationList {
+            hash_type,
+            hashes: BTreeSet::new(),
+        }
+    }
+
+    /// Type of the hashes contained in the list
+    pub fn hash_type(&self) -> RevocationHashType {
+        self.hash_type
+    }
+
+    /// Adds a hash to the list. Both full SHA-256 hashes and their first 16 bytes are accepted, longer hashes are
+    /// truncated. Returns false if the hash is shorter than 16 bytes.
+    pub fn insert(&mut self, hash: &[u8]) -> bool {
+        match truncate(hash) {
+            Some(prefix) => {
+                self.hashes.insert(prefix);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns true if the list contains the given hash, compared on its first 16 bytes
+    pub fn contains(&self, hash: &[u8]) -> bool {
+        truncate(hash)
+            .map(|prefix| self.hashes.contains(&prefix))
+            .unwrap_or(false)
+    }
+
+    /// Returns true if the list holds no hashes
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    /// Number of hashes in the list
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+}
+
+impl HealthCert {
+    /// Checks whether this certificate has been revoked according to `list`.
+    ///
+    /// Depending on [RevocationList::hash_type], either the signature or the UVCI of every entry in the bundle is
+    /// hashed; the certificate is revoked if any of the resulting hashes is in the list.
+    pub fn is_revoked(&self, list: &RevocationList) -> bool {
+        match list.hash_type() {
+            RevocationHashType::Signature => {
+                let sig = &self.signature.signature;
+
+                let hashed = match CoseAlgorithm::from_id(self.signature.algorithm) {
+                    Some(CoseAlgorithm::ES256 | CoseAlgorithm::ES384 | CoseAlgorithm::ES512) => {
+                        &sig[..sig.len() / 2]
+                    }
+                    _ => &sig[..],
+                };
+
+                list.contains(&Sha256::digest(hashed))
+            }
+            RevocationHashType::Uci => self
+                .entries()
+                .filter_map(CertInfo::cert_id)
+                .any(|uci| list.contains(&Sha256::digest(uci))),
+            RevocationHashType::CountryCodeUci => self
+                .entries()
+                .filter_map(|ci| Some((ci.country()?, ci.cert_id()?)))
+                .any(|(country, uci)| {
+                    let hash = Sha256::new()
+                        .chain_update(country.alpha2())
+                        .chain_update(uci)
+                        .finalize();
+
+                    list.contains(&hash)
+                }),
+        }
+    }
+}
+
+fn truncate(hash: &[u8]) -> Option<[u8; HASH_LEN]> {
+    hash.get(..HASH_LEN)?.try_into().ok()
+}
@@ -0,0 +1,92 @@
+// Versions of the DCC JSON schema, see https://github.com/ehn-dcc-development/ehn-dcc-schema
+
+use core::{fmt, str::FromStr};
+
+use serde_derive::Serialize;
+
+use crate::{Error, GreenPass, Result};
+
+// Fields whose requirements changed across the supported schema versions; all other fields are the same in every
+// version. Entries are parsed following the version stated in the `ver` field of their pass.
+//
+// | Field  | 1.0.x - 1.2.x | 1.3.x     |
+// |--------|---------------|-----------|
+// | t/tc   | required      | optional  |
+// | t/dr   | optional      | removed   |
+const V1_3_0: SchemaVersion = SchemaVersion {
+    major: 1,
+    minor: 3,
+    patch: 0,
+};
+
+/// Version of the schema a [GreenPass] has been issued with, as stored in its `ver` field
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct SchemaVersion {
+    /// Major version, changed on incompatible schema revisions
+    pub major: u32,
+
+    /// Minor version, changed when fields are added
+    pub minor: u32,
+
+    /// Patch version
+    pub patch: u32,
+}
+
+impl SchemaVersion {
+    /// Parses a `major.minor.patch` version string, i.e. `1.3.0`
+    pub fn parse(s: &str) -> Result<Self> {
+        let malformed = || Error::MalformedSchemaVersion(s.into());
+
+        let mut parts = s
+            .split('.')
+            .map(|n| n.parse::<u32>().map_err(|_| malformed()));
+
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(major), Some(minor), Some(patch), None) => Ok(SchemaVersion {
+                major: major?,
+                minor: minor?,
+                patch: patch?,
+            }),
+            _ => Err(malformed()),
+        }
+    }
+
+    /// Latest schema version modelled by this crate, whose requirements apply to passes with an unparseable `ver`
+    pub const LATEST: SchemaVersion = V1_3_0;
+
+    /// Returns true if the schema is one whose fields are modelled by this crate, i.e. 1.0.x up to 1.3.x
+    pub fn is_supported(&self) -> bool {
+        self.major == 1 && self.minor <= 3
+    }
+
+    /// Returns true if tests must state their testing centre (`tc`), which is optional since 1.3.0
+    pub fn requires_testing_centre(&self) -> bool {
+        *self < V1_3_0
+    }
+
+    /// Returns true if tests may state when their result was produced (`dr`), which was removed in 1.3.0
+    pub fn allows_test_result_date(&self) -> bool {
+        *self < V1_3_0
+    }
+}
+
+impl FromStr for SchemaVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        SchemaVersion::parse(s)
+    }
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl GreenPass {
+    /// Parses the schema version of this pass
+    pub fn schema_version(&self) -> Result<SchemaVersion> {
+        SchemaVersion::parse(&self.ver)
+    }
+}
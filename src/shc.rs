@@ -0,0 +1,186 @@
+// SMART Health Cards, the format used for vaccination records in the US and Canada, see https://smarthealth.cards.
+// Cards are only decoded structurally: the compact JWS is split and inflated, but its signature is not checked and
+// the FHIR bundle it holds is left as JSON.
+
+use std::io::Read;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL, Engine};
+use chrono::prelude::*;
+use flate2::read::DeflateDecoder;
+use serde_json::Value;
+
+use crate::{Error, HealthCert, Result};
+
+const SHC_PREFIX: &str = "shc:/";
+
+/// A SMART Health Card, decoded but not verified.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShcCert {
+    /// Issuer URL (`iss`), which also locates the issuer keys
+    pub issuer: String,
+
+    /// Time from which the card is valid (`nbf`), if stated
+    pub not_before: Option<DateTime<Utc>>,
+
+    /// Signature algorithm from the JWS header, i.e. `ES256`
+    pub algorithm: String,
+
+    /// Key ID from the JWS header, if present
+    pub kid: Option<String>,
+
+    /// Verifiable credential (`vc`), holding the FHIR bundle with the health data
+    pub credential: Value,
+
+    /// JWS signature
+    pub signature: Vec<u8>,
+}
+
+impl ShcCert {
+    /// Decodes a card from its compact JWS form, i.e. `header.payload.signature`.
+    pub fn from_jws(jws: &str) -> Result<Self> {
+        let malformed = |what: &str| Error::MalformedShc(what.into());
+
+        let mut parts = jws.split('.');
+
+        let (header, payload, signature) =
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(header), Some(payload), Some(signature), None) => {
+                    (header, payload, signature)
+                }
+                _ => return Err(malformed("JWS must have three parts")),
+            };
+
+        let decode = |part: &str| {
+            BASE64_URL
+                .decode(part)
+                .map_err(|_| malformed("invalid base64url in JWS"))
+        };
+
+        let header: Value = serde_json::from_slice(&decode(header)?)
+            .map_err(|_| malformed("invalid JWS header"))?;
+
+        // payloads are compressed with raw DEFLATE, which is declared in the header
+        let payload = match header["zip"].as_str() {
+            Some("DEF") => {
+                let mut inflated = Vec::new();
+                DeflateDecoder::new(&decode(payload)?[..])
+                    .read_to_end(&mut inflated)
+                    .map_err(|_| malformed("invalid DEFLATE payload"))?;
+
+                inflated
+            }
+            Some(_) => return Err(malformed("unsupported payload compression")),
+            None => decode(payload)?,
+        };
+
+        let mut claims: Value =
+            serde_json::from_slice(&payload).map_err(|_| malformed("invalid JWS payload"))?;
+
+        let algorithm = header["alg"]
+            .as_str()
+            .ok_or_else(|| malformed("missing alg"))?
+            .into();
+        let kid = header["kid"].as_str().map(Into::into);
+
+        let issuer = claims["iss"]
+            .as_str()
+            .ok_or_else(|| malformed("missing iss"))?
+            .into();
+
+        // `nbf` is a NumericDate, which may have a fractional part
+        let not_before = match &claims["nbf"] {
+            Value::Null => None,
+            nbf => {
+                let secs = nbf.as_f64().ok_or_else(|| malformed("invalid nbf"))?;
+
+                Some(
+                    Utc.timestamp_opt(secs.trunc() as i64, (secs.fract() * 1e9) as u32)
+                        .single()
+                        .ok_or_else(|| malformed("invalid nbf"))?,
+                )
+            }
+        };
+
+        let credential = match claims.get_mut("vc").map(Value::take) {
+            Some(vc @ Value::Object(_)) => vc,
+            _ => return Err(malformed("missing vc")),
+        };
+
+        Ok(ShcCert {
+            issuer,
+            not_before,
+            algorithm,
+            kid,
+            credential,
+            signature: decode(signature)?,
+        })
+    }
+}
+
+// Turns the numeric encoding used in QR codes back into the compact JWS: every pair of digits is a character, offset
+// by 45 from its code
+fn decode_shc_numeric(digits: &str) -> Result<String> {
+    if digits.contains('/') {
+        return Err(Error::UnsupportedFormat("chunked SMART Health Card".into()));
+    }
+
+    let malformed = || Error::MalformedShc("invalid numeric encoding".into());
+
+    if !digits.len().is_multiple_of(2) {
+        return Err(malformed());
+    }
+
+    digits
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| {
+            core::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| pair.parse::<u8>().ok())
+                .filter(|&n| n <= b'z' - 45)
+                .map(|n| char::from(n + 45))
+                .ok_or_else(malformed)
+        })
+        .collect()
+}
+
+/// A health certificate in any of the formats understood by [parse_any]
+// certificates are few and short-lived, so boxing them would only make matching more awkward
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
+pub enum AnyCert {
+    /// EU Digital COVID Certificate, from an `HC1:` string
+    Dcc(HealthCert),
+
+    /// SMART Health Card, from an `shc:/` string
+    Shc(ShcCert),
+}
+
+/// Parses either an EU Digital COVID Certificate (`HC1:...`) or a SMART Health Card (`shc:/...`), detecting the
+/// format from the prefix. Inputs in any other format fail with [Error::UnsupportedFormat].
+///
+/// SMART Health Cards are only decoded structurally, see [ShcCert]; chunked cards are not supported.
+///
+/// ```no_run
+/// use greenpass::AnyCert;
+///
+/// match greenpass::parse_any("shc:/5676290952...").unwrap() {
+///     AnyCert::Dcc(hc) => println!("{}", hc),
+///     AnyCert::Shc(shc) => println!("SMART Health Card issued by {}", shc.issuer),
+/// }
+/// ```
+pub fn parse_any(data: &str) -> Result<AnyCert> {
+    let trimmed = data.trim_matches(|c: char| c == '\u{feff}' || c.is_whitespace());
+
+    match trimmed.get(..SHC_PREFIX.len()) {
+        Some(prefix) if prefix.eq_ignore_ascii_case(SHC_PREFIX) => {
+            let jws = decode_shc_numeric(&trimmed[SHC_PREFIX.len()..])?;
+
+            ShcCert::from_jws(&jws).map(AnyCert::Shc)
+        }
+        _ => match crate::parse(data) {
+            Err(Error::MissingHCID) => Err(Error::UnsupportedFormat("unknown prefix".into())),
+            res => res.map(AnyCert::Dcc),
+        },
+    }
+}
@@ -0,0 +1,303 @@
+// Quick extraction of the fields needed to triage certificates, i.e. when scanning large batches. Entries are skipped
+// while decoding instead of being built into CBOR values, and only the holder data is kept from each pass.
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::fmt;
+
+use chrono::prelude::*;
+use ciborium::value::Value;
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{from_cbor, timestamp, CertInfo, Country, Cwt, Error, Result};
+
+/// The kinds of entries a pass can hold
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum EntryKind {
+    Exemption,
+    Light,
+    Recovery,
+    Test,
+    Unknown,
+    Vaccine,
+}
+
+impl CertInfo {
+    /// Returns the kind of this entry
+    pub fn kind(&self) -> EntryKind {
+        match self {
+            CertInfo::Exemption(_) => EntryKind::Exemption,
+            CertInfo::Light(_) => EntryKind::Light,
+            CertInfo::Recovery(_) => EntryKind::Recovery,
+            CertInfo::Test(_) => EntryKind::Test,
+            CertInfo::Unknown { .. } => EntryKind::Unknown,
+            CertInfo::Vaccine(_) => EntryKind::Vaccine,
+        }
+    }
+}
+
+/// Holder data and entry kind of a pass, as returned by [parse_summary]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct PassSummary {
+    /// Date of birth
+    pub date_of_birth: String,
+
+    /// Family name, if present
+    pub surname: Option<String>,
+
+    /// First name, if present
+    pub givenname: Option<String>,
+
+    /// Family name in standardized form
+    pub std_surname: String,
+
+    /// First name in standardized form, if present
+    pub std_givenname: Option<String>,
+
+    /// Kind of the entries held by the pass
+    pub kind: EntryKind,
+}
+
+/// The fields of a certificate needed to tell who it belongs to, what it attests and when it expires, as returned by
+/// [parse_summary].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Summary {
+    /// Issuing country, if declared
+    pub some_issuer: Option<Country>,
+
+    /// Issue date
+    pub created: DateTime<Utc>,
+
+    /// Expiration date
+    pub expires: DateTime<Utc>,
+
+    /// Passes in the bundle
+    pub passes: Vec<PassSummary>,
+}
+
+impl Summary {
+    /// Extracts the summary of a certificate from its raw COSE_Sign1 structure, see [HealthCert::from_cose_bytes].
+    ///
+    /// [HealthCert::from_cose_bytes]: crate::HealthCert::from_cose_bytes
+    pub fn from_cose_bytes(data: &[u8]) -> Result<Self> {
+        let root: Value = from_cbor(data)?;
+        let Cwt(cwt_arr) = Cwt::try_from(root)?;
+
+        let claims: Claims = match &cwt_arr[2] {
+            Value::Bytes(bys) => from_cbor(&bys[..])?,
+            _ => {
+                return Err(Error::InvalidFormatFor {
+                    key: "root cert".into(),
+                })
+            }
+        };
+
+        let light = claims.light;
+        let RawPasses(passes) = claims
+            .passes
+            .ok_or_else(|| Error::MissingKey("hcert".into()))?;
+
+        if passes.is_empty() {
+            return Err(Error::MissingKey("hcert entries".into()));
+        }
+
+        let passes = passes
+            .into_iter()
+            .map(|pass| pass.into_summary(light))
+            .collect::<Result<_>>()?;
+
+        Ok(Summary {
+            some_issuer: claims.issuer.map(Country::from),
+            created: timestamp(
+                claims
+                    .created
+                    .ok_or_else(|| Error::MissingKey("issue timestamp".into()))?
+                    .into(),
+            )?,
+            expires: timestamp(
+                claims
+                    .expires
+                    .ok_or_else(|| Error::MissingKey("expiration timestamp".into()))?
+                    .into(),
+            )?,
+            passes,
+        })
+    }
+}
+
+// The claims of the CWT payload relevant to a summary; the others are skipped
+struct Claims {
+    issuer: Option<String>,
+    created: Option<i64>,
+    expires: Option<i64>,
+    passes: Option<RawPasses>,
+    light: bool,
+}
+
+impl<'de> Deserialize<'de> for Claims {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        deserializer.deserialize_map(ClaimsVisitor)
+    }
+}
+
+struct ClaimsVisitor;
+
+impl<'de> Visitor<'de> for ClaimsVisitor {
+    type Value = Claims;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a CWT claims map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> core::result::Result<Claims, A::Error> {
+        let mut claims = Claims {
+            issuer: None,
+            created: None,
+            expires: None,
+            passes: None,
+            light: false,
+        };
+
+        while let Some(key) = map.next_key::<i64>()? {
+            match key {
+                1 => claims.issuer = Some(map.next_value()?),
+                4 => claims.expires = Some(map.next_value()?),
+                6 => claims.created = Some(map.next_value()?),
+                // Swiss certificates light store their passes under their own claim
+                -260 | -250 if claims.passes.is_some() => {
+                    return Err(de::Error::custom("multiple hcert claims"))
+                }
+                -260 | -250 => {
+                    claims.passes = Some(map.next_value()?);
+                    claims.light = key == -250;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(claims)
+    }
+}
+
+// The passes of a bundle, in the order they are stored. Their keys are ignored like HealthCert does, so passes with
+// duplicate keys are all kept
+struct RawPasses(Vec<RawPass>);
+
+impl<'de> Deserialize<'de> for RawPasses {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> core::result::Result<Self, D::Error> {
+        deserializer.deserialize_map(RawPassesVisitor)
+    }
+}
+
+struct RawPassesVisitor;
+
+impl<'de> Visitor<'de> for RawPassesVisitor {
+    type Value = RawPasses;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a map of passes")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> core::result::Result<RawPasses, A::Error> {
+        let mut passes = Vec::new();
+
+        while let Some((IgnoredAny, pass)) = map.next_entry()? {
+            passes.push(pass);
+        }
+
+        Ok(RawPasses(passes))
+    }
+}
+
+// A pass, with its entries skipped
+#[derive(Deserialize)]
+struct RawPass {
+    dob: String,
+
+    #[serde(default)]
+    nam: Option<RawNames>,
+
+    #[serde(default)]
+    v: Option<IgnoredAny>,
+
+    #[serde(default)]
+    t: Option<IgnoredAny>,
+
+    #[serde(default)]
+    r: Option<IgnoredAny>,
+
+    #[serde(default)]
+    e: Option<IgnoredAny>,
+
+    // any other key, which may hold entries of a kind added by a later revision of the schema
+    #[serde(flatten)]
+    other: BTreeMap<String, IgnoredAny>,
+}
+
+#[derive(Default, Deserialize)]
+struct RawNames {
+    #[serde(rename = "fn")]
+    surname: Option<String>,
+
+    #[serde(rename = "gn")]
+    givenname: Option<String>,
+
+    fnt: String,
+
+    gnt: Option<String>,
+}
+
+impl RawPass {
+    fn into_summary(self, light: bool) -> Result<PassSummary> {
+        // same precedence as GreenPass::from_map
+        let kind = if light {
+            EntryKind::Light
+        } else if self.r.is_some() {
+            EntryKind::Recovery
+        } else if self.t.is_some() {
+            EntryKind::Test
+        } else if self.v.is_some() {
+            EntryKind::Vaccine
+        } else if self.e.is_some() {
+            EntryKind::Exemption
+        } else if self.other.keys().any(|k| k != "ver") {
+            EntryKind::Unknown
+        } else {
+            return Err(Error::MissingKey("r, t or v (the actual data)".into()));
+        };
+
+        // as in GreenPass::from_map, a missing `nam` map leaves the names empty
+        let nam = self.nam.unwrap_or_default();
+
+        Ok(PassSummary {
+            date_of_birth: self.dob,
+            surname: nam.surname,
+            givenname: nam.givenname,
+            std_surname: nam.fnt,
+            std_givenname: nam.gnt,
+            kind,
+        })
+    }
+}
+
+/// Extracts the issuer, issue and expiration dates, names and entry kinds from a Base45 CBOR Web Token, without
+/// building the entries of its passes. This is faster than [parse](crate::parse) when scanning large batches of
+/// certificates, although Base45 decoding and decompression still account for most of the time.
+///
+/// The summary is not a validated certificate: entries are skipped without being checked, unknown keys are ignored and
+/// the issuer is not checked against [ParseOptions](crate::ParseOptions). A certificate that can be summarized may
+/// still be rejected by [parse](crate::parse), which should be used before trusting any of its contents.
+///
+/// ```no_run
+/// let summary = greenpass::parse_summary("HC1:...").unwrap();
+///
+/// for pass in summary.passes {
+///     println!("{}: {:?}", pass.std_surname, pass.kind);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn parse_summary(data: &str) -> Result<Summary> {
+    Summary::from_cose_bytes(&crate::decode_hc1(data)?)
+}
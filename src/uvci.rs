@@ -0,0 +1,147 @@
+// Unique Vaccination Certificate/assertion Identifiers, as described in Annex 2 of
+// https://ec.europa.eu/health/sites/default/files/ehealth/docs/vaccination-proof_interoperability-guidelines_en.pdf
+
+use alloc::string::String;
+use core::{fmt, str::FromStr};
+
+use serde_derive::Serialize;
+
+use crate::{Error, Exemption, Recovery, Result, Test, Vaccine};
+
+const URN_PREFIX: &str = "URN:UVCI:";
+
+// Alphabet used when computing the Luhn mod N checksum
+const CHECKSUM_ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ/:";
+
+/// A parsed UVCI, i.e. the `cert_id` field of certificate entries
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub struct Uvci {
+    /// UVCI schema version, i.e. `01`
+    pub version: String,
+
+    /// Country that issued the identifier
+    pub country: String,
+
+    /// Issuer specific part of the identifier
+    pub opaque: String,
+
+    /// Luhn mod N checksum, if present
+    pub checksum: Option<char>,
+}
+
+impl Uvci {
+    /// Parses a UVCI, with or without the `URN:UVCI:` prefix. When present, the checksum is validated against the
+    /// rest of the identifier.
+    pub fn parse(s: &str) -> Result<Self> {
+        let malformed = || Error::MalformedUvci(s.into());
+
+        let (body, checksum) = match s.split_once('#') {
+            Some((body, ck)) => {
+                let mut chars = ck.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => (body, Some(c)),
+                    _ => return Err(malformed()),
+                }
+            }
+            None => (s, None),
+        };
+
+        let rest = body.strip_prefix(URN_PREFIX).unwrap_or(body);
+
+        let (version, rest) =
+            split_checked(rest, 2, |c| c.is_ascii_digit()).ok_or_else(malformed)?;
+        let rest = rest.strip_prefix(&[':', '/'][..]).unwrap_or(rest);
+
+        let (country, rest) =
+            split_checked(rest, 2, |c| c.is_ascii_uppercase()).ok_or_else(malformed)?;
+        let opaque = rest.strip_prefix(&[':', '/'][..]).ok_or_else(malformed)?;
+
+        if opaque.is_empty() {
+            return Err(malformed());
+        }
+
+        if let Some(found) = checksum {
+            let expected = luhn_mod_n(body).ok_or_else(malformed)?;
+
+            if expected != found {
+                return Err(Error::UvciChecksumMismatch { expected, found });
+            }
+        }
+
+        Ok(Uvci {
+            version: version.into(),
+            country: country.into(),
+            opaque: opaque.into(),
+            checksum,
+        })
+    }
+}
+
+impl FromStr for Uvci {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Uvci::parse(s)
+    }
+}
+
+impl fmt::Display for Uvci {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}:{}:{}",
+            URN_PREFIX, self.version, self.country, self.opaque
+        )?;
+
+        match self.checksum {
+            Some(c) => write!(f, "#{}", c),
+            None => Ok(()),
+        }
+    }
+}
+
+// Splits the first n characters of s, if they all satisfy pred
+fn split_checked(s: &str, n: usize, pred: impl Fn(char) -> bool) -> Option<(&str, &str)> {
+    let head = s.get(..n)?;
+
+    if head.chars().all(pred) {
+        Some((head, &s[n..]))
+    } else {
+        None
+    }
+}
+
+// Luhn mod N algorithm, see https://en.wikipedia.org/wiki/Luhn_mod_N_algorithm
+fn luhn_mod_n(s: &str) -> Option<char> {
+    let n = CHECKSUM_ALPHABET.len();
+
+    let mut factor = 2;
+    let mut sum = 0;
+
+    for b in s.bytes().rev() {
+        let code_point = CHECKSUM_ALPHABET.iter().position(|&c| c == b)?;
+
+        let addend = factor * code_point;
+        sum += addend / n + addend % n;
+
+        factor = if factor == 2 { 1 } else { 2 };
+    }
+
+    Some(CHECKSUM_ALPHABET[(n - sum % n) % n] as char)
+}
+
+macro_rules! impl_uvci {
+    ($($t:ty),*) => {
+        $(
+            impl $t {
+                /// Parses the certificate ID of this entry as a UVCI.
+                pub fn uvci(&self) -> Result<Uvci> {
+                    Uvci::parse(&self.cert_id)
+                }
+            }
+        )*
+    };
+}
+
+impl_uvci!(Exemption, Recovery, Test, Vaccine);
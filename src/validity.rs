@@ -0,0 +1,156 @@
+// Validity windows of individual entries. Vaccinations and tests carry no explicit validity, so it is derived from a
+// policy, which by default follows the EU Council recommendations on the acceptance of certificates.
+
+use chrono::{naive::MAX_DATE, prelude::*, Duration};
+
+use crate::{CertInfo, Dated, Test, TestResult, TestType, Vaccine};
+
+/// Rules used to derive the validity of entries that do not state it explicitly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidityPolicy {
+    /// Days after the completion of the primary series before a vaccination is valid
+    pub vaccine_delay_days: u32,
+
+    /// Days after a booster dose before it is valid
+    pub booster_delay_days: u32,
+
+    /// Days a completed primary series stays valid after the vaccination date, or `None` if it never expires.
+    /// Boosters never expire.
+    pub vaccine_validity_days: Option<u32>,
+
+    /// Hours a negative NAAT test is valid after the sample collection
+    pub naat_validity_hours: u32,
+
+    /// Hours a negative rapid antigen test is valid after the sample collection
+    pub rat_validity_hours: u32,
+}
+
+impl Default for ValidityPolicy {
+    fn default() -> Self {
+        ValidityPolicy {
+            vaccine_delay_days: 14,
+            booster_delay_days: 0,
+            vaccine_validity_days: Some(270),
+            naat_validity_hours: 72,
+            rat_validity_hours: 48,
+        }
+    }
+}
+
+/// Time span during which an entry is valid
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidityWindow {
+    /// Start of the validity, included
+    pub from: DateTime<Utc>,
+
+    /// End of the validity, excluded, or `None` if the entry never expires
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl ValidityWindow {
+    /// Returns true if `now` falls within the window
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        self.from <= now && self.until.is_none_or(|until| now < until)
+    }
+
+    // Window spanning whole days, with both bounds included. A window lasting until the last day chrono can
+    // represent has no end
+    fn days(from: NaiveDate, until: Option<NaiveDate>) -> Self {
+        ValidityWindow {
+            from: midnight(from),
+            until: until.and_then(|until| until.succ_opt()).map(midnight),
+        }
+    }
+}
+
+fn midnight(date: NaiveDate) -> DateTime<Utc> {
+    Utc.from_utc_datetime(&date.and_hms(0, 0, 0))
+}
+
+impl Vaccine {
+    /// Computes the validity of this vaccination using the [ValidityPolicy::default] rules, i.e. from 14 days after
+    /// the completion of the primary series for 270 days, or from the day of a booster dose with no expiration.
+    /// Both bounds are included.
+    ///
+    /// The window is computed even if the series has not been completed; [CertInfo::validity_window] only returns it
+    /// for completed series.
+    pub fn default_validity(&self) -> (NaiveDate, Option<NaiveDate>) {
+        self.validity(&ValidityPolicy::default())
+    }
+
+    /// Computes the validity of this vaccination using the given policy. Both bounds are included.
+    ///
+    /// Dates past the range chrono can represent, which only crafted certificates can lead to, are not an error: a
+    /// start past it is clamped to the last representable day, and an end past it means no expiration.
+    pub fn validity(&self, policy: &ValidityPolicy) -> (NaiveDate, Option<NaiveDate>) {
+        let is_booster = self.dose_info().is_ok_and(|info| info.is_booster);
+        let date = self.date.parsed;
+
+        let after = |days: u32| date.checked_add_signed(Duration::days(days.into()));
+
+        if is_booster {
+            (after(policy.booster_delay_days).unwrap_or(MAX_DATE), None)
+        } else {
+            (
+                after(policy.vaccine_delay_days).unwrap_or(MAX_DATE),
+                policy.vaccine_validity_days.and_then(after),
+            )
+        }
+    }
+}
+
+impl Test {
+    // Only negative tests of a known type are valid
+    fn validity_window(&self, policy: &ValidityPolicy) -> Option<ValidityWindow> {
+        if self.outcome() != TestResult::NotDetected {
+            return None;
+        }
+
+        let hours = match self.test_type_kind() {
+            TestType::NAAT => policy.naat_validity_hours,
+            TestType::RAT => policy.rat_validity_hours,
+            TestType::Unknown(_) => return None,
+        };
+
+        let from = self.collect_ts_utc();
+
+        // tests collected right before the last time chrono can represent never expire
+        Some(ValidityWindow {
+            from,
+            until: from.checked_add_signed(Duration::hours(hours.into())),
+        })
+    }
+}
+
+impl CertInfo {
+    /// Computes the time span during which this entry is valid.
+    ///
+    /// Recoveries, exemptions and certificates light use their explicit validity, while vaccinations and tests follow
+    /// `policy`. Returns `None` for entries that are never valid: vaccinations with an incomplete series, positive
+    /// tests or tests of unknown type, and unknown entries.
+    pub fn validity_window(&self, policy: &ValidityPolicy) -> Option<ValidityWindow> {
+        let explicit = |from: &Dated, until: &Dated| {
+            Some(ValidityWindow::days(from.parsed, Some(until.parsed)))
+        };
+
+        match self {
+            CertInfo::Exemption(e) => explicit(&e.valid_from, &e.valid_until),
+            CertInfo::Light(l) => Some(ValidityWindow {
+                from: l.valid_from,
+                until: Some(l.valid_until),
+            }),
+            CertInfo::Recovery(r) => explicit(&r.valid_from, &r.valid_until),
+            CertInfo::Test(t) => t.validity_window(policy),
+            CertInfo::Unknown { .. } => None,
+            CertInfo::Vaccine(v) => {
+                if !v.dose_info().is_ok_and(|info| info.is_complete) {
+                    return None;
+                }
+
+                let (from, until) = v.validity(policy);
+
+                Some(ValidityWindow::days(from, until))
+            }
+        }
+    }
+}
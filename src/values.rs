@@ -1,11 +1,67 @@
 // Value Sets for the Digital COVID Certificates according to https://ec.europa.eu/health/system/files/2022-01/digital-green-value-sets_en.pdf
 
+use alloc::string::String;
+
+use serde_derive::Serialize;
+
 // 2.1 Disease or agent targeted / Disease or agent the citizen has recovered from
 // Fully described in the Implementing Decision.
 
+/// SNOMED CT code for COVID-19, the only disease currently covered by certificates
+pub const COVID_19: &str = "840539006";
+
+/// Disease or agent targeted by an entry, as coded in SNOMED CT
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum Disease {
+    /// COVID-19
+    Covid19,
+
+    /// Any other code, not defined by the value set
+    Other(String),
+}
+
+impl Disease {
+    /// Every disease in the value set
+    pub const ALL: &'static [Disease] = &[Disease::Covid19];
+
+    /// Returns every disease in the value set, same as [Disease::ALL]
+    pub fn all() -> &'static [Disease] {
+        Self::ALL
+    }
+
+    /// Iterates over the codes of the value set, paired with their display names
+    pub fn codes() -> impl Iterator<Item = (&'static str, &'static str)> {
+        Self::ALL.iter().map(|tg| (tg.code(), tg.name()))
+    }
+
+    /// Returns the display name of this disease, or its code if it is not in the value set
+    pub fn name(&self) -> &str {
+        match self {
+            Disease::Covid19 => "COVID-19",
+            Disease::Other(code) => code,
+        }
+    }
+
+    /// Maps a `tg` code to a disease
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            COVID_19 => Disease::Covid19,
+            _ => Disease::Other(code.into()),
+        }
+    }
+
+    /// Returns the SNOMED CT code for this disease
+    pub fn code(&self) -> &str {
+        match self {
+            Disease::Covid19 => COVID_19,
+            Disease::Other(code) => code,
+        }
+    }
+}
+
 // 2.2 COVID-19 vaccine or prophylaxis
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VaccineProphylaxis {
     Antigen,
     MRNA,
@@ -13,6 +69,32 @@ pub enum VaccineProphylaxis {
 }
 
 impl VaccineProphylaxis {
+    /// Every prophylaxis in the value set
+    pub const ALL: &'static [VaccineProphylaxis] = &[
+        VaccineProphylaxis::Antigen,
+        VaccineProphylaxis::MRNA,
+        VaccineProphylaxis::Vaccine,
+    ];
+
+    /// Maps a `vp` code to a prophylaxis, if it is in the value set
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|vp| vp.values().0 == code)
+    }
+
+    /// Returns every prophylaxis in the value set, same as [VaccineProphylaxis::ALL]
+    pub fn all() -> &'static [VaccineProphylaxis] {
+        Self::ALL
+    }
+
+    /// Iterates over the codes of the value set, paired with their display names
+    pub fn codes() -> impl Iterator<Item = (&'static str, &'static str)> {
+        Self::ALL.iter().map(|vp| {
+            let (code, display, ..) = vp.values();
+
+            (code, display)
+        })
+    }
+
     pub fn values(&self) -> (&str, &str, &str, &str, &str, &str) {
         match *self {
             VaccineProphylaxis::Antigen => (
@@ -44,7 +126,7 @@ impl VaccineProphylaxis {
 }
 
 // 2.3 Vaccine medicinal product
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VaccineMedicinalProduct {
     Comirnaty,
     Spikevax,
@@ -84,6 +166,14 @@ pub enum VaccineAuthorizationStatus {
     NotAuthorized,
 }
 
+impl VaccineAuthorizationStatus {
+    /// Returns true if the product is centrally authorized in the EU, i.e. listed in the Union Register of medicinal
+    /// products. Products in rolling review are not authorized yet.
+    pub fn is_eu_authorized(&self) -> bool {
+        matches!(self, VaccineAuthorizationStatus::CentrallyAuthorized)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum CodeSystemVersion {
     V1_0,
@@ -96,6 +186,102 @@ pub enum CodeSystemVersion {
 }
 
 impl VaccineMedicinalProduct {
+    /// Every medicinal product in the value set, once per code. Variants that are only aliases of another product with
+    /// the same code (`VeroCell`, `BBV152ABC` and `ChAdOx1nCoV19`) are left out.
+    pub const ALL: &'static [VaccineMedicinalProduct] = &[
+        VaccineMedicinalProduct::Comirnaty,
+        VaccineMedicinalProduct::Spikevax,
+        VaccineMedicinalProduct::Vaxzevria,
+        VaccineMedicinalProduct::COVID19VaccineJanssen,
+        VaccineMedicinalProduct::CVnCoV,
+        VaccineMedicinalProduct::NVXCoV2373,
+        VaccineMedicinalProduct::SputnikV,
+        VaccineMedicinalProduct::Convidecia,
+        VaccineMedicinalProduct::EpiVacCorona,
+        VaccineMedicinalProduct::BBIBPCorV,
+        VaccineMedicinalProduct::InactivatedSARSCoV2,
+        VaccineMedicinalProduct::CoronaVac,
+        VaccineMedicinalProduct::Covaxin,
+        VaccineMedicinalProduct::Covishield,
+        VaccineMedicinalProduct::Covid19Recombinant,
+        VaccineMedicinalProduct::RCOVI,
+        VaccineMedicinalProduct::CoviVac,
+        VaccineMedicinalProduct::SputnikLight,
+        VaccineMedicinalProduct::HayatVax,
+        VaccineMedicinalProduct::Abdala,
+        VaccineMedicinalProduct::WIBPCorV,
+        VaccineMedicinalProduct::MVCCOVID19Vaccine,
+        VaccineMedicinalProduct::Nuvaxovid,
+    ];
+
+    /// Maps a `mp` code to a medicinal product, if it is in the value set
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|mp| mp.values().0 == code)
+    }
+
+    /// Returns every medicinal product in the value set, same as [VaccineMedicinalProduct::ALL]
+    pub fn all() -> &'static [VaccineMedicinalProduct] {
+        Self::ALL
+    }
+
+    /// Iterates over the codes of the value set, paired with their display names
+    pub fn codes() -> impl Iterator<Item = (&'static str, &'static str)> {
+        Self::ALL.iter().map(|mp| {
+            let (code, display, ..) = mp.values();
+
+            (code, display)
+        })
+    }
+
+    /// Marketing authorization holders or manufacturers known to produce this product. An empty slice means that
+    /// the manufacturer is not known.
+    pub fn manufacturers(&self) -> &'static [Manufacturer] {
+        use Manufacturer::*;
+
+        match *self {
+            VaccineMedicinalProduct::Comirnaty => &[BiontechManufacturing],
+            VaccineMedicinalProduct::Spikevax => &[ModernaBiotechSpain],
+            VaccineMedicinalProduct::Vaxzevria => &[AstraZeneca],
+            VaccineMedicinalProduct::COVID19VaccineJanssen => &[JanssenCilagInternational],
+            VaccineMedicinalProduct::CVnCoV => &[Curevac],
+            VaccineMedicinalProduct::NVXCoV2373 | VaccineMedicinalProduct::Nuvaxovid => &[Novavax],
+            VaccineMedicinalProduct::SputnikV | VaccineMedicinalProduct::SputnikLight => {
+                &[GamaleyaResearchInstitute]
+            }
+            VaccineMedicinalProduct::Convidecia => &[CanSinoBiologics],
+            VaccineMedicinalProduct::EpiVacCorona => &[VectorInstitute],
+            VaccineMedicinalProduct::BBIBPCorV
+            | VaccineMedicinalProduct::InactivatedSARSCoV2
+            | VaccineMedicinalProduct::VeroCell => {
+                &[ChinaSinopharm, SinopharmWeiqidaPrague, SinopharmZhijun]
+            }
+            VaccineMedicinalProduct::CoronaVac => &[SinovacBiotech],
+            VaccineMedicinalProduct::Covaxin | VaccineMedicinalProduct::BBV152ABC => {
+                &[BharatBiotech]
+            }
+            VaccineMedicinalProduct::Covishield | VaccineMedicinalProduct::ChAdOx1nCoV19 => {
+                &[SerumInstituteOfIndia]
+            }
+            VaccineMedicinalProduct::Covid19Recombinant => &[Fiocruz],
+            VaccineMedicinalProduct::RCOVI => &[RPharmCJSC],
+            VaccineMedicinalProduct::CoviVac => &[Chumakov],
+            VaccineMedicinalProduct::HayatVax => &[GulfPharmaceutical],
+            VaccineMedicinalProduct::Abdala => &[CIGB],
+            VaccineMedicinalProduct::WIBPCorV => &[SinopharmWuhan],
+            VaccineMedicinalProduct::MVCCOVID19Vaccine => &[Medigen],
+        }
+    }
+
+    /// Returns true if this is an mRNA vaccine
+    pub fn is_mrna(&self) -> bool {
+        matches!(
+            *self,
+            VaccineMedicinalProduct::Comirnaty
+                | VaccineMedicinalProduct::Spikevax
+                | VaccineMedicinalProduct::CVnCoV
+        )
+    }
+
     pub fn values(
         &self,
     ) -> (
@@ -136,14 +322,14 @@ impl VaccineMedicinalProduct {
                 Some(CodeSystemVersion::V1_0),
             ),
             VaccineMedicinalProduct::NVXCoV2373 => (
-                "NVX-CoV2373 (deprecated,see Annex A for more instructions",
+                // deprecated, see Annex A of the value sets
+                "NVX-CoV2373",
                 "NVX-CoV2373",
                 VaccineAuthorizationStatus::InRollingReview,
                 Some(CodeSystemVersion::V1_0),
             ),
             VaccineMedicinalProduct::SputnikV => (
-                "
-                Sputnik-V",
+                "Sputnik-V",
                 "Sputnik V",
                 VaccineAuthorizationStatus::InRollingReview,
                 Some(CodeSystemVersion::V1_0),
@@ -167,7 +353,8 @@ impl VaccineMedicinalProduct {
                 Some(CodeSystemVersion::V1_0),
             ),
             VaccineMedicinalProduct::InactivatedSARSCoV2 | VaccineMedicinalProduct::VeroCell => (
-                "Inactivated-SARS-CoV-2-Vero-Cell (deprecated, see Annex A for more instructions)",
+                // deprecated, see Annex A of the value sets
+                "Inactivated-SARS-CoV-2-Vero-Cell",
                 "Inactivated SARS-CoV-2 (Vero Cell)",
                 VaccineAuthorizationStatus::NotAuthorized,
                 Some(CodeSystemVersion::V1_0),
@@ -255,7 +442,7 @@ pub enum ManufacturerInOMS {
     No,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Manufacturer {
     AstraZeneca,
     BiontechManufacturing,
@@ -282,6 +469,51 @@ pub enum Manufacturer {
 }
 
 impl Manufacturer {
+    /// Every manufacturer in the value set
+    pub const ALL: &'static [Manufacturer] = &[
+        Manufacturer::AstraZeneca,
+        Manufacturer::BiontechManufacturing,
+        Manufacturer::JanssenCilagInternational,
+        Manufacturer::ModernaBiotechSpain,
+        Manufacturer::Curevac,
+        Manufacturer::CanSinoBiologics,
+        Manufacturer::ChinaSinopharm,
+        Manufacturer::SinopharmWeiqidaPrague,
+        Manufacturer::SinopharmZhijun,
+        Manufacturer::Novavax,
+        Manufacturer::GamaleyaResearchInstitute,
+        Manufacturer::VectorInstitute,
+        Manufacturer::SinovacBiotech,
+        Manufacturer::BharatBiotech,
+        Manufacturer::SerumInstituteOfIndia,
+        Manufacturer::Fiocruz,
+        Manufacturer::RPharmCJSC,
+        Manufacturer::Chumakov,
+        Manufacturer::GulfPharmaceutical,
+        Manufacturer::CIGB,
+        Manufacturer::SinopharmWuhan,
+        Manufacturer::Medigen,
+    ];
+
+    /// Maps a `ma` code to a manufacturer, if it is in the value set
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|ma| ma.values().0 == code)
+    }
+
+    /// Returns every manufacturer in the value set, same as [Manufacturer::ALL]
+    pub fn all() -> &'static [Manufacturer] {
+        Self::ALL
+    }
+
+    /// Iterates over the codes of the value set, paired with their display names
+    pub fn codes() -> impl Iterator<Item = (&'static str, &'static str)> {
+        Self::ALL.iter().map(|ma| {
+            let (code, display, ..) = ma.values();
+
+            (code, display)
+        })
+    }
+
     pub fn values(&self) -> (&str, &str, ManufacturerInOMS, Option<CodeSystemVersion>) {
         match *self {
             Manufacturer::AstraZeneca => (
@@ -421,7 +653,7 @@ impl Manufacturer {
 }
 
 /// Identifies the recognized test types
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
 pub enum TestName {
     /// A Nucleic Acid Amplification Test, with the name of the specific test
     NAAT { name: String }, // nm
@@ -429,3 +661,69 @@ pub enum TestName {
     /// A Rapid Antigen Test, with a string identifying the device from the JRC database
     RAT { device_id: String }, // ma
 }
+
+/// Result of a test, as coded in SNOMED CT
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum TestResult {
+    /// The disease agent has been detected, i.e. the test is positive
+    Detected,
+
+    /// The disease agent has not been detected, i.e. the test is negative
+    NotDetected,
+
+    /// Any other code, not defined by the value set
+    Other(String),
+}
+
+impl TestResult {
+    /// Maps a `tr` code to a test result
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "260373001" => TestResult::Detected,
+            "260415000" => TestResult::NotDetected,
+            _ => TestResult::Other(code.into()),
+        }
+    }
+
+    /// Returns the SNOMED CT code for this result
+    pub fn code(&self) -> &str {
+        match self {
+            TestResult::Detected => "260373001",
+            TestResult::NotDetected => "260415000",
+            TestResult::Other(code) => code,
+        }
+    }
+}
+
+/// Testing method, as coded in LOINC
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum TestType {
+    /// Nucleic Acid Amplification with probe detection, i.e. a molecular test such as RT-PCR
+    NAAT,
+
+    /// Rapid immunoassay, i.e. an antigen test
+    RAT,
+
+    /// Any other code, not defined by the value set
+    Unknown(String),
+}
+
+impl TestType {
+    /// Maps a `tt` code to a test type
+    pub fn from_code(code: &str) -> Self {
+        match code {
+            "LP6464-4" => TestType::NAAT,
+            "LP217198-3" => TestType::RAT,
+            _ => TestType::Unknown(code.into()),
+        }
+    }
+
+    /// Returns the LOINC code for this test type
+    pub fn code(&self) -> &str {
+        match self {
+            TestType::NAAT => "LP6464-4",
+            TestType::RAT => "LP217198-3",
+            TestType::Unknown(code) => code,
+        }
+    }
+}
@@ -0,0 +1,636 @@
+// Signature verification for COSE_Sign1 certificates, as described in section 3.3 of
+// https://ec.europa.eu/health/sites/default/files/ehealth/docs/digital-green-certificates_v3_en.pdf
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use core::fmt;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ciborium::value::Value;
+use p256::ecdsa::signature::Verifier;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use x509_cert::{
+    der::{Decode, Encode},
+    spki::{ObjectIdentifier, SubjectPublicKeyInfoRef},
+    Certificate,
+};
+
+use crate::{Country, Error, HealthCert, Result, MAX_CBOR_DEPTH};
+
+const COUNTRY_NAME: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.5.4.6");
+const EC_PUBLIC_KEY: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+const ECDSA_WITH_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.2");
+const ECDSA_WITH_SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.3");
+const ECDSA_WITH_SHA512: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.4.3.4");
+const RSA_ENCRYPTION: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+const RSASSA_PSS: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.10");
+const SHA256_WITH_RSA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11");
+const SECP256R1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+const SECP384R1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.34");
+const SECP521R1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.35");
+
+// COSE header label of the certificate chain of the signer, see RFC 9360
+const X5CHAIN: i128 = 33;
+
+/// COSE signature algorithms allowed for Digital Green Certificates
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoseAlgorithm {
+    /// ECDSA with SHA-256 over the P-256 curve (COSE id -7)
+    ES256,
+
+    /// ECDSA with SHA-384 over the P-384 curve (COSE id -35)
+    ES384,
+
+    /// ECDSA with SHA-512 over the P-521 curve (COSE id -36)
+    ES512,
+
+    /// RSASSA-PSS with SHA-256 (COSE id -37)
+    PS256,
+}
+
+impl CoseAlgorithm {
+    /// Maps a COSE algorithm identifier, as found in [crate::Signature::algorithm], to a supported algorithm.
+    pub fn from_id(id: i128) -> Option<Self> {
+        match id {
+            -7 => Some(CoseAlgorithm::ES256),
+            -35 => Some(CoseAlgorithm::ES384),
+            -36 => Some(CoseAlgorithm::ES512),
+            -37 => Some(CoseAlgorithm::PS256),
+            _ => None,
+        }
+    }
+
+    /// Returns the COSE identifier for this algorithm
+    pub fn id(&self) -> i128 {
+        match *self {
+            CoseAlgorithm::ES256 => -7,
+            CoseAlgorithm::ES384 => -35,
+            CoseAlgorithm::ES512 => -36,
+            CoseAlgorithm::PS256 => -37,
+        }
+    }
+
+    /// Returns the family of keys this algorithm signs with
+    pub fn key_family(&self) -> KeyFamily {
+        match *self {
+            CoseAlgorithm::ES256 | CoseAlgorithm::ES384 | CoseAlgorithm::ES512 => KeyFamily::Ecdsa,
+            CoseAlgorithm::PS256 => KeyFamily::Rsa,
+        }
+    }
+}
+
+/// Families of public keys, regardless of their curve or size
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyFamily {
+    /// Elliptic curve keys, used by ECDSA
+    Ecdsa,
+
+    /// RSA keys
+    Rsa,
+}
+
+/// Public key of a Document Signer Certificate (DSC), used to check certificate signatures.
+pub enum VerificationKey {
+    /// ECDSA key over the P-256 curve
+    Ecdsa(p256::ecdsa::VerifyingKey),
+
+    /// ECDSA key over the P-384 curve
+    EcdsaP384(p384::ecdsa::VerifyingKey),
+
+    /// ECDSA key over the P-521 curve
+    EcdsaP521(p521::ecdsa::VerifyingKey),
+
+    /// RSA key
+    Rsa(rsa::RsaPublicKey),
+}
+
+impl VerificationKey {
+    /// Loads a key from DER data, either an X.509 certificate or a bare SubjectPublicKeyInfo.
+    pub fn from_der(der: &[u8]) -> Result<Self> {
+        match Certificate::from_der(der) {
+            Ok(cert) => {
+                let spki = cert
+                    .tbs_certificate
+                    .subject_public_key_info
+                    .to_der()
+                    .map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+
+                Self::from_spki_der(&spki)
+            }
+            Err(_) => Self::from_spki_der(der),
+        }
+    }
+
+    /// Loads a key from a PEM-encoded X.509 certificate or public key.
+    pub fn from_pem(pem: &str) -> Result<Self> {
+        let pem = pem::parse(pem).map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+
+        match pem.tag() {
+            "CERTIFICATE" | "PUBLIC KEY" => Self::from_der(pem.contents()),
+            tag => Err(Error::InvalidCertificate(format!(
+                "unexpected PEM block {}",
+                tag
+            ))),
+        }
+    }
+
+    /// Loads a key from either PEM or Base64-encoded DER data, which are the forms commonly found in trust lists.
+    pub fn parse(data: &str) -> Result<Self> {
+        let data = data.trim();
+
+        if data.starts_with("-----BEGIN") {
+            Self::from_pem(data)
+        } else {
+            let der = BASE64
+                .decode(data)
+                .map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+
+            Self::from_der(&der)
+        }
+    }
+
+    /// Returns the family this key belongs to
+    pub fn family(&self) -> KeyFamily {
+        match self {
+            VerificationKey::Ecdsa(_)
+            | VerificationKey::EcdsaP384(_)
+            | VerificationKey::EcdsaP521(_) => KeyFamily::Ecdsa,
+            VerificationKey::Rsa(_) => KeyFamily::Rsa,
+        }
+    }
+
+    fn from_spki_der(der: &[u8]) -> Result<Self> {
+        let spki = SubjectPublicKeyInfoRef::from_der(der)
+            .map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+
+        match spki.algorithm.oid {
+            EC_PUBLIC_KEY => {
+                let point = spki.subject_public_key.raw_bytes();
+
+                match spki.algorithm.parameters_oid() {
+                    Ok(SECP256R1) => p256::ecdsa::VerifyingKey::from_sec1_bytes(point)
+                        .map(VerificationKey::Ecdsa),
+                    Ok(SECP384R1) => p384::ecdsa::VerifyingKey::from_sec1_bytes(point)
+                        .map(VerificationKey::EcdsaP384),
+                    Ok(SECP521R1) => p521::ecdsa::VerifyingKey::from_sec1_bytes(point)
+                        .map(VerificationKey::EcdsaP521),
+                    _ => {
+                        return Err(Error::InvalidCertificate(
+                            "unsupported elliptic curve".into(),
+                        ))
+                    }
+                }
+                .map_err(|e| Error::InvalidCertificate(e.to_string()))
+            }
+            RSA_ENCRYPTION => {
+                use rsa::pkcs8::DecodePublicKey;
+
+                rsa::RsaPublicKey::from_public_key_der(der)
+                    .map(VerificationKey::Rsa)
+                    .map_err(|e| Error::InvalidCertificate(e.to_string()))
+            }
+            oid => Err(Error::InvalidCertificate(format!(
+                "unsupported public key algorithm {}",
+                oid
+            ))),
+        }
+    }
+
+    fn verify(&self, alg: CoseAlgorithm, data: &[u8], signature: &[u8]) -> Result<()> {
+        if alg.key_family() != self.family() {
+            return Err(Error::AlgorithmKeyMismatch {
+                algorithm: alg,
+                key: self.family(),
+            });
+        }
+
+        match (alg, self) {
+            (CoseAlgorithm::ES256, VerificationKey::Ecdsa(key)) => {
+                let sig = p256::ecdsa::Signature::from_slice(signature)
+                    .map_err(|_| Error::InvalidSignature)?;
+
+                key.verify(data, &sig).map_err(|_| Error::InvalidSignature)
+            }
+            (CoseAlgorithm::ES384, VerificationKey::EcdsaP384(key)) => {
+                let sig = p384::ecdsa::Signature::from_slice(signature)
+                    .map_err(|_| Error::InvalidSignature)?;
+
+                key.verify(data, &sig).map_err(|_| Error::InvalidSignature)
+            }
+            (CoseAlgorithm::ES512, VerificationKey::EcdsaP521(key)) => {
+                let sig = p521::ecdsa::Signature::from_slice(signature)
+                    .map_err(|_| Error::InvalidSignature)?;
+
+                key.verify(data, &sig).map_err(|_| Error::InvalidSignature)
+            }
+            (CoseAlgorithm::PS256, VerificationKey::Rsa(key)) => {
+                let key = rsa::pss::VerifyingKey::<Sha256>::new(key.clone());
+                let sig = rsa::pss::Signature::try_from(signature)
+                    .map_err(|_| Error::InvalidSignature)?;
+
+                key.verify(data, &sig).map_err(|_| Error::InvalidSignature)
+            }
+            // the key curve must also match the one mandated by the algorithm
+            _ => Err(Error::InvalidSignature),
+        }
+    }
+
+    // Checks the signature of an X.509 certificate issued with this key
+    pub(crate) fn verifies_certificate(&self, cert: &Certificate) -> bool {
+        match (cert.tbs_certificate.to_der(), cert.signature.as_bytes()) {
+            (Ok(tbs), Some(signature)) => {
+                self.verifies_x509(cert.signature_algorithm.oid, &tbs, signature)
+            }
+            _ => false,
+        }
+    }
+
+    // Checks an X.509 signature (i.e. of a certificate or CRL) made with this key over `tbs`. ECDSA issuers may pick a
+    // hash which does not match their curve, so the digest is computed separately
+    pub(crate) fn verifies_x509(
+        &self,
+        algorithm: ObjectIdentifier,
+        tbs: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        use p256::ecdsa::signature::hazmat::PrehashVerifier;
+
+        let prehash = match algorithm {
+            ECDSA_WITH_SHA256 => Sha256::digest(tbs).to_vec(),
+            ECDSA_WITH_SHA384 => Sha384::digest(tbs).to_vec(),
+            ECDSA_WITH_SHA512 => Sha512::digest(tbs).to_vec(),
+            SHA256_WITH_RSA => {
+                return match self {
+                    VerificationKey::Rsa(key) => rsa::pkcs1v15::Signature::try_from(signature)
+                        .is_ok_and(|sig| {
+                            rsa::pkcs1v15::VerifyingKey::<Sha256>::new(key.clone())
+                                .verify(tbs, &sig)
+                                .is_ok()
+                        }),
+                    _ => false,
+                };
+            }
+            // the PSS parameters are not inspected, as issuers only use SHA-256
+            RSASSA_PSS => {
+                return match self {
+                    VerificationKey::Rsa(key) => rsa::pss::Signature::try_from(signature)
+                        .is_ok_and(|sig| {
+                            rsa::pss::VerifyingKey::<Sha256>::new(key.clone())
+                                .verify(tbs, &sig)
+                                .is_ok()
+                        }),
+                    _ => false,
+                };
+            }
+            _ => return false,
+        };
+
+        match self {
+            VerificationKey::Ecdsa(key) => p256::ecdsa::Signature::from_der(signature)
+                .is_ok_and(|sig| key.verify_prehash(&prehash, &sig).is_ok()),
+            VerificationKey::EcdsaP384(key) => p384::ecdsa::Signature::from_der(signature)
+                .is_ok_and(|sig| key.verify_prehash(&prehash, &sig).is_ok()),
+            VerificationKey::EcdsaP521(key) => p521::ecdsa::Signature::from_der(signature)
+                .is_ok_and(|sig| key.verify_prehash(&prehash, &sig).is_ok()),
+            VerificationKey::Rsa(_) => false,
+        }
+    }
+}
+
+impl fmt::Debug for VerificationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationKey::Ecdsa(key) => f.debug_tuple("Ecdsa").field(key).finish(),
+            VerificationKey::EcdsaP384(key) => f.debug_tuple("EcdsaP384").field(key).finish(),
+            VerificationKey::EcdsaP521(key) => f
+                .debug_tuple("EcdsaP521")
+                .field(&key.to_encoded_point(true))
+                .finish(),
+            VerificationKey::Rsa(key) => f.debug_tuple("Rsa").field(key).finish(),
+        }
+    }
+}
+
+/// Computes the KID of a DER-encoded DSC, i.e. the first 8 bytes of the SHA-256 hash of the certificate.
+pub fn kid_from_der(der: &[u8]) -> [u8; 8] {
+    let mut kid = [0; 8];
+    kid.copy_from_slice(&Sha256::digest(der)[..8]);
+
+    kid
+}
+
+/// Computes the KID of a PEM-encoded DSC, see [kid_from_der].
+pub fn kid_from_pem(pem: &str) -> Result<[u8; 8]> {
+    let pem = pem::parse(pem).map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+
+    match pem.tag() {
+        "CERTIFICATE" => Ok(kid_from_der(pem.contents())),
+        tag => Err(Error::InvalidCertificate(format!(
+            "unexpected PEM block {}",
+            tag
+        ))),
+    }
+}
+
+// Extracts the country from the subject of a DER-encoded DSC; bare public keys carry no country
+fn dsc_country(der: &[u8]) -> Option<Country> {
+    let cert = Certificate::from_der(der).ok()?;
+
+    cert.tbs_certificate
+        .subject
+        .0
+        .iter()
+        .flat_map(|rdn| rdn.0.iter())
+        .find(|atv| atv.oid == COUNTRY_NAME)
+        .and_then(|atv| core::str::from_utf8(atv.value.value()).ok())
+        .map(Country::from)
+}
+
+// Same as dsc_country, for the PEM or Base64-encoded DER data accepted by VerificationKey::parse
+fn dsc_country_from_text(data: &str) -> Option<Country> {
+    let data = data.trim();
+
+    let der = if data.starts_with("-----BEGIN") {
+        pem::parse(data).ok()?.into_contents()
+    } else {
+        BASE64.decode(data).ok()?
+    };
+
+    dsc_country(&der)
+}
+
+#[derive(Debug)]
+struct TrustEntry {
+    key: VerificationKey,
+    country: Option<Country>,
+}
+
+/// Collection of trusted DSC keys, indexed by their key identifier (KID).
+///
+/// Each key may be associated with the country of its DSC, which is taken from the subject of the certificates the
+/// list is loaded from and reported by [HealthCert::verify_with].
+#[derive(Debug, Default)]
+pub struct TrustList {
+    keys: BTreeMap<Vec<u8>, TrustEntry>,
+}
+
+impl TrustList {
+    /// Creates an empty trust list
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a trust list from a JSON object mapping Base64-encoded KIDs to PEM or Base64-encoded DER keys, i.e.
+    /// `{ "2Rk3X8HntrI=": "-----BEGIN CERTIFICATE-----..." }`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let entries: BTreeMap<String, String> = serde_json::from_str(json)?;
+
+        let mut trust = Self::new();
+
+        for (kid, key) in entries {
+            let kid = BASE64
+                .decode(&kid)
+                .map_err(|_| Error::InvalidCertificate(format!("invalid KID {}", kid)))?;
+
+            trust.insert_parsed(kid, &key)?;
+        }
+
+        Ok(trust)
+    }
+
+    /// Loads a trust list in the CBOR format distributed by DGC trust list services, i.e.
+    /// `{ "c": [{ "i": kid, "c": certificate }, ...] }`, where each certificate is DER-encoded.
+    pub fn from_dgc_cbor(bytes: &[u8]) -> Result<Self> {
+        let malformed =
+            |what: &str| Error::InvalidCertificate(format!("malformed trust list: {}", what));
+
+        // the list comes from upstream, so it gets the same depth limit as certificates
+        let root: Value = ciborium::de::from_reader_with_recursion_limit(bytes, MAX_CBOR_DEPTH)
+            .map_err(|e| match e {
+                ciborium::de::Error::RecursionLimitExceeded => Error::CborTooDeep,
+                e => Error::InvalidCertificate(e.to_string()),
+            })?;
+
+        let entries = match cbor_field(&root, "c") {
+            Some(Value::Array(entries)) => entries,
+            _ => return Err(malformed("missing certificate array")),
+        };
+
+        let mut trust = Self::new();
+
+        for entry in entries {
+            match (cbor_field(entry, "i"), cbor_field(entry, "c")) {
+                (Some(Value::Bytes(kid)), Some(Value::Bytes(cert))) => trust.insert_entry(
+                    kid.clone(),
+                    VerificationKey::from_der(cert)?,
+                    dsc_country(cert),
+                ),
+                _ => return Err(malformed("invalid certificate entry")),
+            }
+        }
+
+        Ok(trust)
+    }
+
+    /// Adds a key to the list, replacing any other key with the same KID. The key has no associated country.
+    pub fn insert(&mut self, kid: impl Into<Vec<u8>>, key: VerificationKey) {
+        self.insert_entry(kid.into(), key, None);
+    }
+
+    /// Adds a key issued by the given country to the list, replacing any other key with the same KID
+    pub fn insert_with_country(
+        &mut self,
+        kid: impl Into<Vec<u8>>,
+        key: VerificationKey,
+        country: impl Into<Country>,
+    ) {
+        self.insert_entry(kid.into(), key, Some(country.into()));
+    }
+
+    // Parses a key as VerificationKey::parse does, also recording the country of its DSC
+    pub(crate) fn insert_parsed(&mut self, kid: impl Into<Vec<u8>>, data: &str) -> Result<()> {
+        let key = VerificationKey::parse(data)?;
+        self.insert_entry(kid.into(), key, dsc_country_from_text(data));
+
+        Ok(())
+    }
+
+    fn insert_entry(&mut self, kid: Vec<u8>, key: VerificationKey, country: Option<Country>) {
+        self.keys.insert(kid, TrustEntry { key, country });
+    }
+
+    /// Retrieves the key associated with the given KID
+    pub fn get(&self, kid: &[u8]) -> Option<&VerificationKey> {
+        self.keys.get(kid).map(|entry| &entry.key)
+    }
+
+    /// Retrieves the country of the DSC associated with the given KID, if known
+    pub fn country(&self, kid: &[u8]) -> Option<&Country> {
+        self.keys.get(kid).and_then(|entry| entry.country.as_ref())
+    }
+
+    /// Returns true if the list holds no keys
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Number of keys in the list
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+}
+
+// Looks up a text key in a CBOR map
+fn cbor_field<'a>(map: &'a Value, key: &str) -> Option<&'a Value> {
+    match map {
+        Value::Map(entries) => entries
+            .iter()
+            .find(|(k, _)| matches!(k, Value::Text(k) if k == key))
+            .map(|(_, v)| v),
+        _ => None,
+    }
+}
+
+/// Details about a successfully verified signature
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerificationInfo {
+    /// Country of the DSC that signed the certificate according to the trust list, if known.
+    ///
+    /// Unlike [HealthCert::some_issuer], which is declared by the certificate itself, this cannot be forged without
+    /// access to the DSC private key, so the two should match.
+    pub signer_country: Option<Country>,
+
+    /// KID of the key that verified the signature
+    pub kid: Vec<u8>,
+
+    /// Algorithm the certificate was signed with
+    pub algorithm: CoseAlgorithm,
+}
+
+impl HealthCert {
+    /// Checks the signature of this certificate using the key matching its KID in `trust`, returning which key and
+    /// algorithm verified it.
+    ///
+    /// Only certificates obtained through parsing can be verified, because verification requires the raw COSE data
+    /// stored in [HealthCert::raw].
+    pub fn verify_with(&self, trust: &TrustList) -> Result<VerificationInfo> {
+        let algorithm = CoseAlgorithm::from_id(self.signature.algorithm)
+            .ok_or(Error::UnsupportedAlgorithm(self.signature.algorithm))?;
+
+        let kid = &self.signature.kid;
+        let entry = trust
+            .keys
+            .get(kid)
+            .ok_or_else(|| Error::KeyNotFound(kid.clone()))?;
+
+        entry
+            .key
+            .verify(algorithm, &self.signed_data(), &self.signature.signature)?;
+
+        Ok(VerificationInfo {
+            signer_country: entry.country.clone(),
+            kid: kid.clone(),
+            algorithm,
+        })
+    }
+
+    /// Checks the signature of this certificate using the certificate chain embedded in its COSE header (`x5chain`,
+    /// label 33, see RFC 9360) instead of a trust list, returning which key and algorithm verified it.
+    ///
+    /// The first certificate of the chain must verify the signature. If `roots` is given, each certificate must also
+    /// be issued by the next one, and the last one by one of `roots`, so that the chain leads to a trusted CSCA;
+    /// otherwise the chain is taken as is, which only proves that the certificate was signed by the embedded one.
+    /// Validity periods and extensions of the chain certificates are not checked.
+    pub fn verify_with_x5chain(
+        &self,
+        roots: Option<&[VerificationKey]>,
+    ) -> Result<VerificationInfo> {
+        let algorithm = CoseAlgorithm::from_id(self.signature.algorithm)
+            .ok_or(Error::UnsupportedAlgorithm(self.signature.algorithm))?;
+
+        // a chain made of a single certificate may be stored as is, instead of in an array
+        let chain = match self.headers().rest.remove(&X5CHAIN) {
+            Some(Value::Bytes(cert)) => vec![cert],
+            Some(Value::Array(certs)) => certs
+                .into_iter()
+                .map(|cert| match cert {
+                    Value::Bytes(cert) => Ok(cert),
+                    _ => Err(Error::InvalidFormatFor {
+                        key: "x5chain".into(),
+                    }),
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => {
+                return Err(Error::InvalidFormatFor {
+                    key: "x5chain".into(),
+                })
+            }
+            None => return Err(Error::MissingKey("x5chain".into())),
+        };
+
+        let leaf = chain
+            .first()
+            .ok_or_else(|| Error::MissingKey("x5chain".into()))?;
+
+        VerificationKey::from_der(leaf)?.verify(
+            algorithm,
+            &self.signed_data(),
+            &self.signature.signature,
+        )?;
+
+        if let Some(roots) = roots {
+            let certs = chain
+                .iter()
+                .map(|der| {
+                    Certificate::from_der(der).map_err(|e| Error::InvalidCertificate(e.to_string()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            for (i, pair) in certs.windows(2).enumerate() {
+                if !VerificationKey::from_der(&chain[i + 1])?.verifies_certificate(&pair[0]) {
+                    return Err(Error::UntrustedChain(format!(
+                        "certificate {} is not issued by the next one",
+                        i
+                    )));
+                }
+            }
+
+            let last = certs.last().expect("the chain is not empty");
+
+            if !roots.iter().any(|root| root.verifies_certificate(last)) {
+                return Err(Error::UntrustedChain(
+                    "the chain does not lead to a trusted root".into(),
+                ));
+            }
+        }
+
+        Ok(VerificationInfo {
+            signer_country: dsc_country(leaf),
+            kid: kid_from_der(leaf).to_vec(),
+            algorithm,
+        })
+    }
+
+    /// Returns the data covered by the signature, that is the CBOR-encoded COSE Sig_structure
+    /// `["Signature1", protected, h'', payload]` (RFC 8152, section 4.4), built from the raw protected header and
+    /// payload. This is what [HealthCert::verify_with] checks, and is useful to debug failed verifications.
+    pub fn signed_data(&self) -> Vec<u8> {
+        let sig_structure = Value::Array(vec![
+            Value::Text("Signature1".into()),
+            Value::Bytes(self.raw.protected.clone()),
+            Value::Bytes(Vec::new()),
+            Value::Bytes(self.raw.payload.clone()),
+        ]);
+
+        let mut buf = Vec::new();
+        ciborium::ser::into_writer(&sig_structure, &mut buf).expect("writing to a Vec cannot fail");
+
+        buf
+    }
+}
@@ -0,0 +1,202 @@
+// Data quality issues which do not prevent a certificate from being parsed, but which validators may want to report
+// or act upon.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+use serde_derive::Serialize;
+
+use crate::{is_icao_name, CertInfo, Country, HealthCert, VaccineMedicinalProduct};
+
+/// A soft issue found in a certificate which was nonetheless parsed, see [HealthCert::warnings].
+///
+/// Paths follow the same format as the ones in parsing errors, i.e. `v[0].mp`, and are relative to the pass holding
+/// the entry. In bundles with more than one pass, they are prefixed with the index of the pass, as in
+/// `passes[1].v[0].mp`. The issuer of the bundle has path `iss`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum Warning {
+    /// The sample collection or result time of a test entry is after the certificate was issued, or the result comes
+    /// before the collection, see [Test::is_timestamp_plausible](crate::Test::is_timestamp_plausible)
+    ImplausibleTimestamp { path: String },
+
+    /// The dose number or total dose count of a vaccine entry is zero
+    InvalidDoses {
+        path: String,
+        number: usize,
+        total: usize,
+    },
+
+    /// A standardized name uses characters outside of the ICAO Doc 9303 transliteration, see
+    /// [GreenPass::standardized_names_valid](crate::GreenPass::standardized_names_valid)
+    InvalidStandardizedName { path: String, name: String },
+
+    /// The issuer of a recovery entry is a known authority of a country other than the one the entry declares, see
+    /// [Recovery::issuer_matches_country](crate::Recovery::issuer_matches_country)
+    IssuerCountryMismatch {
+        path: String,
+        issuer: String,
+        country: Country,
+    },
+
+    /// A country code is not a known ISO 3166-1 alpha-2 code
+    UnknownCountry { path: String, country: Country },
+
+    /// The vaccine product of a vaccine entry is not in the value set
+    UnknownVaccine { path: String, product: String },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::ImplausibleTimestamp { path } => {
+                write!(f, "{}: test timestamps do not fit the issue time", path)
+            }
+            Warning::InvalidDoses {
+                path,
+                number,
+                total,
+            } => write!(f, "{}: invalid dose count {}/{}", path, number, total),
+            Warning::InvalidStandardizedName { path, name } => {
+                write!(f, "{}: invalid standardized name {:?}", path, name)
+            }
+            Warning::IssuerCountryMismatch {
+                path,
+                issuer,
+                country,
+            } => write!(
+                f,
+                "{}: issuer {:?} does not belong to country {}",
+                path, issuer, country
+            ),
+            Warning::UnknownCountry { path, country } => {
+                write!(f, "{}: unknown country code {}", path, country)
+            }
+            Warning::UnknownVaccine { path, product } => {
+                write!(f, "{}: unknown vaccine product {}", path, product)
+            }
+        }
+    }
+}
+
+impl HealthCert {
+    /// Returns the data quality issues found in the certificate, such as unknown codes, dose counts of zero, invalid
+    /// standardized names or tests collected after the certificate was issued.
+    ///
+    /// None of these prevents the certificate from being parsed, and some may be legitimate (i.e. a vaccine approved
+    /// after the value sets were last updated); it is up to the caller whether to report them or to reject the
+    /// certificate. The list is empty for well-formed certificates.
+    pub fn warnings(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        if let Some(issuer) = &self.some_issuer {
+            check_country(&mut warnings, "iss".into(), issuer);
+        }
+
+        for (i, pass) in self.passes.iter().enumerate() {
+            let pass_path = |key: String| match self.passes.len() {
+                1 => key,
+                _ => format!("passes[{}].{}", i, key),
+            };
+
+            let names = [
+                ("fnt", Some(&pass.std_surname)),
+                ("gnt", pass.std_givenname.as_ref()),
+            ];
+
+            for (key, name) in names {
+                if let Some(name) = name.filter(|name| !is_icao_name(name)) {
+                    warnings.push(Warning::InvalidStandardizedName {
+                        path: pass_path(format!("nam.{}", key)),
+                        name: name.clone(),
+                    });
+                }
+            }
+
+            // entries are numbered within their group, as in the payload
+            let mut counts = [0; 4];
+
+            for ci in &pass.entries {
+                let (group, slot) = match ci {
+                    CertInfo::Exemption(_) => ("e", 0),
+                    CertInfo::Light(_) | CertInfo::Unknown { .. } => continue,
+                    CertInfo::Recovery(_) => ("r", 1),
+                    CertInfo::Test(_) => ("t", 2),
+                    CertInfo::Vaccine(_) => ("v", 3),
+                };
+
+                let path = |key: &str| pass_path(format!("{}[{}].{}", group, counts[slot], key));
+
+                if let Some(country) = ci.country() {
+                    check_country(&mut warnings, path("co"), country);
+                }
+
+                match ci {
+                    CertInfo::Recovery(r) if r.issuer_matches_country() == Some(false) => warnings
+                        .push(Warning::IssuerCountryMismatch {
+                            path: path("is"),
+                            issuer: r.issuer.clone(),
+                            country: r.country.clone(),
+                        }),
+                    CertInfo::Test(t) if !t.is_timestamp_plausible(self.created) => {
+                        // samples collected in time mean that the result time is the one out of place
+                        let key = if t.collect_ts_utc() > self.created {
+                            "sc"
+                        } else {
+                            "dr"
+                        };
+
+                        warnings.push(Warning::ImplausibleTimestamp { path: path(key) })
+                    }
+                    CertInfo::Vaccine(v) => {
+                        if v.dose_number == 0 || v.dose_total == 0 {
+                            warnings.push(Warning::InvalidDoses {
+                                path: path("dn"),
+                                number: v.dose_number,
+                                total: v.dose_total,
+                            });
+                        }
+
+                        if VaccineMedicinalProduct::from_code(&v.product).is_none() {
+                            warnings.push(Warning::UnknownVaccine {
+                                path: path("mp"),
+                                product: v.product.clone(),
+                            });
+                        }
+                    }
+                    _ => {}
+                }
+
+                counts[slot] += 1;
+            }
+        }
+
+        warnings
+    }
+}
+
+fn check_country(warnings: &mut Vec<Warning>, path: String, country: &Country) {
+    if !country.is_known() {
+        warnings.push(Warning::UnknownCountry {
+            path,
+            country: country.clone(),
+        });
+    }
+}
+
+/// Same as [parse](crate::parse), also returning the data quality issues found in the certificate (see
+/// [HealthCert::warnings]). Structural and encoding problems are still reported as errors.
+///
+/// ```no_run
+/// let (health_cert, warnings) = greenpass::parse_with_warnings("HC1:...").unwrap();
+///
+/// for w in warnings {
+///     eprintln!("warning: {}", w);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn parse_with_warnings(data: &str) -> crate::Result<(HealthCert, Vec<Warning>)> {
+    let hc = crate::parse(data)?;
+    let warnings = hc.warnings();
+
+    Ok((hc, warnings))
+}
@@ -0,0 +1,30 @@
+// Entry points for WebAssembly builds, only available with the `wasm` feature.
+
+use std::error::Error;
+
+use wasm_bindgen::prelude::*;
+
+/// Parses a HC1 string and serializes the resulting certificate as JSON.
+///
+/// Errors are returned as JavaScript `Error` objects, whose message includes the underlying causes.
+#[wasm_bindgen]
+pub fn parse_to_json(input: &str) -> Result<String, JsValue> {
+    let hc = crate::parse(input).map_err(|e| js_error(&e))?;
+
+    serde_json::to_string(&hc).map_err(|e| js_error(&e))
+}
+
+// Flattens an error and its causes into a single message
+fn js_error(e: &dyn Error) -> JsValue {
+    let mut msg = e.to_string();
+
+    let mut source = e.source();
+    while let Some(cause) = source {
+        msg.push_str(": ");
+        msg.push_str(&cause.to_string());
+
+        source = cause.source();
+    }
+
+    JsError::new(&msg).into()
+}
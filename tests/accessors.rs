@@ -0,0 +1,422 @@
+use greenpass::{
+    CertInfo, Disease, DoseInfo, Error, SchemaVersion, TestResult, TestType,
+    VaccineMedicinalProduct, VaccineMismatch,
+};
+
+mod common;
+use common::*;
+
+#[test]
+fn entry_iterators() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    assert_eq!(hc.entries().count(), 1);
+    assert_eq!(hc.recoveries().count(), 0);
+    assert_eq!(hc.tests().count(), 0);
+
+    let vaccines: Vec<_> = hc.vaccines().collect();
+    assert_eq!(vaccines.len(), 1);
+    assert_eq!(vaccines[0].product, "EU/1/20/1528");
+
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+
+    assert_eq!(hc.tests().count(), 1);
+    assert_eq!(hc.vaccines().count(), 0);
+
+    let hc = greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap();
+
+    assert_eq!(hc.recoveries().count(), 1);
+    assert_eq!(hc.tests().count(), 0);
+}
+
+#[test]
+fn test_outcome() {
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+    let test = hc.tests().next().unwrap();
+
+    assert_eq!(test.outcome(), TestResult::NotDetected);
+    assert_eq!(TestResult::from_code("260373001"), TestResult::Detected);
+
+    let unknown = TestResult::from_code("1234");
+    assert_eq!(unknown, TestResult::Other("1234".into()));
+    assert_eq!(unknown.code(), "1234");
+}
+
+#[test]
+fn test_type_kind() {
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+    assert_eq!(hc.tests().next().unwrap().test_type_kind(), TestType::NAAT);
+
+    let hc = greenpass::parse(ANTIGEN_TEST_SAMPLE_PAYLOAD).unwrap();
+    assert_eq!(hc.tests().next().unwrap().test_type_kind(), TestType::RAT);
+
+    assert_eq!(
+        TestType::from_code("LP1234-5"),
+        TestType::Unknown("LP1234-5".into())
+    );
+}
+
+#[test]
+fn dose_info() {
+    let mut hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let vaccine = match &mut hc.passes[0].entries[0] {
+        CertInfo::Vaccine(v) => v,
+        _ => unreachable!(),
+    };
+
+    assert_eq!(
+        vaccine.dose_info().unwrap(),
+        DoseInfo {
+            number: 1,
+            total: 2,
+            is_complete: false,
+            is_booster: false,
+        }
+    );
+
+    vaccine.dose_number = 3;
+
+    let info = vaccine.dose_info().unwrap();
+    assert!(info.is_complete);
+    assert!(info.is_booster);
+
+    vaccine.dose_number = 0;
+
+    assert!(matches!(
+        vaccine.dose_info(),
+        Err(Error::InvalidDoses {
+            number: 0,
+            total: 2
+        })
+    ));
+}
+
+#[test]
+fn schema_version() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let version = hc.passes[0].schema_version().unwrap();
+
+    assert_eq!(
+        version,
+        SchemaVersion {
+            major: 1,
+            minor: 2,
+            patch: 1
+        }
+    );
+    assert!(version.is_supported());
+    assert_eq!(version.to_string(), "1.2.1");
+
+    assert!(!"2.0.0".parse::<SchemaVersion>().unwrap().is_supported());
+    assert!(!"1.4.0".parse::<SchemaVersion>().unwrap().is_supported());
+
+    for invalid in ["1.3", "1.3.0.1", "1.x.0", ""] {
+        assert!(matches!(
+            SchemaVersion::parse(invalid),
+            Err(Error::MalformedSchemaVersion(_))
+        ));
+    }
+}
+
+#[test]
+fn cert_info_kinds() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let entry = hc.entries().next().unwrap().clone();
+
+    assert!(entry.is_vaccine());
+    assert!(!entry.is_test() && !entry.is_recovery() && !entry.is_exemption());
+
+    assert_eq!(entry.as_vaccine().unwrap().product, "EU/1/20/1528");
+    assert!(entry.as_test().is_none());
+    assert!(entry.as_recovery().is_none());
+
+    assert!(entry.clone().into_recovery().is_none());
+    assert_eq!(
+        entry.into_vaccine().unwrap().cert_id,
+        "URN:UVCI:01:AT:10807843F94AEE0EE5093FBC254BD813#B"
+    );
+
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+    let entry = hc.passes[0].entries[0].clone();
+
+    assert!(entry.is_test() && !entry.is_vaccine());
+    assert!(entry.as_test().is_some());
+    assert!(entry.into_test().is_some());
+}
+
+#[test]
+fn vaccine_consistency() {
+    let mut hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let vaccine = match &mut hc.passes[0].entries[0] {
+        CertInfo::Vaccine(v) => v,
+        _ => panic!("not a vaccine"),
+    };
+
+    assert_eq!(
+        VaccineMedicinalProduct::from_code(&vaccine.product),
+        Some(VaccineMedicinalProduct::Comirnaty)
+    );
+    assert!(vaccine.is_consistent());
+
+    // the generic code is fine for any product
+    vaccine.prophylaxis_kind = "J07BX03".into();
+    assert!(vaccine.is_consistent());
+
+    // Vaxzevria is not an mRNA vaccine, nor is it made by Biontech
+    vaccine.product = "EU/1/21/1529".into();
+    vaccine.prophylaxis_kind = "1119349007".into();
+
+    assert!(!vaccine.is_consistent());
+    assert_eq!(
+        vaccine.inconsistencies(),
+        [
+            VaccineMismatch::Manufacturer("ORG-100030215".into()),
+            VaccineMismatch::Prophylaxis("1119349007".into())
+        ]
+    );
+
+    vaccine.market_auth = "ORG-100001699".into();
+    vaccine.prophylaxis_kind = "1119305005".into();
+    assert!(vaccine.is_consistent());
+
+    // nothing can be said about unknown products
+    vaccine.product = "Unknown-Vaccine".into();
+    assert!(vaccine.is_consistent());
+}
+
+#[test]
+fn eu_authorization() {
+    let mut hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let vaccine = match &mut hc.passes[0].entries[0] {
+        CertInfo::Vaccine(v) => v,
+        _ => panic!("not a vaccine"),
+    };
+
+    assert!(VaccineMedicinalProduct::Comirnaty
+        .values()
+        .2
+        .is_eu_authorized());
+    assert_eq!(vaccine.is_eu_authorized(), Some(true));
+
+    // neither products in rolling review nor unauthorized ones count
+    vaccine.product = "CVnCoV".into();
+    assert_eq!(vaccine.is_eu_authorized(), Some(false));
+
+    vaccine.product = "Convidecia".into();
+    assert_eq!(vaccine.is_eu_authorized(), Some(false));
+
+    vaccine.product = "Unknown-Vaccine".into();
+    assert_eq!(vaccine.is_eu_authorized(), None);
+}
+
+#[test]
+fn target_disease() {
+    for payload in [
+        VACCINE_SAMPLE_PAYLOAD,
+        PCR_TEST_SAMPLE_PAYLOAD,
+        RECOVERY_SAMPLE_PAYLOAD,
+    ] {
+        let hc = greenpass::parse(payload).unwrap();
+
+        assert!(hc
+            .entries()
+            .all(|ci| ci.target_disease() == Some(Disease::Covid19)));
+    }
+
+    let mut hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let vaccine = match &mut hc.passes[0].entries[0] {
+        CertInfo::Vaccine(v) => v,
+        _ => panic!("not a vaccine"),
+    };
+
+    assert_eq!(vaccine.target_disease().code(), "840539006");
+
+    vaccine.disease = "6142004".into();
+    assert_eq!(vaccine.target_disease(), Disease::Other("6142004".into()));
+    assert_eq!(vaccine.target_disease().code(), "6142004");
+}
+
+#[test]
+fn present_keys() {
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+    let keys = hc.passes[0].present_keys();
+
+    assert!(keys.contains("nam.gn"));
+    assert!(keys.contains("t.sc"));
+    assert!(keys.contains("t.nm") != keys.contains("t.ma"));
+    assert!(!keys.contains("v"));
+
+    let mut pass = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap().passes[0].clone();
+    pass.givenname = None;
+    pass.std_givenname = None;
+
+    let keys = pass.present_keys();
+
+    assert!(keys.contains("nam.fn"));
+    assert!(keys.contains("nam.fnt"));
+    assert!(!keys.contains("nam.gn"));
+    assert!(!keys.contains("nam.gnt"));
+    assert!(keys.contains("v.dn"));
+    assert!(!keys.iter().any(|k| k.starts_with("t.")));
+}
+
+#[test]
+fn latest_entries() {
+    let mut pass = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap().passes[0].clone();
+    let first = pass.entries[0].as_vaccine().unwrap().clone();
+
+    let mut second = first.clone();
+    second.dose_number = 2;
+    second.date = chrono::NaiveDate::from_ymd(2021, 2, 19).into();
+
+    let mut tied = second.clone();
+    tied.cert_id = "URN:UVCI:01:AT:TIED".into();
+
+    pass.entries = vec![
+        CertInfo::Vaccine(first.clone()),
+        CertInfo::Vaccine(second.clone()),
+        CertInfo::Vaccine(tied),
+    ];
+
+    assert_eq!(pass.latest_vaccine(), Some(&second));
+    assert_eq!(pass.latest_test(), None);
+    assert_eq!(pass.primary_entry(), Some(&CertInfo::Vaccine(second)));
+
+    let test_pass = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap().passes[0].clone();
+    let test = test_pass.entries[0].clone();
+
+    pass.entries.push(test.clone());
+
+    assert_eq!(pass.latest_test(), test.as_test());
+    assert_eq!(pass.primary_entry(), Some(&test));
+
+    pass.entries.clear();
+    assert_eq!(pass.primary_entry(), None);
+}
+
+#[test]
+fn single_pass() {
+    let mut hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    assert!(hc.is_single());
+    assert_eq!(hc.single_pass().unwrap(), &hc.passes[0]);
+
+    hc.passes.push(hc.passes[0].clone());
+
+    assert!(!hc.is_single());
+    assert!(matches!(hc.single_pass(), Err(Error::MultiplePasses(2))));
+
+    hc.passes.clear();
+
+    assert!(!hc.is_single());
+    assert!(matches!(hc.single_pass(), Err(Error::NoPasses)));
+}
+
+#[test]
+fn same_holder() {
+    let pass = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap().passes[0].clone();
+    assert!(pass.same_holder(&pass));
+
+    // only the standardized names matter, which are compared ignoring case and whitespace
+    let mut other = pass.clone();
+    other.surname = Some("Musterfrau-Goessinger".into());
+    other.std_surname = format!(" {} ", pass.std_surname.to_lowercase());
+    other.date_of_birth = format!("{} ", pass.date_of_birth);
+    assert!(pass.same_holder(&other));
+
+    let mut other = pass.clone();
+    other.std_givenname = None;
+    assert!(!pass.same_holder(&other));
+
+    let mut other = pass.clone();
+    other.date_of_birth = "1998-02-27".into();
+    assert!(!pass.same_holder(&other));
+
+    // passes without a nam map have nothing to compare
+    let mut other = pass.clone();
+    other.std_surname.clear();
+    other.std_givenname = None;
+    assert!(!other.same_holder(&other));
+
+    let mut other = pass.clone();
+    other.std_surname = "MUSTERMANN".into();
+    assert!(!pass.same_holder(&other));
+
+    // the samples share their holder, despite holding different entries
+    let test_pass = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap().passes[0].clone();
+    assert!(pass.same_holder(&test_pass));
+}
+
+#[test]
+fn value_set_listing() {
+    use greenpass::{Manufacturer, VaccineProphylaxis};
+
+    assert_eq!(VaccineMedicinalProduct::all(), VaccineMedicinalProduct::ALL);
+    assert_eq!(Manufacturer::all().len(), Manufacturer::ALL.len());
+    assert_eq!(VaccineProphylaxis::all().len(), 3);
+    assert_eq!(Disease::all(), [Disease::Covid19]);
+
+    let products: Vec<_> = VaccineMedicinalProduct::codes().collect();
+    assert!(products.contains(&("EU/1/20/1528", "Comirnaty")));
+
+    // aliases sharing a code are only listed once
+    let mut codes: Vec<_> = products.iter().map(|(code, _)| code).collect();
+    codes.sort();
+    codes.dedup();
+    assert_eq!(codes.len(), products.len());
+
+    assert_eq!(
+        VaccineMedicinalProduct::ChAdOx1nCoV19.manufacturers(),
+        VaccineMedicinalProduct::Covishield.manufacturers()
+    );
+
+    // every code maps back to its own entry
+    for (code, _) in Manufacturer::codes() {
+        assert_eq!(Manufacturer::from_code(code).unwrap().values().0, code);
+    }
+
+    assert!(VaccineProphylaxis::codes().any(|(code, _)| code == "1119349007"));
+    assert_eq!(
+        Disease::codes().collect::<Vec<_>>(),
+        [("840539006", "COVID-19")]
+    );
+    assert_eq!(Disease::from_code("123").name(), "123");
+}
+
+#[test]
+fn value_set_codes() {
+    use greenpass::{Manufacturer, VaccineProphylaxis};
+
+    // codes are compared verbatim against certificates, so they must not carry any note from the value set document
+    let bare = |code: &str| {
+        !code.is_empty()
+            && code
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '/')
+    };
+
+    for &mp in VaccineMedicinalProduct::ALL {
+        let code = mp.values().0;
+
+        assert!(bare(code), "{:?} has code {:?}", mp, code);
+        assert_eq!(VaccineMedicinalProduct::from_code(code), Some(mp));
+    }
+
+    for &ma in Manufacturer::ALL {
+        assert!(bare(ma.values().0), "{:?} has code {:?}", ma, ma.values().0);
+    }
+
+    for &vp in VaccineProphylaxis::ALL {
+        assert!(bare(vp.values().0), "{:?} has code {:?}", vp, vp.values().0);
+    }
+
+    assert_eq!(
+        VaccineMedicinalProduct::from_code("Sputnik-V"),
+        Some(VaccineMedicinalProduct::SputnikV)
+    );
+    assert_eq!(
+        VaccineMedicinalProduct::from_code("NVX-CoV2373"),
+        Some(VaccineMedicinalProduct::NVXCoV2373)
+    );
+}
@@ -0,0 +1,145 @@
+use ciborium::value::Value;
+use greenpass::{CertInfo, CertInfoRef, Error, HealthCert, HealthCertRef, TestName};
+
+mod common;
+use common::*;
+
+// Checks that `s` points into `buf`, rather than into a copy
+fn borrows_from(s: &str, buf: &[u8]) -> bool {
+    buf.as_ptr_range().contains(&s.as_ptr())
+}
+
+#[test]
+fn matches_owned_parse() {
+    for hc1 in [
+        VACCINE_SAMPLE_PAYLOAD,
+        PCR_TEST_SAMPLE_PAYLOAD,
+        ANTIGEN_TEST_SAMPLE_PAYLOAD,
+        RECOVERY_SAMPLE_PAYLOAD,
+    ] {
+        let buf = greenpass::decode_payload(hc1).unwrap();
+
+        let owned = HealthCert::from_cose_bytes(&buf).unwrap();
+        let hc = HealthCertRef::from_cose_bytes(&buf).unwrap();
+
+        assert_eq!(
+            hc.some_issuer,
+            owned.some_issuer.as_ref().map(|c| c.alpha2())
+        );
+        assert_eq!(
+            (hc.created, hc.expires, hc.not_before),
+            (owned.created, owned.expires, owned.not_before)
+        );
+        assert_eq!(hc.kid, owned.signature.kid);
+        assert_eq!(hc.algorithm, owned.signature.algorithm);
+        assert_eq!(hc.signature, owned.signature.signature);
+
+        let (pass, owned_pass) = (&hc.passes[0], &owned.passes[0]);
+
+        assert_eq!(pass.date_of_birth, owned_pass.date_of_birth);
+        assert_eq!(pass.surname, owned_pass.surname.as_deref());
+        assert_eq!(pass.std_surname, owned_pass.std_surname);
+        assert_eq!(pass.std_givenname, owned_pass.std_givenname.as_deref());
+        assert_eq!(pass.ver, owned_pass.ver);
+        assert!(borrows_from(pass.std_surname, &buf));
+
+        match (&pass.entries[0], &owned_pass.entries[0]) {
+            (CertInfoRef::Recovery(r), CertInfo::Recovery(owned)) => {
+                assert_eq!(r.cert_id, owned.cert_id);
+                assert_eq!(r.valid_until, owned.valid_until.to_string());
+            }
+            (CertInfoRef::Test(t), CertInfo::Test(owned)) => {
+                assert_eq!(t.cert_id, owned.cert_id);
+                assert_eq!(t.result, owned.result);
+
+                match &owned.name {
+                    TestName::NAAT { name } => assert_eq!(t.name, Some(name.as_str())),
+                    TestName::RAT { device_id } => {
+                        assert_eq!(t.device_id, Some(device_id.as_str()))
+                    }
+                }
+            }
+            (CertInfoRef::Vaccine(v), CertInfo::Vaccine(owned)) => {
+                assert_eq!(v.cert_id, owned.cert_id);
+                assert_eq!(v.product, owned.product);
+                assert_eq!(
+                    (v.dose_number, v.dose_total),
+                    (owned.dose_number, owned.dose_total)
+                );
+                assert!(borrows_from(v.cert_id, &buf));
+            }
+            (entry, owned) => panic!("{:?} parsed as {:?}", owned, entry),
+        }
+    }
+}
+
+#[test]
+fn borrowed_errors() {
+    let buf = greenpass::decode_payload(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    assert!(matches!(
+        HealthCertRef::from_cose_bytes(&buf[..buf.len() - 1]),
+        Err(Error::MalformedCBOR { .. })
+    ));
+
+    // 100k nested single-element arrays
+    let mut nested = vec![0x81; 100_000];
+    nested.push(0x00);
+
+    assert!(matches!(
+        HealthCertRef::from_cose_bytes(&nested),
+        Err(Error::CborTooDeep)
+    ));
+
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dn", text("1"));
+    });
+    let buf = greenpass::decode_payload(&hc1).unwrap();
+
+    assert!(matches!(
+        HealthCertRef::from_cose_bytes(&buf),
+        Err(Error::InvalidFormatFor { key }) if key == "v[0].dn"
+    ));
+
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        vaccine.retain(|(k, _)| *k != Value::Text("mp".into()));
+    });
+    let buf = greenpass::decode_payload(&hc1).unwrap();
+
+    assert!(matches!(
+        HealthCertRef::from_cose_bytes(&buf),
+        Err(Error::MissingKey(key)) if key == "v[0].mp"
+    ));
+}
+
+#[test]
+fn duplicate_keys() {
+    let hc1 = with_pass(VACCINE_SAMPLE_PAYLOAD, |pass| {
+        pass.push((text("dob"), text("1970-01-01")));
+    });
+    let hc1 = with_entry(&hc1, "v", |vaccine| {
+        vaccine.push((text("ci"), text("URN:UVCI:01:IT:DUPLICATE")));
+    });
+    let hc1 = with_payload(&hc1, |claims| {
+        claims.push((
+            Value::Integer(4.into()),
+            Value::Integer(2_000_000_000.into()),
+        ));
+    });
+    let buf = greenpass::decode_payload(&hc1).unwrap();
+
+    let owned = HealthCert::from_cose_bytes(&buf).unwrap();
+    let hc = HealthCertRef::from_cose_bytes(&buf).unwrap();
+
+    assert_eq!(hc.expires, owned.expires);
+    assert_eq!(hc.passes[0].date_of_birth, "1970-01-01");
+    assert_eq!(hc.passes[0].date_of_birth, owned.passes[0].date_of_birth);
+
+    match (&hc.passes[0].entries[0], &owned.passes[0].entries[0]) {
+        (CertInfoRef::Vaccine(v), CertInfo::Vaccine(owned)) => {
+            assert_eq!(v.cert_id, "URN:UVCI:01:IT:DUPLICATE");
+            assert_eq!(v.cert_id, owned.cert_id);
+        }
+        (entry, owned) => panic!("{:?} parsed as {:?}", owned, entry),
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "builder")]
+
+use chrono::prelude::*;
+use greenpass::{Error, Recovery, Test, TestName, TestResult, Vaccine};
+
+mod common;
+use common::*;
+
+#[test]
+fn build_vaccine() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let vaccine = Vaccine::builder()
+        .cert_id("URN:UVCI:01:AT:10807843F94AEE0EE5093FBC254BD813#B")
+        .country("AT")
+        .date(NaiveDate::from_ymd(2021, 2, 18))
+        .dose_number(1usize)
+        .dose_total(2usize)
+        .issuer("Ministry of Health, Austria")
+        .market_auth("ORG-100030215")
+        .product("EU/1/20/1528")
+        .prophylaxis_kind("1119349007")
+        .build()
+        .unwrap();
+
+    assert_eq!(&vaccine, hc.vaccines().next().unwrap());
+}
+
+#[test]
+fn build_test() {
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+
+    let test = Test::builder()
+        .cert_id("URN:UVCI:01:AT:B5921A35D6A0D696421B3E2462178297#I")
+        .collect_ts(FixedOffset::east(0).ymd(2021, 2, 20).and_hms(4, 34, 56))
+        .country("AT")
+        .issuer("Ministry of Health, Austria")
+        .name(TestName::NAAT {
+            name: "Roche LightCycler qPCR".into(),
+        })
+        .result(TestResult::NotDetected.code())
+        .test_type("LP6464-4")
+        .testing_centre("Testing center Vienna 1")
+        .build()
+        .unwrap();
+
+    assert_eq!(&test, hc.tests().next().unwrap());
+}
+
+#[test]
+fn build_incomplete() {
+    let recovery = Recovery::builder()
+        .cert_id("URN:UVCI:01:AT:858CC18CFCF5965EF82F60E493349AA5#K")
+        .country("AT")
+        .diagnosed(NaiveDate::from_ymd(2021, 2, 20))
+        .issuer("Ministry of Health, Austria")
+        .valid_from(NaiveDate::from_ymd(2021, 4, 4));
+
+    assert!(matches!(
+        recovery.build(),
+        Err(Error::MissingKey(k)) if k == "du"
+    ));
+}
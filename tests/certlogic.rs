@@ -0,0 +1,195 @@
+#![cfg(feature = "certlogic")]
+
+use std::collections::BTreeMap;
+
+use chrono::prelude::*;
+use greenpass::{CertLogicRule, ExternalParams, RuleOutcome, RuleResult};
+use serde_json::json;
+
+mod common;
+use common::*;
+
+fn external(clock: DateTime<Utc>) -> ExternalParams {
+    let mut value_sets = BTreeMap::new();
+    value_sets.insert(
+        "EU-Vaccines".to_string(),
+        vec!["EU/1/20/1528".to_string(), "EU/1/20/1507".to_string()],
+    );
+
+    ExternalParams {
+        validation_clock: clock,
+        value_sets,
+        country_code: "IT".into(),
+    }
+}
+
+fn rule(identifier: &str, logic: serde_json::Value) -> CertLogicRule {
+    serde_json::from_value(json!({
+        "Identifier": identifier,
+        "Type": "Acceptance",
+        "Country": "IT",
+        "Logic": logic,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn vaccine_rules() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let rules = [
+        rule(
+            "VR-IT-0000",
+            json!({
+                "if": [
+                    { "var": "payload.v.0" },
+                    { "in": [{ "var": "payload.v.0.mp" }, { "var": "external.valueSets.EU-Vaccines" }] },
+                    true
+                ]
+            }),
+        ),
+        rule(
+            "VR-IT-0001",
+            json!({
+                "if": [
+                    { "var": "payload.v.0" },
+                    { "<=": [{ "var": "payload.v.0.dn" }, { "var": "payload.v.0.sd" }] },
+                    true
+                ]
+            }),
+        ),
+        rule(
+            "VR-IT-0002",
+            json!({
+                "not-before": [
+                    { "plusTime": [{ "var": "external.validationClock" }, 0, "day"] },
+                    { "plusTime": [{ "var": "payload.v.0.dt" }, 14, "day"] }
+                ]
+            }),
+        ),
+        rule(
+            "VR-IT-0003",
+            json!({ "===": [{ "extractFromUVCI": [{ "var": "payload.v.0.ci" }, 1] }, "AT"] }),
+        ),
+    ];
+
+    let results = hc.evaluate_rules(&rules, &external(Utc.ymd(2021, 3, 10).and_hms(12, 0, 0)));
+
+    assert_eq!(
+        results,
+        ["VR-IT-0000", "VR-IT-0001", "VR-IT-0002", "VR-IT-0003"]
+            .iter()
+            .map(|id| RuleResult {
+                identifier: id.to_string(),
+                outcome: RuleOutcome::Passed,
+            })
+            .collect::<Vec<_>>()
+    );
+
+    let results = hc.evaluate_rules(&rules, &external(Utc.ymd(2021, 2, 20).and_hms(12, 0, 0)));
+
+    assert_eq!(results[2].identifier, "VR-IT-0002");
+    assert_eq!(results[2].outcome, RuleOutcome::Failed);
+}
+
+#[test]
+fn date_operations() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let rules = [
+        rule(
+            "month-end",
+            json!({ "===": [{ "plusTime": ["2021-01-31", 1, "month"] }, "2021-02-28T00:00:00Z"] }),
+        ),
+        rule(
+            "adult",
+            json!({
+                "not-after": [
+                    { "plusTime": [{ "dccDateOfBirth": [{ "var": "payload.dob" }] }, 18, "year"] },
+                    { "var": "external.validationClock" }
+                ]
+            }),
+        ),
+        rule(
+            "partial-dob",
+            json!({ "===": [{ "dccDateOfBirth": ["1998-02"] }, "1998-02-28"] }),
+        ),
+        rule(
+            "doses",
+            json!({
+                "===": [
+                    {
+                        "reduce": [
+                            { "var": "payload.v" },
+                            { "+": [{ "var": "accumulator" }, { "var": "current.dn" }] },
+                            0
+                        ]
+                    },
+                    1
+                ]
+            }),
+        ),
+    ];
+
+    let results = hc.evaluate_rules(&rules, &external(Utc.ymd(2021, 3, 1).and_hms(0, 0, 0)));
+
+    assert!(
+        results.iter().all(|r| r.outcome == RuleOutcome::Passed),
+        "{:?}",
+        results
+    );
+}
+
+#[test]
+fn truthiness() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let rules = [
+        rule(
+            "empty-object",
+            json!({ "!": [{ "var": "external.valueSets" }] }),
+        ),
+        rule(
+            "object",
+            json!({ "if": [{ "var": "payload.nam" }, true, false] }),
+        ),
+        rule("empty-array", json!({ "!": [[]] })),
+        rule("zero", json!({ "!": [0] })),
+    ];
+
+    let mut external = external(Utc.ymd(2021, 3, 1).and_hms(0, 0, 0));
+    external.value_sets.clear();
+
+    let results = hc.evaluate_rules(&rules, &external);
+
+    assert!(
+        results.iter().all(|r| r.outcome == RuleOutcome::Passed),
+        "{:?}",
+        results
+    );
+}
+
+#[test]
+fn rule_errors() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let rules = [
+        rule("unknown-op", json!({ "frobnicate": [1, 2] })),
+        rule("non-boolean", json!({ "var": "payload.dob" })),
+        rule(
+            "bad-date",
+            json!({ "after": [{ "var": "payload.nam.fn" }, "2021-01-01"] }),
+        ),
+        rule(
+            "missing",
+            json!({ "===": [{ "var": "payload.r.0.fr" }, null] }),
+        ),
+    ];
+
+    let results = hc.evaluate_rules(&rules, &external(Utc.ymd(2021, 3, 1).and_hms(0, 0, 0)));
+
+    assert!(matches!(results[0].outcome, RuleOutcome::Error(_)));
+    assert!(matches!(results[1].outcome, RuleOutcome::Error(_)));
+    assert!(matches!(results[2].outcome, RuleOutcome::Error(_)));
+    assert_eq!(results[3].outcome, RuleOutcome::Passed);
+}
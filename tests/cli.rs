@@ -0,0 +1,79 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+mod common;
+use common::*;
+
+// Runs the CLI on `input` read from stdin, returning whether it succeeded and what it printed to stderr
+fn run(input: &[u8]) -> (bool, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_greenpass"))
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input).unwrap();
+
+    let out = child.wait_with_output().unwrap();
+
+    (
+        out.status.success(),
+        String::from_utf8_lossy(&out.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn no_certificate_in_input() {
+    let (ok, stderr) = run(b" \n\t\n");
+
+    assert!(!ok);
+    assert!(stderr.contains("no HC1 certificate found"), "{}", stderr);
+
+    let (ok, stderr) = run(b"\xff\xfe not a certificate\n");
+
+    assert!(!ok);
+    assert!(stderr.contains("missing initial HC string"), "{}", stderr);
+}
+
+#[test]
+fn non_utf8_lines() {
+    let (head, tail) = VACCINE_SAMPLE_PAYLOAD.split_at(20);
+
+    let mut input = Vec::new();
+    input.extend_from_slice(VACCINE_SAMPLE_PAYLOAD.as_bytes());
+    input.extend_from_slice(b"\xff\r\n\nnot a certificate\n");
+    input.extend_from_slice(head.as_bytes());
+    input.push(0xfe);
+    input.extend_from_slice(tail.as_bytes());
+    input.push(b'\n');
+    input.extend_from_slice(VACCINE_SAMPLE_PAYLOAD.as_bytes());
+
+    let (ok, stderr) = run(&input);
+
+    assert!(!ok);
+
+    // line numbers refer to the input, blank lines included
+    assert!(
+        stderr.contains("error: line 3: missing initial HC string"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("error: line 4: invalid base45 in input: invalid character"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("error: line 1:"), "{}", stderr);
+    assert!(!stderr.contains("error: line 5:"), "{}", stderr);
+    assert!(
+        stderr.contains("2 of 4 certificates could not be parsed"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,186 @@
+#![allow(dead_code)]
+
+use std::{
+    collections::BTreeMap,
+    io::{Read, Write},
+};
+
+use ciborium::value::Value;
+use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
+
+// Sample certificates shared between tests
+
+pub const RECOVERY_SAMPLE_PAYLOAD : &str = "HC1:NCFOXN%TS3DH3ZSUZK+.V0ETD%65NL-AH-XIIOOA+IWBCNQ5GJL-XVRJAKD93B4:ZH6I1$4JF 2K%5+G9F.PNF67J6UW6LEQV46PK9E:00$4*2DN43U*0CEBQ/GXQFY73CIBC:GVEBBIBBL7BIB4UNAWNJKBOJJ5PNT53/FJ8FN96B2M3-6BHI7UG55:44$28A9H0D3ZCL4JMYAZ+S-A5$XKX6T2YC 35H/ITX8GL2TK96L6SR9MU9DV5 R1BPIZKH03RW63LD3LS4JYK9EFH78$ZJ*DJ3Q4+Y5V$K2:6.77/Z6KZ5LD6E6P 9SH87/YQJ/RL35+Y5P Q*8D$JD IBCLCK5MJMS68H36DH.K:Z28AL**I3DN3F7MHFEVV%*4HBTSCNT 4C%C47TO*47*KB*KYQT3LT+*4.$S6ZC0JB%JBK:TBSH6FN-G6N%VPJ13M9K9J8*TPB12-VRSQ8XMA6EBNVC28:APQ71K:ON4K25A:I9/-HUZGOZFB04YME%6DAUNO2B+TQG/2I0A4Z5NDROBVKS0J$28XG";
+pub const PCR_TEST_SAMPLE_PAYLOAD : &str = "HC1:NCFOXN%TS3DH3ZSUZK+.V0ETD%65NL-AHBP1IOOA+IS7C$068WAD1W7:BAT4V22F/8X*G3M9BM9Z0BFU2P4JY73JC3KD34LT7A3-4386BXSJ$IJGX8.+IIYC6Q0ZIJPKJ+LJ%2TK/IS/SR4DKJ5QWCB4DN57E-4LXKV85HZ0T+0K%I17JLXKB6J57TJK57ALT$I/+GDG6Z$U*C2OQ1:PIGEGEV4*2DN43U*0CEBQ/GXQFY73CIBC:GUC7QHBN83GG3NQN%976FNXEB.FJN83HB3EG3CAJTA3ANBXEBGM5J%44$28A9H0D3ZCL4JMYAZ+S-A5$XKX6T2YC 35H/ITX8GL2TK96L6SR9MU9DV5 R1JNI:E4I+C7*4M:KCY07LPMIH-O9XZQSH9R$FXQGDVBK*RZP3:*DG1W7SGT$7S%RMSG2UQYI96GGLXK6*K$X4FUTD14//EF.712U0$89NT2V457U8+9W2KQ-7LF9-DF07U$B97JJ1D7WKP/HLIJLRKF1MFHJP7NVDEBU1J6+2FBKBSHNAIFVV%KN$2W5+IKDP6SFPQC16LITI/-7P E:ZSYMJS$5-BPDMFAMBJ7TN7FMRHL:19XI9X5ZL36%OGODHIFE8SHLH0ZLG$DDZG$DR-00*%E%4";
+pub const ANTIGEN_TEST_SAMPLE_PAYLOAD : &str = "HC1:NCFOXN%TS3DH3ZSUZK+.V0ETD%65NL-AH+VEIOOA+ILACMB9GJL. V/1KKD93B4:ZH6I1$4JM:IP1MUF5Z$5NF67J6QW6%PQSE6-96XNM6-6SF6IRH*PPKS9.Q6%%6%E5%NPC71RF6+17S%MBX6PF51$5DB97-59$PO1BD/9BL5$W5151-V9NVP.SA0T932QHG3JZILDB523G*S2U2V8TQEDK8CD/SYJC.0EPSTBVC9ND2SSIYDNYDF*S8/D7BCU5TL0D BD$8D8ND1UT*WD.XI5TBW1UYIJGDBGIASJLA8KOHSLOJJPAOJKCGSFC17KPDS9+E93ZM$96PZ6+Q6X46KE0.43RA3/43KD3F23/9TL4T1C9 UPVD5BT17$1MV15K1DR1FIEC2F5+1T+UC2FSH9 UP+/UEOJDKBO.AI9BVYTOCFOPS788O5L9Y4KCT:WC.L76V0VSNRCN /KU%CW.4WV2L4L$XKV7J$%25I3IC33835AL5:4A93QF08T1+G3N313SGXVA.-ND3JH/F.*OXNENHN%C36$EQU3*7LT AOH1N6OJZF3RTF7LS8BJFTGAH%BO3GA7QH*VTP7P.1GA G-/N5Y7T UDEUGBDSHNJ50/BHS2";
+pub const VACCINE_SAMPLE_PAYLOAD : &str = "HC1:NCFOXN%TS3DH3ZSUZK+.V0ETD%65NL-AH-R6IOOA+IZJS:A8GJL*XV%O3+QI6M8SA3/-2E%5VR5VVB9ZILAPIZI.EJJ14B2MZ8DC8COVD9VC/MJK.A+ C/8DXED%JCC8C62KXJAUYCOS2QW6%PQRZMPK9I+0MCIKYJGCC:H3J1D1I3-*TW CXBDW33+ CD8CQ8C0EC%*TGHD1KT0NDPST7KDQHDN8TSVD2NDB*S6ECX%LBZI+PB/VSQOL9DLKWCZ3EBKD8IIGDB0D48UJ06J9UBSVAXCIF4LEIIPBJ7OICWK%5BBS22T9UF5LDCPF5RBQ746B46JZ0V-OEA7IB6$C94JB2E9Z3E8AE-QD+PB.QCD-H/8O3BEQ8L9VN.6A4JBLHLM7A$JD IBCLCK5MJMS68H36DH.K:Z28AL**I3DN3F7MHFEVV%*4HBTSCNT 4C%C47TO*47*KB*KYQT3LT+*4.$S6ZC0JB%JB% NHTC:OS6K7C*M9$4HOUJJ8BZR+AB9MRX.JK/DXQC*%ESNO3VVS$EO:9-14:6VEVUOEKF4PSTT:L9HRJKP4H6RAOV%Q7RB1P 9OG2O%LAULC97*JLUEH";
+// This certificate is taken from the German CovPass app
+// https://raw.githubusercontent.com/Digitaler-Impfnachweis/covpass-android/0ef39462ba162303a156942aedb468c7de353ba7/covpass-sdk/src/test/resources/vaccination-cert.txt
+// It stores the KID in the unprotected properties
+pub const VACCINE_SAMPLE_PAYLOAD_UNPROTECTED_KID : &str = "HC1:6BFOXN*TS0BI$ZD4N9:9S6RCVN5+O30K3/XIV0W23NTDEXWK G2EP4J0BGJLOFJEIKVGAE%9ETMSA3/-2E%5VR5VVBJZILDBZ8D%JTQOL0EC7KD/ZL/8D:8DQVDLED0AC2AU/B2/+3HN2HPCT12IID*2T$/TVPTM*SQYDLADYR3JZIM-1U96UX4795L*KDYPWGO+9AKCO.BHOH63K5 *JAYUQJAGPENXUJRHQJA5G6VOE:OQPAU:IAJ0AZZ0OWCR/C+T4D-4HRVUMNMD3323R1392VC-4A+2XEN QT QTHC31M3+E3CP456L X4CZKHKB-43:C3J:R90JK.A5*G%DBZI9$JAQJKKIJX2MM+GWHKSKE MCAOI8%MQQK8+S4-R:KIIX0VJA$:O3HH:EF9NT6D7.Z8OMR-C137HZW2$XK6AL4%IYT0BUF1MFXZG$IV6$0+BN$MVYWV9Y4KCT7-S$ 0GKFCTR0KV4$0RCNV7J$%25I3HC3X83P47YOR40F80U8EHL%BP0CC9R$SEN59KYL 2O1/7*HVNY6:W0..DXJ5YKV4/J/JVZPRD*S0ZV+IR5H7*QS7%JX7HU0PA0PLY705JM/RA73CE3FBI";
+
+/// Decodes a HC1 string into the four elements of its COSE_Sign1 structure
+pub fn decode_cose(hc1: &str) -> Vec<Value> {
+    let defl = base45::decode(hc1.trim_start_matches("HC1:")).unwrap();
+
+    let mut data = Vec::new();
+    ZlibDecoder::new(&defl[..]).read_to_end(&mut data).unwrap();
+
+    match ciborium::de::from_reader(&data[..]).unwrap() {
+        Value::Array(arr) => arr,
+        Value::Tag(_, v) => match *v {
+            Value::Array(arr) => arr,
+            _ => panic!("not a COSE_Sign1"),
+        },
+        _ => panic!("not a COSE_Sign1"),
+    }
+}
+
+/// Encodes a COSE_Sign1 structure back into a HC1 string
+pub fn encode_cose(cose: Vec<Value>) -> String {
+    let mut cbor = Vec::new();
+    ciborium::ser::into_writer(&Value::Array(cose), &mut cbor).unwrap();
+
+    let mut enc = ZlibEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(&cbor).unwrap();
+
+    format!("HC1:{}", base45::encode_from_buffer(enc.finish().unwrap()))
+}
+
+/// Serializes a value as CBOR
+pub fn to_cbor(v: &Value) -> Vec<u8> {
+    let mut buf = Vec::new();
+    ciborium::ser::into_writer(v, &mut buf).unwrap();
+
+    buf
+}
+
+/// Builds the COSE Sig_structure for the given protected header and payload
+pub fn sig_structure(protected: &[u8], payload: &[u8]) -> Vec<u8> {
+    to_cbor(&Value::Array(vec![
+        Value::Text("Signature1".into()),
+        Value::Bytes(protected.to_vec()),
+        Value::Bytes(Vec::new()),
+        Value::Bytes(payload.to_vec()),
+    ]))
+}
+
+/// Builds a protected header holding the given algorithm and KID
+pub fn protected_header(alg: i64, kid: &[u8]) -> Vec<u8> {
+    to_cbor(&Value::Map(vec![
+        (Value::Integer(1.into()), Value::Integer(alg.into())),
+        (Value::Integer(4.into()), Value::Bytes(kid.to_vec())),
+    ]))
+}
+
+/// Replaces the protected header of a sample certificate and signs it again with `sign`
+pub fn resign(hc1: &str, protected: Vec<u8>, sign: impl Fn(&[u8]) -> Vec<u8>) -> String {
+    let mut cose = decode_cose(hc1);
+
+    let payload = match &cose[2] {
+        Value::Bytes(bys) => bys.clone(),
+        _ => panic!("invalid payload"),
+    };
+
+    let signature = sign(&sig_structure(&protected, &payload));
+
+    cose[0] = Value::Bytes(protected);
+    cose[3] = Value::Bytes(signature);
+
+    encode_cose(cose)
+}
+
+/// Applies `f` to the CWT payload map of a sample certificate, returning the modified (unsigned) certificate
+pub fn with_payload(hc1: &str, f: impl FnOnce(&mut Vec<(Value, Value)>)) -> String {
+    let mut cose = decode_cose(hc1);
+
+    let mut payload = match &cose[2] {
+        Value::Bytes(bys) => match ciborium::de::from_reader(&bys[..]).unwrap() {
+            Value::Map(map) => map,
+            _ => panic!("invalid payload"),
+        },
+        _ => panic!("invalid payload"),
+    };
+
+    f(&mut payload);
+
+    cose[2] = Value::Bytes(to_cbor(&Value::Map(payload)));
+
+    encode_cose(cose)
+}
+
+/// Applies `f` to the map of the first pass of a sample certificate
+pub fn with_pass(hc1: &str, f: impl FnOnce(&mut Vec<(Value, Value)>)) -> String {
+    with_payload(hc1, |payload| {
+        let hcert = payload
+            .iter_mut()
+            .find(|(k, _)| *k == Value::Integer((-260).into()))
+            .map(|(_, v)| v);
+
+        match hcert {
+            Some(Value::Map(hcert)) => match &mut hcert[0].1 {
+                Value::Map(pass) => f(pass),
+                _ => panic!("invalid pass"),
+            },
+            _ => panic!("invalid hcert"),
+        }
+    })
+}
+
+/// Applies `f` to the first entry of kind `kind` (i.e. `v`) of the first pass of a sample certificate
+pub fn with_entry(hc1: &str, kind: &str, f: impl FnOnce(&mut Vec<(Value, Value)>)) -> String {
+    with_pass(hc1, |pass| {
+        match pass.iter_mut().find(|(k, _)| *k == text(kind)) {
+            Some((_, Value::Array(entries))) => match &mut entries[0] {
+                Value::Map(entry) => f(entry),
+                _ => panic!("invalid entry"),
+            },
+            _ => panic!("no entries of kind {}", kind),
+        }
+    })
+}
+
+/// Sets `key` to `value` in the map of a certificate entry
+pub fn set_key(entry: &mut [(Value, Value)], key: &str, value: Value) {
+    for (k, v) in entry.iter_mut() {
+        if *k == text(key) {
+            *v = value;
+            return;
+        }
+    }
+
+    panic!("missing key {}", key);
+}
+
+/// Builds a string-keyed map, as accepted by the `TryFrom<BTreeMap<String, Value>>` implementations
+pub fn str_map(entries: &[(&str, Value)]) -> BTreeMap<String, Value> {
+    entries
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.clone()))
+        .collect()
+}
+
+/// Builds a CBOR map with string keys
+pub fn cbor_map(entries: &[(&str, Value)]) -> Value {
+    Value::Map(
+        entries
+            .iter()
+            .map(|(k, v)| (Value::Text(k.to_string()), v.clone()))
+            .collect(),
+    )
+}
+
+/// Shorthand for a CBOR text value
+pub fn text(s: &str) -> Value {
+    Value::Text(s.into())
+}
+
+/// Name map shared by all synthetic passes
+pub fn sample_name() -> Value {
+    cbor_map(&[
+        ("fn", text("Musterfrau-Gößinger")),
+        ("gn", text("Gabriele")),
+        ("fnt", text("MUSTERFRAU<GOESSINGER")),
+        ("gnt", text("GABRIELE")),
+    ])
+}
@@ -0,0 +1,63 @@
+use ciborium::value::Value;
+
+mod common;
+use common::*;
+
+// Reverses the order of the keys of every map in `v`
+fn reverse_maps(v: &mut Value) {
+    match v {
+        Value::Map(m) => {
+            m.reverse();
+
+            for (_, v) in m.iter_mut() {
+                reverse_maps(v);
+            }
+        }
+        Value::Array(a) => a.iter_mut().for_each(reverse_maps),
+        _ => {}
+    }
+}
+
+#[test]
+fn content_hash_ignores_encoding() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let reordered = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        payload.reverse();
+
+        for (_, v) in payload.iter_mut() {
+            reverse_maps(v);
+        }
+    });
+    let reordered = greenpass::parse(&reordered).unwrap();
+
+    assert_ne!(reordered.raw.payload, hc.raw.payload);
+    assert_eq!(reordered.content_hash(), hc.content_hash());
+
+    let resigned = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-7, &[1, 2, 3, 4]),
+        |_| vec![0; 64],
+    );
+    let resigned = greenpass::parse(&resigned).unwrap();
+
+    assert_ne!(resigned.signature, hc.signature);
+    assert_eq!(resigned.content_hash(), hc.content_hash());
+}
+
+#[test]
+fn content_hash_covers_contents() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let mut other = hc.clone();
+    other.passes[0].givenname = Some("Gabriel".into());
+
+    assert_ne!(other.content_hash(), hc.content_hash());
+
+    assert_ne!(
+        greenpass::parse(RECOVERY_SAMPLE_PAYLOAD)
+            .unwrap()
+            .content_hash(),
+        hc.content_hash()
+    );
+}
@@ -0,0 +1,169 @@
+use ciborium::value::Value;
+use greenpass::{Country, Error, GreenPass, ParseOptions, VaccineMedicinalProduct};
+
+mod common;
+use common::*;
+
+fn recovery_pass(country: &str) -> std::collections::BTreeMap<String, Value> {
+    str_map(&[
+        ("dob", text("1998-02-26")),
+        ("nam", sample_name()),
+        ("ver", text("1.3.0")),
+        (
+            "r",
+            Value::Array(vec![cbor_map(&[
+                (
+                    "ci",
+                    text("URN:UVCI:01:AT:858CC18CFCF5965EF82F60E493349AA5#K"),
+                ),
+                ("co", text(country)),
+                ("tg", text("840539006")),
+                ("is", text("Ministry of Health, Austria")),
+                ("fr", text("2021-02-20")),
+                ("df", text("2021-04-04")),
+                ("du", text("2021-10-04")),
+            ])]),
+        ),
+    ])
+}
+
+#[test]
+fn country_names() {
+    assert_eq!(Country::from("AT").name(), Some("Austria"));
+    assert_eq!(Country::from("EU").name(), Some("European Union"));
+    assert_eq!(Country::from("XK").name(), Some("Kosovo"));
+    assert_eq!(Country::from("ZZ").name(), None);
+    assert_eq!(Country::from("at").name(), None);
+
+    assert_eq!(Country::from("UK").name(), Some("United Kingdom"));
+    assert_eq!(Country::from("UK").canonical(), "GB");
+    assert_eq!(Country::from("GB").canonical(), "GB");
+}
+
+#[test]
+fn parsed_country() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    assert_eq!(hc.some_issuer.as_ref().unwrap().name(), Some("Austria"));
+    assert_eq!(hc.vaccines().next().unwrap().country.alpha2(), "AT");
+}
+
+#[test]
+fn unknown_country_kept_by_default() {
+    let gp = GreenPass::try_from(recovery_pass("ZZ")).unwrap();
+
+    let country = gp.entries[0].country().unwrap();
+
+    assert_eq!(*country, "ZZ");
+    assert!(!country.is_known());
+}
+
+#[test]
+fn unknown_country_rejected_when_strict() {
+    let opts = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    GreenPass::from_map(recovery_pass("AT"), &opts).unwrap();
+
+    assert!(matches!(
+        GreenPass::from_map(recovery_pass("ZZ"), &opts),
+        Err(Error::UnknownCountry(c)) if c == "ZZ"
+    ));
+}
+
+// Synthesizes a booster issued by the NHS, which uses `GB` as issuer and the generic J07BX03 prophylaxis code
+fn nhs_vaccine_sample(country: &str) -> String {
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(
+            vaccine,
+            "ci",
+            text("URN:UVCI:01:GB:1637846012345ABCDEFGH#E"),
+        );
+        set_key(vaccine, "co", text(country));
+        set_key(vaccine, "is", text("NHS Digital"));
+        set_key(vaccine, "vp", text("J07BX03"));
+        set_key(vaccine, "dt", text("2021-11-25"));
+        set_key(vaccine, "dn", Value::Integer(3.into()));
+        set_key(vaccine, "sd", Value::Integer(3.into()));
+    });
+
+    let hc1 = with_pass(&hc1, |pass| set_key(pass, "ver", text("1.3.0")));
+
+    with_payload(&hc1, |payload| {
+        for (k, v) in payload.iter_mut() {
+            if *k == Value::Integer(1.into()) {
+                *v = text(country);
+            }
+        }
+    })
+}
+
+#[test]
+fn nhs_vaccine() {
+    let opts = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    let hc = greenpass::parse_with(&nhs_vaccine_sample("GB"), &opts).unwrap();
+    let vaccine = hc.vaccines().next().unwrap();
+
+    assert_eq!(
+        hc.some_issuer.as_ref().unwrap().name(),
+        Some("United Kingdom")
+    );
+    assert_eq!(vaccine.issuer, "NHS Digital");
+    assert!(vaccine.dose_info().unwrap().is_complete);
+    assert!(vaccine.is_consistent());
+    assert_eq!(
+        VaccineMedicinalProduct::from_code(&vaccine.product),
+        Some(VaccineMedicinalProduct::Comirnaty)
+    );
+
+    // the reserved UK code is accepted even when strict, and normalized to GB
+    let mut hc = greenpass::parse_with(&nhs_vaccine_sample("UK"), &opts).unwrap();
+    hc.normalize();
+
+    assert_eq!(hc.some_issuer, Some("GB".into()));
+    assert_eq!(hc.vaccines().next().unwrap().country, "GB");
+}
+
+#[test]
+fn issuer_matches_country() {
+    let matches = |country: &str| {
+        let gp = GreenPass::try_from(recovery_pass(country)).unwrap();
+
+        gp.entries[0]
+            .as_recovery()
+            .unwrap()
+            .issuer_matches_country()
+    };
+
+    assert_eq!(matches("AT"), Some(true));
+    assert_eq!(matches("at"), Some(true));
+    assert_eq!(matches("DE"), Some(false));
+
+    let mut pass = recovery_pass("AT");
+    if let Some(Value::Array(entries)) = pass.get_mut("r") {
+        if let Value::Map(entry) = &mut entries[0] {
+            set_key(entry, "is", text("Some Regional Health Authority"));
+        }
+    }
+
+    let gp = GreenPass::try_from(pass).unwrap();
+    assert_eq!(
+        gp.entries[0]
+            .as_recovery()
+            .unwrap()
+            .issuer_matches_country(),
+        None
+    );
+
+    let hc = greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap();
+    assert_eq!(
+        hc.recoveries().next().unwrap().issuer_matches_country(),
+        Some(true)
+    );
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "revocation-http")]
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use greenpass::{CrlCache, Error, VerificationKey};
+
+const CSCA: &str = include_str!("data/csca_crl.pem");
+const DSC: &str = include_str!("data/dsc_crl.pem");
+const REVOKED_DSC: &str = include_str!("data/dsc_crl_revoked.pem");
+const EC_CERT: &str = include_str!("data/dsc_ec.pem");
+
+const CRL: &[u8] = include_bytes!("data/csca.crl");
+
+// Serves the CSCA's CRL over plain HTTP on the address in the CRL distribution points of the test DSCs. Returns a
+// request counter.
+fn serve_crl() -> Arc<AtomicUsize> {
+    let listener = TcpListener::bind("127.0.0.1:47861").unwrap();
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+
+            BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|l| !l.is_empty())
+                .for_each(drop);
+
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Type: application/pkix-crl\r\nContent-Length: {}\r\n\r\n",
+                CRL.len()
+            );
+
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(CRL).unwrap();
+        }
+    });
+
+    requests
+}
+
+fn der(cert: &str) -> Vec<u8> {
+    pem::parse(cert).unwrap().into_contents()
+}
+
+#[tokio::test]
+async fn dsc_revocation() {
+    let requests = serve_crl();
+
+    let csca = VerificationKey::from_pem(CSCA).unwrap();
+    let cache = CrlCache::new();
+
+    assert!(!cache.is_dsc_revoked(&der(DSC), &csca).await.unwrap());
+    assert!(cache
+        .is_dsc_revoked(&der(REVOKED_DSC), &csca)
+        .await
+        .unwrap());
+
+    // both DSCs share the same distribution point, and the CRL is valid until its next update
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn dsc_revocation_wrong_csca() {
+    let other = VerificationKey::from_pem(EC_CERT).unwrap();
+
+    assert!(matches!(
+        CrlCache::new().is_dsc_revoked(&der(DSC), &other).await,
+        Err(Error::UntrustedChain(_))
+    ));
+}
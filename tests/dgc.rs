@@ -0,0 +1,56 @@
+use serde_json::json;
+
+mod common;
+use common::*;
+
+#[test]
+fn vaccine_dgc_json() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    // from the Austrian test data at https://github.com/eu-digital-green-certificates/dgc-testdata
+    let expected = json!({
+        "v": [{
+            "dn": 1,
+            "ma": "ORG-100030215",
+            "vp": "1119349007",
+            "dt": "2021-02-18",
+            "co": "AT",
+            "ci": "URN:UVCI:01:AT:10807843F94AEE0EE5093FBC254BD813#B",
+            "mp": "EU/1/20/1528",
+            "is": "Ministry of Health, Austria",
+            "sd": 2,
+            "tg": "840539006"
+        }],
+        "nam": {
+            "fnt": "MUSTERFRAU<GOESSINGER",
+            "fn": "Musterfrau-Gößinger",
+            "gnt": "GABRIELE",
+            "gn": "Gabriele"
+        },
+        "ver": "1.2.1",
+        "dob": "1998-02-26"
+    });
+
+    assert_eq!(hc.passes[0].to_dgc_json(), expected);
+}
+
+#[test]
+fn test_and_recovery_dgc_json() {
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+    let dgc = hc.passes[0].to_dgc_json();
+
+    let test = &dgc["t"][0];
+
+    assert_eq!(test["tt"], "LP6464-4");
+    assert!(test["nm"].is_string());
+    assert!(test.get("ma").is_none());
+    assert!(test["sc"].as_str().unwrap().starts_with("2021-"));
+    assert!(dgc.get("v").is_none());
+
+    let hc = greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap();
+    let dgc = hc.passes[0].to_dgc_json();
+
+    assert_eq!(dgc["r"][0]["fr"], "2021-02-20");
+    assert_eq!(dgc["r"][0]["df"], "2021-04-04");
+    assert_eq!(dgc["r"][0]["du"], "2021-10-04");
+}
@@ -0,0 +1,111 @@
+use greenpass::CertInfo;
+
+mod common;
+use common::*;
+
+const VACCINE_SAMPLE_DUMP: &str = r#"EU Digital COVID Certificate
+
+Issued by: AT
+Created at: 2021-07-02 23:58:57 UTC
+Expires at: 2022-07-02 23:58:57 UTC
+
+Signature algorithm: -7
+Signature KID: d919375fc1e7b6b2
+Signature data: (64 bytes) 4592cb3db20a17e9121db716053f068d42cdb0ec3f6ad9004fd7daaeb5ca53c2f6f193f9527181607b7cd240b3801940ad064e48e714564d639455a688f53d77
+
+Pass#0:
+    Cert version 1.2.1
+    Emitted to: Gabriele Musterfrau-Gößinger
+    Standardized Name: GABRIELE MUSTERFRAU<GOESSINGER
+    Date of birth: 1998-02-26
+
+    Vaccination data:
+        Cert ID: URN:UVCI:01:AT:10807843F94AEE0EE5093FBC254BD813#B
+        Disease: 840539006
+        Issuer: Ministry of Health, Austria
+        Country: AT
+        Vaccination date: 2021-02-18
+        Doses administered: 1/2
+        Product ID: EU/1/20/1528 (Comirnaty)
+        Market Authorization ID: ORG-100030215 (Biontech Manufacturing GmbH)
+        Vaccine/Prophylaxis ID: 1119349007"#;
+
+#[test]
+fn display_vaccination() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    assert_eq!(hc.to_string(), VACCINE_SAMPLE_DUMP);
+}
+
+#[test]
+fn display_indentation() {
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+    let test = &hc.passes[0].entries[0];
+
+    assert!(format!("{}", test).starts_with("Testing attestation:\n    Cert ID: "));
+    assert!(format!("{:2}", test).starts_with("  Testing attestation:\n      Cert ID: "));
+}
+
+#[test]
+fn display_dose_annotations() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let mut vaccine = hc.passes[0].entries[0].as_vaccine().unwrap().clone();
+
+    vaccine.dose_number = 2;
+    assert!(vaccine
+        .to_string()
+        .contains("Doses administered: 2/2 (complete)\n"));
+
+    vaccine.dose_number = 3;
+    assert!(vaccine
+        .to_string()
+        .contains("Doses administered: 3/2 (booster)\n"));
+
+    vaccine.product = "EU/1/99/0000".into();
+    assert!(vaccine.to_string().contains("Product ID: EU/1/99/0000\n"));
+}
+
+#[test]
+fn summary_line() {
+    let line = |hc1: &str| greenpass::parse(hc1).unwrap().passes[0].summary_line();
+
+    assert_eq!(
+        line(VACCINE_SAMPLE_PAYLOAD),
+        "Vaccine 1/2 Comirnaty (AT) not valid"
+    );
+    assert_eq!(
+        line(PCR_TEST_SAMPLE_PAYLOAD),
+        "Test NAAT Negative (AT) exp 2021-02-23"
+    );
+    assert_eq!(
+        line(ANTIGEN_TEST_SAMPLE_PAYLOAD),
+        "Test RAT Negative (AT) exp 2021-02-22"
+    );
+    assert_eq!(
+        line(RECOVERY_SAMPLE_PAYLOAD),
+        "Recovery from COVID-19 (AT) exp 2021-10-04"
+    );
+
+    let mut pass = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap().passes[0].clone();
+
+    if let CertInfo::Vaccine(v) = &mut pass.entries[0] {
+        v.dose_number = 2;
+    }
+
+    let (_, until) = pass.entries[0].as_vaccine().unwrap().default_validity();
+    assert_eq!(
+        pass.summary_line(),
+        format!("Vaccine 2/2 Comirnaty (AT) exp {}", until.unwrap())
+    );
+
+    // boosters never expire, and unknown products are left as codes
+    if let CertInfo::Vaccine(v) = &mut pass.entries[0] {
+        v.dose_number = 3;
+        v.product = "EU/1/99/9999".into();
+    }
+
+    assert_eq!(pass.summary_line(), "Vaccine 3/2 EU/1/99/9999 (AT) no exp");
+
+    pass.entries.clear();
+    assert_eq!(pass.summary_line(), "No entries");
+}
@@ -0,0 +1,250 @@
+use chrono::prelude::*;
+use ciborium::value::Value;
+use greenpass::{CertInfo, EntryKind, Error, Exemption, GreenPass, ParseOptions};
+
+mod common;
+use common::*;
+
+fn exemption_pass() -> std::collections::BTreeMap<String, Value> {
+    str_map(&[
+        ("dob", text("1998-02-26")),
+        ("nam", sample_name()),
+        ("ver", text("1.3.0")),
+        (
+            "e",
+            Value::Array(vec![cbor_map(&[
+                ("ci", text("URN:UVCI:01:HU:0123456789ABCDEF#X")),
+                ("co", text("HU")),
+                ("tg", text("840539006")),
+                ("is", text("National Public Health Center")),
+                ("rs", text("medical contraindication")),
+                ("df", text("2021-06-01")),
+                ("du", text("2021-12-31")),
+            ])]),
+        ),
+    ])
+}
+
+#[test]
+fn parse_exemption() {
+    let opts = ParseOptions {
+        exemptions: true,
+        ..Default::default()
+    };
+
+    let gp = GreenPass::from_map(exemption_pass(), &opts).unwrap();
+
+    assert_eq!(
+        gp.entries,
+        vec![CertInfo::Exemption(Exemption {
+            cert_id: "URN:UVCI:01:HU:0123456789ABCDEF#X".into(),
+            country: "HU".into(),
+            disease: "840539006".into(),
+            issuer: "National Public Health Center".into(),
+            reason: Some("medical contraindication".into()),
+            valid_from: NaiveDate::from_ymd(2021, 6, 1).into(),
+            valid_until: NaiveDate::from_ymd(2021, 12, 31).into(),
+        })]
+    );
+}
+
+#[test]
+fn exemption_rejected_by_default() {
+    assert!(matches!(
+        GreenPass::try_from(exemption_pass()),
+        Err(Error::MissingKey(_))
+    ));
+}
+
+// Synthetic vaccine entry carrying national annotations next to the standard keys, as found in some Italian passes
+fn annotated_vaccine_pass() -> std::collections::BTreeMap<String, Value> {
+    str_map(&[
+        ("dob", text("1998-02-26")),
+        ("nam", sample_name()),
+        ("ver", text("1.3.0")),
+        (
+            "v",
+            Value::Array(vec![cbor_map(&[
+                ("ci", text("01ITE7300E1AB2A84C719004F103DCB1F70A#6")),
+                ("co", text("IT")),
+                ("dn", Value::Integer(3.into())),
+                ("dt", text("2021-12-01")),
+                ("is", text("Ministero della Salute")),
+                ("ma", text("ORG-100030215")),
+                ("mp", text("EU/1/20/1528")),
+                ("sd", Value::Integer(2.into())),
+                ("tg", text("840539006")),
+                ("vp", text("1119349007")),
+                ("it_booster", Value::Bool(true)),
+                ("it_note", text("dose aggiuntiva")),
+            ])]),
+        ),
+    ])
+}
+
+#[test]
+fn extra_keys() {
+    let opts = ParseOptions {
+        extra_keys: true,
+        ..Default::default()
+    };
+
+    let gp = GreenPass::from_map(annotated_vaccine_pass(), &opts).unwrap();
+    let vaccine = gp.entries[0].as_vaccine().unwrap();
+
+    assert_eq!(vaccine.dose_number, 3);
+    assert_eq!(vaccine.extra.len(), 2);
+    assert_eq!(vaccine.extra["it_booster"], [0xf5]);
+
+    let decoded: Value = ciborium::de::from_reader(&vaccine.extra["it_note"][..]).unwrap();
+    assert_eq!(decoded, text("dose aggiuntiva"));
+
+    assert!(vaccine.to_string().contains("Extra key it_booster: f5"));
+
+    let json = serde_json::to_value(vaccine).unwrap();
+    assert_eq!(json["extra"]["it_booster"], "f5");
+}
+
+#[test]
+fn extra_keys_rejected_by_default() {
+    assert!(matches!(
+        GreenPass::try_from(annotated_vaccine_pass()),
+        Err(Error::SpuriousData(_))
+    ));
+
+    let strict = ParseOptions {
+        extra_keys: true,
+        strict: true,
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        GreenPass::from_map(annotated_vaccine_pass(), &strict),
+        Err(Error::SpuriousData(_))
+    ));
+}
+
+// A pass holding an entry of a kind added by a hypothetical later revision of the schema
+fn future_entry_pass(known: bool) -> std::collections::BTreeMap<String, Value> {
+    let mut pass = exemption_pass();
+    pass.remove("e");
+
+    pass.insert(
+        "p".into(),
+        Value::Array(vec![cbor_map(&[("ci", text("URN:UVCI:01:XX:NEW#1"))])]),
+    );
+
+    if known {
+        pass.insert(
+            "r".into(),
+            Value::Array(vec![cbor_map(&[
+                (
+                    "ci",
+                    text("URN:UVCI:01:AT:858CC18CFCF5965EF82F60E493349AA5#K"),
+                ),
+                ("co", text("AT")),
+                ("tg", text("840539006")),
+                ("is", text("Ministry of Health, Austria")),
+                ("fr", text("2021-02-20")),
+                ("df", text("2021-04-04")),
+                ("du", text("2021-10-04")),
+            ])]),
+        );
+    }
+
+    pass
+}
+
+#[test]
+fn unknown_entries() {
+    let gp = GreenPass::try_from(future_entry_pass(false)).unwrap();
+
+    let expected = to_cbor(&cbor_map(&[("ci", text("URN:UVCI:01:XX:NEW#1"))]));
+    assert_eq!(
+        gp.entries,
+        [CertInfo::Unknown {
+            key: "p".into(),
+            value: expected.clone()
+        }]
+    );
+
+    assert_eq!(gp.entries[0].cert_id(), None);
+    assert!(gp.primary_entry().is_none());
+    assert!(gp.entries[0]
+        .to_string()
+        .contains(&format!("Data: {}", hex::encode(&expected))));
+
+    // known entries are parsed as usual
+    let gp = GreenPass::try_from(future_entry_pass(true)).unwrap();
+    assert_eq!(gp.entries.len(), 2);
+    assert!(gp.entries[0].is_recovery());
+    assert!(matches!(&gp.entries[1], CertInfo::Unknown { key, .. } if key == "p"));
+
+    let strict = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        GreenPass::from_map(future_entry_pass(false), &strict),
+        Err(Error::MissingKey(_))
+    ));
+    assert!(matches!(
+        GreenPass::from_map(future_entry_pass(true), &strict),
+        Err(Error::SpuriousData(_))
+    ));
+}
+
+#[test]
+fn unknown_entries_summary() {
+    let hc1 = with_pass(VACCINE_SAMPLE_PAYLOAD, |pass| {
+        pass.retain(|(k, _)| *k != text("v"));
+        pass.push((text("p"), Value::Array(vec![cbor_map(&[])])));
+    });
+
+    let hc = greenpass::parse(&hc1).unwrap();
+    assert_eq!(hc.passes[0].entries[0].kind(), EntryKind::Unknown);
+    assert_eq!(
+        greenpass::parse_summary(&hc1).unwrap().passes[0].kind,
+        EntryKind::Unknown
+    );
+}
+
+#[test]
+fn coerce_types() {
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dn", text("2"));
+        set_key(vaccine, "sd", text(" 2"));
+    });
+
+    assert!(matches!(
+        greenpass::parse(&hc1),
+        Err(Error::InvalidFormatFor { key }) if key == "v[0].dn"
+    ));
+
+    let lenient = ParseOptions {
+        coerce_types: true,
+        ..Default::default()
+    };
+
+    let hc = greenpass::parse_with(&hc1, &lenient).unwrap();
+    let vaccine = hc.passes[0].entries[0].as_vaccine().unwrap();
+
+    assert_eq!((vaccine.dose_number, vaccine.dose_total), (2, 2));
+
+    let strict = ParseOptions {
+        strict: true,
+        ..lenient.clone()
+    };
+
+    assert!(greenpass::parse_with(&hc1, &strict).is_err());
+
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dn", text("two"));
+    });
+
+    assert!(matches!(
+        greenpass::parse_with(&hc1, &lenient),
+        Err(Error::InvalidFormatFor { key }) if key == "v[0].dn"
+    ));
+}
@@ -0,0 +1,88 @@
+use ciborium::value::Value;
+use greenpass::{CertInfo, Error, Light, ParseOptions};
+
+mod common;
+use common::*;
+
+// Turns the vaccine sample into a synthetic Swiss certificate light, moving its pass from the hcert claim (-260) to
+// the light one (-250) and optionally stripping the vaccination data.
+fn light_sample(strip_entries: bool) -> String {
+    with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        for (k, v) in payload.iter_mut() {
+            if *k != Value::Integer((-260).into()) {
+                continue;
+            }
+
+            *k = Value::Integer((-250).into());
+
+            if let Value::Map(hcert) = v {
+                if let Value::Map(pass) = &mut hcert[0].1 {
+                    if strip_entries {
+                        pass.retain(|(k, _)| *k != text("v"));
+                    }
+
+                    for (k, v) in pass.iter_mut() {
+                        if *k == text("ver") {
+                            *v = text("1.0.0");
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[test]
+fn parse_light() {
+    let hc = greenpass::parse(&light_sample(true)).unwrap();
+    let pass = &hc.passes[0];
+
+    assert_eq!(pass.surname.as_deref(), Some("Musterfrau-Gößinger"));
+    assert_eq!(pass.date_of_birth, "1998-02-26");
+    assert_eq!(pass.ver, "1.0.0");
+
+    assert_eq!(
+        pass.entries,
+        vec![CertInfo::Light(Light {
+            valid_from: hc.created,
+            valid_until: hc.expires,
+        })]
+    );
+
+    assert_eq!(pass.entries[0].cert_id(), None);
+    assert_eq!(hc.vaccines().count(), 0);
+}
+
+#[test]
+fn light_with_health_data() {
+    assert!(matches!(
+        greenpass::parse(&light_sample(false)),
+        Err(Error::SpuriousData(_))
+    ));
+}
+
+#[test]
+fn strict_light_without_name() {
+    let hc1 = with_payload(&light_sample(true), |payload| {
+        for (k, v) in payload.iter_mut() {
+            if let (true, Value::Map(hcert)) = (*k == Value::Integer((-250).into()), v) {
+                if let Value::Map(pass) = &mut hcert[0].1 {
+                    pass.retain(|(k, _)| *k != text("nam"));
+                }
+            }
+        }
+    });
+
+    let hc = greenpass::parse(&hc1).unwrap();
+    assert_eq!(hc.passes[0].std_surname, "");
+
+    let strict = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        greenpass::parse_with(&hc1, &strict),
+        Err(Error::MissingKey(key)) if key == "nam"
+    ));
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "qr")]
+
+mod common;
+use common::*;
+
+fn scan(png: &[u8]) -> Vec<String> {
+    let img = image::load_from_memory(png).unwrap().into_luma8();
+
+    let mut prepared = rqrr::PreparedImage::prepare(img);
+
+    prepared
+        .detect_grids()
+        .into_iter()
+        .map(|grid| grid.decode().unwrap().1)
+        .collect()
+}
+
+#[test]
+fn qr_roundtrip() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let png = hc.to_qr_png(400).unwrap();
+    assert_eq!(image::load_from_memory(&png).unwrap().width(), 400);
+
+    let scanned = scan(&png);
+    assert_eq!(scanned, [hc.to_hc1()]);
+    assert_eq!(greenpass::parse(&scanned[0]).unwrap(), hc);
+}
+
+#[test]
+fn qr_minimum_size() {
+    let hc = greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap();
+
+    // too small to fit the code, so it is rendered at one pixel per module
+    let img = image::load_from_memory(&hc.to_qr_png(0).unwrap()).unwrap();
+    assert!(img.width() > 0);
+    assert_eq!(img.width(), img.height());
+}
@@ -0,0 +1,59 @@
+use greenpass::{CertInfo, RedactOptions};
+use sha2::{Digest, Sha256};
+
+mod common;
+use common::*;
+
+#[test]
+fn redact_personal_data() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let redacted = hc.redacted(&RedactOptions::default());
+
+    let pass = &redacted.passes[0];
+
+    assert_eq!(pass.surname, None);
+    assert_eq!(pass.givenname, None);
+    assert_eq!(pass.std_surname, "");
+    assert_eq!(pass.std_givenname, None);
+    assert_eq!(pass.date_of_birth, "1998");
+
+    let vaccine = redacted.vaccines().next().unwrap();
+    assert_eq!(vaccine.cert_id, "");
+    assert_eq!(vaccine.product, "EU/1/20/1528");
+
+    assert_eq!(redacted.expires, hc.expires);
+    assert_eq!(redacted.signature, hc.signature);
+    assert!(redacted.raw.payload.is_empty());
+}
+
+#[test]
+fn redact_hashing_cert_ids() {
+    let hc = greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap();
+    let redacted = hc.redacted(&RedactOptions {
+        hash_cert_ids: true,
+    });
+
+    let original = &hc.recoveries().next().unwrap().cert_id;
+
+    assert_eq!(
+        redacted.recoveries().next().unwrap().cert_id,
+        hex::encode(Sha256::digest(original))
+    );
+}
+
+#[test]
+fn redact_extra_keys() {
+    let mut hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    match &mut hc.passes[0].entries[0] {
+        CertInfo::Vaccine(v) => {
+            v.extra
+                .insert("it_note".into(), b"\x6bMario Rossi".to_vec());
+        }
+        ci => panic!("unexpected entry {:?}", ci),
+    }
+
+    let redacted = hc.redacted(&RedactOptions::default());
+
+    assert!(redacted.vaccines().next().unwrap().extra.is_empty());
+}
@@ -0,0 +1,60 @@
+use greenpass::{RevocationHashType, RevocationList};
+use sha2::{Digest, Sha256};
+
+mod common;
+use common::*;
+
+#[test]
+fn revoked_by_uci() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let mut list = RevocationList::new(RevocationHashType::Uci);
+    assert!(!hc.is_revoked(&list));
+
+    assert!(list.insert(&Sha256::digest(
+        "URN:UVCI:01:AT:858CC18CFCF5965EF82F60E493349AA5#K"
+    )));
+    assert!(!hc.is_revoked(&list));
+
+    // lists usually only carry the first 128 bits of each hash
+    assert!(list.insert(&Sha256::digest("URN:UVCI:01:AT:10807843F94AEE0EE5093FBC254BD813#B")[..16]));
+    assert!(hc.is_revoked(&list));
+    assert_eq!(list.len(), 2);
+}
+
+#[test]
+fn revoked_by_country_code_uci() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let mut uci = RevocationList::new(RevocationHashType::Uci);
+    let mut country_uci = RevocationList::new(RevocationHashType::CountryCodeUci);
+
+    let hash = Sha256::digest("ATURN:UVCI:01:AT:10807843F94AEE0EE5093FBC254BD813#B");
+    uci.insert(&hash);
+    country_uci.insert(&hash);
+
+    assert!(!hc.is_revoked(&uci));
+    assert!(hc.is_revoked(&country_uci));
+}
+
+#[test]
+fn revoked_by_signature() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let mut list = RevocationList::new(RevocationHashType::Signature);
+
+    // the whole ECDSA signature is not what gets hashed
+    list.insert(&Sha256::digest(&hc.signature.signature));
+    assert!(!hc.is_revoked(&list));
+
+    list.insert(&Sha256::digest(&hc.signature.signature[..32]));
+    assert!(hc.is_revoked(&list));
+}
+
+#[test]
+fn short_hash_rejected() {
+    let mut list = RevocationList::new(RevocationHashType::Uci);
+
+    assert!(!list.insert(&[0; 8]));
+    assert!(list.is_empty());
+}
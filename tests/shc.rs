@@ -0,0 +1,103 @@
+use std::io::Write;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as BASE64_URL, Engine};
+use chrono::prelude::*;
+use flate2::{write::DeflateEncoder, Compression};
+use greenpass::{AnyCert, Error, ErrorKind};
+use serde_json::json;
+
+mod common;
+use common::*;
+
+// Builds a SMART Health Card with the given claims, signed with a dummy signature
+fn shc(claims: &serde_json::Value) -> String {
+    let header = json!({ "zip": "DEF", "alg": "ES256", "kid": "3Kfdg-XwP-7gXyywtUfUADwBumDOPKMQx-iELL11W9s" });
+
+    let mut deflate = DeflateEncoder::new(Vec::new(), Compression::default());
+    deflate.write_all(claims.to_string().as_bytes()).unwrap();
+
+    let jws = format!(
+        "{}.{}.{}",
+        BASE64_URL.encode(header.to_string()),
+        BASE64_URL.encode(deflate.finish().unwrap()),
+        BASE64_URL.encode([1, 2, 3, 4])
+    );
+
+    let digits: String = jws.bytes().map(|b| format!("{:02}", b - 45)).collect();
+
+    format!("shc:/{}", digits)
+}
+
+fn sample_claims() -> serde_json::Value {
+    json!({
+        "iss": "https://spec.smarthealth.cards/examples/issuer",
+        "nbf": 1620847989.5,
+        "vc": {
+            "type": ["https://smarthealth.cards#health-card"],
+            "credentialSubject": {
+                "fhirVersion": "4.0.1",
+                "fhirBundle": { "resourceType": "Bundle", "type": "collection", "entry": [] }
+            }
+        }
+    })
+}
+
+#[test]
+fn parse_shc() {
+    let card = match greenpass::parse_any(&shc(&sample_claims())).unwrap() {
+        AnyCert::Shc(card) => card,
+        AnyCert::Dcc(_) => panic!("not a SMART Health Card"),
+    };
+
+    assert_eq!(
+        card.issuer,
+        "https://spec.smarthealth.cards/examples/issuer"
+    );
+    assert_eq!(
+        card.not_before,
+        Some(Utc.timestamp(1620847989, 500_000_000))
+    );
+    assert_eq!(card.algorithm, "ES256");
+    assert_eq!(
+        card.kid.as_deref(),
+        Some("3Kfdg-XwP-7gXyywtUfUADwBumDOPKMQx-iELL11W9s")
+    );
+    assert_eq!(card.credential["credentialSubject"]["fhirVersion"], "4.0.1");
+    assert_eq!(card.signature, [1, 2, 3, 4]);
+}
+
+#[test]
+fn parse_any_dispatch() {
+    assert!(matches!(
+        greenpass::parse_any(VACCINE_SAMPLE_PAYLOAD),
+        Ok(AnyCert::Dcc(hc)) if hc == greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap()
+    ));
+
+    let err = greenpass::parse_any("EUDCC1:whatever").unwrap_err();
+    assert!(matches!(err, Error::UnsupportedFormat(_)));
+    assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+
+    assert!(matches!(
+        greenpass::parse_any("shc:/2/1/5676290952"),
+        Err(Error::UnsupportedFormat(_))
+    ));
+}
+
+#[test]
+fn malformed_shc() {
+    for input in ["shc:/567", "shc:/99", "shc:/5676"] {
+        assert!(
+            matches!(greenpass::parse_any(input), Err(Error::MalformedShc(_))),
+            "{}",
+            input
+        );
+    }
+
+    let mut claims = sample_claims();
+    claims.as_object_mut().unwrap().remove("vc");
+
+    assert!(matches!(
+        greenpass::parse_any(&shc(&claims)),
+        Err(Error::MalformedShc(what)) if what == "missing vc"
+    ));
+}
@@ -0,0 +1,105 @@
+use ciborium::value::Value;
+use greenpass::{EntryKind, Error, HealthCert, Summary};
+
+mod common;
+use common::*;
+
+fn assert_matches_full_parse(hc1: &str) {
+    let hc = greenpass::parse(hc1).unwrap();
+    let summary = greenpass::parse_summary(hc1).unwrap();
+
+    assert_eq!(summary.some_issuer, hc.some_issuer);
+    assert_eq!(summary.created, hc.created);
+    assert_eq!(summary.expires, hc.expires);
+    assert_eq!(summary.passes.len(), hc.passes.len());
+
+    for (ps, pass) in summary.passes.iter().zip(&hc.passes) {
+        assert_eq!(ps.date_of_birth, pass.date_of_birth);
+        assert_eq!(ps.surname, pass.surname);
+        assert_eq!(ps.givenname, pass.givenname);
+        assert_eq!(ps.std_surname, pass.std_surname);
+        assert_eq!(ps.std_givenname, pass.std_givenname);
+        assert_eq!(ps.kind, pass.entries[0].kind());
+    }
+}
+
+#[test]
+fn summary_matches_parse() {
+    for hc1 in [
+        VACCINE_SAMPLE_PAYLOAD,
+        VACCINE_SAMPLE_PAYLOAD_UNPROTECTED_KID,
+        PCR_TEST_SAMPLE_PAYLOAD,
+        ANTIGEN_TEST_SAMPLE_PAYLOAD,
+        RECOVERY_SAMPLE_PAYLOAD,
+    ] {
+        assert_matches_full_parse(hc1);
+    }
+
+    let summary = greenpass::parse_summary(RECOVERY_SAMPLE_PAYLOAD).unwrap();
+    assert_eq!(summary.passes[0].kind, EntryKind::Recovery);
+}
+
+#[test]
+fn summary_skips_entry_validation() {
+    // an invalid entry is rejected by parse, but the summary does not look into it
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dt", Value::Integer(42.into()))
+    });
+
+    assert!(greenpass::parse(&hc1).is_err());
+
+    let summary = greenpass::parse_summary(&hc1).unwrap();
+    assert_eq!(summary.passes[0].kind, EntryKind::Vaccine);
+    assert_eq!(summary.passes[0].std_surname, "MUSTERFRAU<GOESSINGER");
+}
+
+#[test]
+fn summary_duplicate_passes() {
+    // both passes are stored under the same key, and neither parser drops one
+    let hc1 = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        for (k, v) in payload.iter_mut() {
+            if let (Value::Integer(k), Value::Map(hcert)) = (k, v) {
+                if i128::from(*k) == -260 {
+                    hcert.push(hcert[0].clone());
+                }
+            }
+        }
+    });
+
+    assert_eq!(greenpass::parse(&hc1).unwrap().passes.len(), 2);
+    assert_matches_full_parse(&hc1);
+}
+
+#[test]
+fn summary_errors() {
+    let hc1 = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        payload.retain(|(k, _)| *k != Value::Integer((-260).into()))
+    });
+
+    assert!(matches!(
+        greenpass::parse_summary(&hc1),
+        Err(Error::MissingKey(key)) if key == "hcert"
+    ));
+
+    let hc1 = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        payload.retain(|(k, _)| *k != Value::Integer(4.into()))
+    });
+
+    assert!(matches!(
+        greenpass::parse_summary(&hc1),
+        Err(Error::MissingKey(_))
+    ));
+
+    let cose = decode_cose(VACCINE_SAMPLE_PAYLOAD);
+    assert!(matches!(
+        Summary::from_cose_bytes(&to_cbor(&Value::Array(cose[..3].to_vec()))),
+        Err(Error::UnsupportedCoseStructure { len: 3 })
+    ));
+
+    // bytes that can be summarized are still parsed the same way
+    let bytes = to_cbor(&Value::Array(decode_cose(VACCINE_SAMPLE_PAYLOAD)));
+    assert_eq!(
+        Summary::from_cose_bytes(&bytes).unwrap().passes[0].std_surname,
+        HealthCert::from_cose_bytes(&bytes).unwrap().passes[0].std_surname
+    );
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+use tracing::{
+    span::{Attributes, Id, Record},
+    Event, Metadata, Subscriber,
+};
+
+mod common;
+use common::*;
+
+// Records the names of the spans created while parsing
+#[derive(Clone, Default)]
+struct SpanNames {
+    names: Arc<Mutex<Vec<&'static str>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Subscriber for SpanNames {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        self.names.lock().unwrap().push(span.metadata().name());
+
+        Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn parse_stages() {
+    let subscriber = SpanNames::default();
+    let names = subscriber.names.clone();
+
+    tracing::subscriber::with_default(subscriber, || {
+        greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    });
+
+    assert_eq!(
+        *names.lock().unwrap(),
+        ["base45", "inflate", "cose", "cwt", "mapping"]
+    );
+
+    names.lock().unwrap().clear();
+
+    tracing::subscriber::with_default(
+        SpanNames {
+            names: names.clone(),
+            ..Default::default()
+        },
+        || {
+            greenpass::parse("HC1:NCF").unwrap_err();
+        },
+    );
+
+    assert_eq!(*names.lock().unwrap(), ["base45", "inflate"]);
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "trustlist-http")]
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use greenpass::{TrustList, TrustListCache};
+
+const EC_CERT: &str = include_str!("data/dsc_ec.pem");
+
+const ETAG: &str = "\"v1\"";
+
+// Serves a fixed trust list over plain HTTP, honouring If-None-Match. Returns the URL and a request counter.
+fn serve_trust_list() -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/trustlist.json", listener.local_addr().unwrap());
+
+    let body = format!(r#"{{ "2Rk3X8HntrI=": {:?} }}"#, EC_CERT);
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            counter.fetch_add(1, Ordering::SeqCst);
+
+            let headers: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|l| !l.is_empty())
+                .collect();
+
+            let not_modified = headers
+                .iter()
+                .any(|h| h.to_ascii_lowercase() == format!("if-none-match: {}", ETAG));
+
+            let resp = if not_modified {
+                "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string()
+            } else {
+                format!(
+                    "HTTP/1.1 200 OK\r\nConnection: close\r\nETag: {}\r\nContent-Length: {}\r\n\r\n{}",
+                    ETAG,
+                    body.len(),
+                    body
+                )
+            };
+
+            stream.write_all(resp.as_bytes()).unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+#[tokio::test]
+async fn fetch_trust_list() {
+    let (url, _) = serve_trust_list();
+
+    let trust = TrustList::fetch(&url).await.unwrap();
+
+    assert_eq!(trust.len(), 1);
+    assert!(trust.get(&[217, 25, 55, 95, 193, 231, 182, 178]).is_some());
+}
+
+#[tokio::test]
+async fn cache_within_ttl() {
+    let (url, requests) = serve_trust_list();
+
+    let cache = TrustListCache::new(url, Duration::from_secs(3600));
+
+    let first = cache.get().await.unwrap();
+    let second = cache.get().await.unwrap();
+
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn cache_revalidates_with_etag() {
+    let (url, requests) = serve_trust_list();
+
+    let cache = TrustListCache::new(url, Duration::ZERO);
+
+    let first = cache.get().await.unwrap();
+    let second = cache.get().await.unwrap();
+
+    // the second request is answered with 304 Not Modified, so the cached list is kept
+    assert!(Arc::ptr_eq(&first, &second));
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
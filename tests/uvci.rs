@@ -0,0 +1,77 @@
+use greenpass::{Error, Uvci};
+
+mod common;
+use common::*;
+
+#[test]
+fn parse_uvci_with_checksum() {
+    let uvci = Uvci::parse("01DE/84503/1119349007/DXSGWLWL40SU8ZFKIYIBK39A3#S").unwrap();
+
+    assert_eq!(
+        uvci,
+        Uvci {
+            version: "01".into(),
+            country: "DE".into(),
+            opaque: "84503/1119349007/DXSGWLWL40SU8ZFKIYIBK39A3".into(),
+            checksum: Some('S'),
+        }
+    );
+}
+
+#[test]
+fn parse_uvci_without_checksum() {
+    let uvci = Uvci::parse("URN:UVCI:01:AT:10807843F94AEE0EE5093FBC254BD813").unwrap();
+
+    assert_eq!(uvci.version, "01");
+    assert_eq!(uvci.country, "AT");
+    assert_eq!(uvci.opaque, "10807843F94AEE0EE5093FBC254BD813");
+    assert_eq!(uvci.checksum, None);
+    assert_eq!(
+        uvci.to_string(),
+        "URN:UVCI:01:AT:10807843F94AEE0EE5093FBC254BD813"
+    );
+}
+
+#[test]
+fn uvci_checksum_mismatch() {
+    assert!(matches!(
+        Uvci::parse("01DE/84503/1119349007/DXSGWLWL40SU8ZFKIYIBK39A3#T"),
+        Err(Error::UvciChecksumMismatch {
+            expected: 'S',
+            found: 'T'
+        })
+    ));
+
+    // the Austrian samples carry a placeholder checksum
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    assert!(matches!(
+        hc.vaccines().next().unwrap().uvci(),
+        Err(Error::UvciChecksumMismatch { found: 'B', .. })
+    ));
+}
+
+#[test]
+fn malformed_uvci() {
+    for uvci in [
+        "",
+        "URN:UVCI:",
+        "URN:UVCI:01:AT",
+        "1:AT:ABC",
+        "01:at:ABC",
+        "01:AT:ABC#XY",
+    ] {
+        assert!(
+            matches!(Uvci::parse(uvci), Err(Error::MalformedUvci(_))),
+            "{}",
+            uvci
+        );
+    }
+}
+
+#[test]
+fn entry_uvci() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD_UNPROTECTED_KID).unwrap();
+
+    assert_eq!(hc.vaccines().next().unwrap().uvci().unwrap().country, "DE");
+}
@@ -1,16 +1,16 @@
+#![allow(clippy::zero_prefixed_literal)]
+
 use chrono::prelude::*;
-use greenpass::{CertInfo, GreenPass, HealthCert, Recovery, Signature, Test, TestName, Vaccine};
+use ciborium::value::Value;
+use greenpass::{
+    CertInfo, Dated, Error, ErrorKind, GreenPass, HealthCert, Recovery, Signature, Test, TestName,
+    Vaccine, Warning,
+};
 
-// Quick and dirty validation tests
+mod common;
+use common::*;
 
-const RECOVERY_SAMPLE_PAYLOAD : &str = "HC1:NCFOXN%TS3DH3ZSUZK+.V0ETD%65NL-AH-XIIOOA+IWBCNQ5GJL-XVRJAKD93B4:ZH6I1$4JF 2K%5+G9F.PNF67J6UW6LEQV46PK9E:00$4*2DN43U*0CEBQ/GXQFY73CIBC:GVEBBIBBL7BIB4UNAWNJKBOJJ5PNT53/FJ8FN96B2M3-6BHI7UG55:44$28A9H0D3ZCL4JMYAZ+S-A5$XKX6T2YC 35H/ITX8GL2TK96L6SR9MU9DV5 R1BPIZKH03RW63LD3LS4JYK9EFH78$ZJ*DJ3Q4+Y5V$K2:6.77/Z6KZ5LD6E6P 9SH87/YQJ/RL35+Y5P Q*8D$JD IBCLCK5MJMS68H36DH.K:Z28AL**I3DN3F7MHFEVV%*4HBTSCNT 4C%C47TO*47*KB*KYQT3LT+*4.$S6ZC0JB%JBK:TBSH6FN-G6N%VPJ13M9K9J8*TPB12-VRSQ8XMA6EBNVC28:APQ71K:ON4K25A:I9/-HUZGOZFB04YME%6DAUNO2B+TQG/2I0A4Z5NDROBVKS0J$28XG";
-const PCR_TEST_SAMPLE_PAYLOAD : &str = "HC1:NCFOXN%TS3DH3ZSUZK+.V0ETD%65NL-AHBP1IOOA+IS7C$068WAD1W7:BAT4V22F/8X*G3M9BM9Z0BFU2P4JY73JC3KD34LT7A3-4386BXSJ$IJGX8.+IIYC6Q0ZIJPKJ+LJ%2TK/IS/SR4DKJ5QWCB4DN57E-4LXKV85HZ0T+0K%I17JLXKB6J57TJK57ALT$I/+GDG6Z$U*C2OQ1:PIGEGEV4*2DN43U*0CEBQ/GXQFY73CIBC:GUC7QHBN83GG3NQN%976FNXEB.FJN83HB3EG3CAJTA3ANBXEBGM5J%44$28A9H0D3ZCL4JMYAZ+S-A5$XKX6T2YC 35H/ITX8GL2TK96L6SR9MU9DV5 R1JNI:E4I+C7*4M:KCY07LPMIH-O9XZQSH9R$FXQGDVBK*RZP3:*DG1W7SGT$7S%RMSG2UQYI96GGLXK6*K$X4FUTD14//EF.712U0$89NT2V457U8+9W2KQ-7LF9-DF07U$B97JJ1D7WKP/HLIJLRKF1MFHJP7NVDEBU1J6+2FBKBSHNAIFVV%KN$2W5+IKDP6SFPQC16LITI/-7P E:ZSYMJS$5-BPDMFAMBJ7TN7FMRHL:19XI9X5ZL36%OGODHIFE8SHLH0ZLG$DDZG$DR-00*%E%4";
-const ANTIGEN_TEST_SAMPLE_PAYLOAD : &str = "HC1:NCFOXN%TS3DH3ZSUZK+.V0ETD%65NL-AH+VEIOOA+ILACMB9GJL. V/1KKD93B4:ZH6I1$4JM:IP1MUF5Z$5NF67J6QW6%PQSE6-96XNM6-6SF6IRH*PPKS9.Q6%%6%E5%NPC71RF6+17S%MBX6PF51$5DB97-59$PO1BD/9BL5$W5151-V9NVP.SA0T932QHG3JZILDB523G*S2U2V8TQEDK8CD/SYJC.0EPSTBVC9ND2SSIYDNYDF*S8/D7BCU5TL0D BD$8D8ND1UT*WD.XI5TBW1UYIJGDBGIASJLA8KOHSLOJJPAOJKCGSFC17KPDS9+E93ZM$96PZ6+Q6X46KE0.43RA3/43KD3F23/9TL4T1C9 UPVD5BT17$1MV15K1DR1FIEC2F5+1T+UC2FSH9 UP+/UEOJDKBO.AI9BVYTOCFOPS788O5L9Y4KCT:WC.L76V0VSNRCN /KU%CW.4WV2L4L$XKV7J$%25I3IC33835AL5:4A93QF08T1+G3N313SGXVA.-ND3JH/F.*OXNENHN%C36$EQU3*7LT AOH1N6OJZF3RTF7LS8BJFTGAH%BO3GA7QH*VTP7P.1GA G-/N5Y7T UDEUGBDSHNJ50/BHS2";
-const VACCINE_SAMPLE_PAYLOAD : &str = "HC1:NCFOXN%TS3DH3ZSUZK+.V0ETD%65NL-AH-R6IOOA+IZJS:A8GJL*XV%O3+QI6M8SA3/-2E%5VR5VVB9ZILAPIZI.EJJ14B2MZ8DC8COVD9VC/MJK.A+ C/8DXED%JCC8C62KXJAUYCOS2QW6%PQRZMPK9I+0MCIKYJGCC:H3J1D1I3-*TW CXBDW33+ CD8CQ8C0EC%*TGHD1KT0NDPST7KDQHDN8TSVD2NDB*S6ECX%LBZI+PB/VSQOL9DLKWCZ3EBKD8IIGDB0D48UJ06J9UBSVAXCIF4LEIIPBJ7OICWK%5BBS22T9UF5LDCPF5RBQ746B46JZ0V-OEA7IB6$C94JB2E9Z3E8AE-QD+PB.QCD-H/8O3BEQ8L9VN.6A4JBLHLM7A$JD IBCLCK5MJMS68H36DH.K:Z28AL**I3DN3F7MHFEVV%*4HBTSCNT 4C%C47TO*47*KB*KYQT3LT+*4.$S6ZC0JB%JB% NHTC:OS6K7C*M9$4HOUJJ8BZR+AB9MRX.JK/DXQC*%ESNO3VVS$EO:9-14:6VEVUOEKF4PSTT:L9HRJKP4H6RAOV%Q7RB1P 9OG2O%LAULC97*JLUEH";
-// This certificate is taken from the German CovPass app
-// https://raw.githubusercontent.com/Digitaler-Impfnachweis/covpass-android/0ef39462ba162303a156942aedb468c7de353ba7/covpass-sdk/src/test/resources/vaccination-cert.txt
-// It stores the KID in the unprotected properties
-const VACCINE_SAMPLE_PAYLOAD_UNPROTECTED_KID : &str = "HC1:6BFOXN*TS0BI$ZD4N9:9S6RCVN5+O30K3/XIV0W23NTDEXWK G2EP4J0BGJLOFJEIKVGAE%9ETMSA3/-2E%5VR5VVBJZILDBZ8D%JTQOL0EC7KD/ZL/8D:8DQVDLED0AC2AU/B2/+3HN2HPCT12IID*2T$/TVPTM*SQYDLADYR3JZIM-1U96UX4795L*KDYPWGO+9AKCO.BHOH63K5 *JAYUQJAGPENXUJRHQJA5G6VOE:OQPAU:IAJ0AZZ0OWCR/C+T4D-4HRVUMNMD3323R1392VC-4A+2XEN QT QTHC31M3+E3CP456L X4CZKHKB-43:C3J:R90JK.A5*G%DBZI9$JAQJKKIJX2MM+GWHKSKE MCAOI8%MQQK8+S4-R:KIIX0VJA$:O3HH:EF9NT6D7.Z8OMR-C137HZW2$XK6AL4%IYT0BUF1MFXZG$IV6$0+BN$MVYWV9Y4KCT7-S$ 0GKFCTR0KV4$0RCNV7J$%25I3HC3X83P47YOR40F80U8EHL%BP0CC9R$SEN59KYL 2O1/7*HVNY6:W0..DXJ5YKV4/J/JVZPRD*S0ZV+IR5H7*QS7%JX7HU0PA0PLY705JM/RA73CE3FBI";
+// Quick and dirty validation tests
 
 #[test]
 fn parse_recovery() {
@@ -18,21 +18,23 @@ fn parse_recovery() {
         some_issuer: Some("AT".into()),
         created: Utc.ymd(2021, 07, 02).and_hms(21, 24, 42),
         expires: Utc.ymd(2022, 07, 02).and_hms(21, 24, 42),
+        not_before: None,
+        cwt_id: None,
         passes: vec![GreenPass {
             date_of_birth: "1998-02-26".into(),
-            surname: "Musterfrau-Gößinger".into(),
-            givenname: "Gabriele".into(),
+            surname: Some("Musterfrau-Gößinger".into()),
+            givenname: Some("Gabriele".into()),
             std_surname: "MUSTERFRAU<GOESSINGER".into(),
-            std_givenname: "GABRIELE".into(),
+            std_givenname: Some("GABRIELE".into()),
             ver: "1.2.1".into(),
             entries: vec![CertInfo::Recovery(Recovery {
                 cert_id: "URN:UVCI:01:AT:858CC18CFCF5965EF82F60E493349AA5#K".into(),
                 country: "AT".into(),
-                diagnosed: NaiveDate::from_ymd(2021, 02, 20),
+                diagnosed: NaiveDate::from_ymd(2021, 02, 20).into(),
                 disease: "840539006".into(),
                 issuer: "Ministry of Health, Austria".into(),
-                valid_from: NaiveDate::from_ymd(2021, 04, 04),
-                valid_until: NaiveDate::from_ymd(2021, 10, 04),
+                valid_from: NaiveDate::from_ymd(2021, 04, 04).into(),
+                valid_until: NaiveDate::from_ymd(2021, 10, 04).into(),
             })],
         }],
         signature: Signature {
@@ -44,7 +46,9 @@ fn parse_recovery() {
                 212, 136, 60, 54, 144, 171, 14, 55, 241, 213, 9, 232, 132, 86, 223, 157, 37, 146,
                 235, 232, 94, 228, 57, 56, 11, 175, 15, 141, 229,
             ],
+            counter_signature: None,
         },
+        raw: Default::default(),
     };
 
     assert_eq!(greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap(), rec_hc);
@@ -56,12 +60,14 @@ fn parse_test_pcr() {
         some_issuer: Some("AT".into()),
         created: Utc.ymd(2021, 07, 02).and_hms(20, 54, 37),
         expires: Utc.ymd(2022, 07, 02).and_hms(20, 54, 37),
+        not_before: None,
+        cwt_id: None,
         passes: vec![GreenPass {
             date_of_birth: "1998-02-26".into(),
-            surname: "Musterfrau-Gößinger".into(),
-            givenname: "Gabriele".into(),
+            surname: Some("Musterfrau-Gößinger".into()),
+            givenname: Some("Gabriele".into()),
             std_surname: "MUSTERFRAU<GOESSINGER".into(),
-            std_givenname: "GABRIELE".into(),
+            std_givenname: Some("GABRIELE".into()),
             ver: "1.2.1".into(),
             entries: vec![CertInfo::Test(Test {
                 cert_id: "URN:UVCI:01:AT:B5921A35D6A0D696421B3E2462178297#I".into(),
@@ -74,7 +80,8 @@ fn parse_test_pcr() {
                 },
                 result: "260415000".into(),
                 test_type: "LP6464-4".into(),
-                testing_centre: "Testing center Vienna 1".into(),
+                testing_centre: Some("Testing center Vienna 1".into()),
+                result_ts: None,
             })],
         }],
         signature: Signature {
@@ -86,7 +93,9 @@ fn parse_test_pcr() {
                 138, 79, 21, 151, 82, 210, 97, 150, 104, 182, 12, 24, 152, 214, 136, 110, 23, 75,
                 31, 33, 184, 58, 21, 60, 152, 84, 92, 62,
             ],
+            counter_signature: None,
         },
+        raw: Default::default(),
     };
 
     assert_eq!(greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap(), pcr_hc);
@@ -98,12 +107,14 @@ fn parse_test_antigen() {
         some_issuer: Some("AT".into()),
         created: Utc.ymd(2021, 07, 02).and_hms(20, 55, 37),
         expires: Utc.ymd(2022, 07, 02).and_hms(20, 55, 37),
+        not_before: None,
+        cwt_id: None,
         passes: vec![GreenPass {
             date_of_birth: "1998-02-26".into(),
-            surname: "Musterfrau-Gößinger".into(),
-            givenname: "Gabriele".into(),
+            surname: Some("Musterfrau-Gößinger".into()),
+            givenname: Some("Gabriele".into()),
             std_surname: "MUSTERFRAU<GOESSINGER".into(),
-            std_givenname: "GABRIELE".into(),
+            std_givenname: Some("GABRIELE".into()),
             ver: "1.2.1".into(),
             entries: vec![CertInfo::Test(Test {
                 cert_id: "URN:UVCI:01:AT:71EE2559DE38C6BF7304FB65A1A451EC#3".into(),
@@ -116,7 +127,8 @@ fn parse_test_antigen() {
                 },
                 result: "260415000".into(),
                 test_type: "LP217198-3".into(),
-                testing_centre: "Testing center Vienna 1".into(),
+                testing_centre: Some("Testing center Vienna 1".into()),
+                result_ts: None,
             })],
         }],
         signature: Signature {
@@ -128,7 +140,9 @@ fn parse_test_antigen() {
                 84, 47, 48, 23, 27, 237, 140, 37, 142, 90, 18, 143, 254, 10, 87, 220, 200, 45, 222,
                 229, 140, 74, 159, 247, 188, 40, 129, 44, 209,
             ],
+            counter_signature: None,
         },
+        raw: Default::default(),
     };
 
     assert_eq!(
@@ -143,17 +157,19 @@ fn parse_vaccination() {
         some_issuer: Some("AT".into()),
         created: Utc.ymd(2021, 07, 02).and_hms(23, 58, 57),
         expires: Utc.ymd(2022, 07, 02).and_hms(23, 58, 57),
+        not_before: None,
+        cwt_id: None,
         passes: vec![GreenPass {
             date_of_birth: "1998-02-26".into(),
-            surname: "Musterfrau-Gößinger".into(),
-            givenname: "Gabriele".into(),
+            surname: Some("Musterfrau-Gößinger".into()),
+            givenname: Some("Gabriele".into()),
             std_surname: "MUSTERFRAU<GOESSINGER".into(),
-            std_givenname: "GABRIELE".into(),
+            std_givenname: Some("GABRIELE".into()),
             ver: "1.2.1".into(),
             entries: vec![CertInfo::Vaccine(Vaccine {
                 cert_id: "URN:UVCI:01:AT:10807843F94AEE0EE5093FBC254BD813#B".into(),
                 country: "AT".into(),
-                date: NaiveDate::from_ymd(2021, 02, 18),
+                date: NaiveDate::from_ymd(2021, 02, 18).into(),
                 disease: "840539006".into(),
                 dose_number: 1,
                 dose_total: 2,
@@ -161,6 +177,7 @@ fn parse_vaccination() {
                 market_auth: "ORG-100030215".into(),
                 product: "EU/1/20/1528".into(),
                 prophylaxis_kind: "1119349007".into(),
+                extra: Default::default(),
             })],
         }],
         signature: Signature {
@@ -172,7 +189,9 @@ fn parse_vaccination() {
                 113, 129, 96, 123, 124, 210, 64, 179, 128, 25, 64, 173, 6, 78, 72, 231, 20, 86, 77,
                 99, 148, 85, 166, 136, 245, 61, 119,
             ],
+            counter_signature: None,
         },
+        raw: Default::default(),
     };
 
     assert_eq!(greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap(), vac_hc);
@@ -184,17 +203,19 @@ fn parse_vaccination_unprotected_kid() {
         some_issuer: Some("DE".into()),
         created: Utc.ymd(2021, 04, 23).and_hms(08, 38, 51),
         expires: Utc.ymd(2021, 05, 10).and_hms(13, 08, 37),
+        not_before: None,
+        cwt_id: None,
         passes: vec![GreenPass {
             date_of_birth: "1964-08-12".into(),
-            surname: "Schmitt Mustermann".into(),
-            givenname: "Erika Dörte".into(),
+            surname: Some("Schmitt Mustermann".into()),
+            givenname: Some("Erika Dörte".into()),
             std_surname: "SCHMITT<MUSTERMANN".into(),
-            std_givenname: "ERIKA<DOERTE".into(),
+            std_givenname: Some("ERIKA<DOERTE".into()),
             ver: "1.0.0".into(),
             entries: vec![CertInfo::Vaccine(Vaccine {
                 cert_id: "01DE/84503/1119349007/DXSGWLWL40SU8ZFKIYIBK39A3#S".into(),
                 country: "DE".into(),
-                date: NaiveDate::from_ymd(2021, 02, 02),
+                date: NaiveDate::from_ymd(2021, 02, 02).into(),
                 disease: "840539006".into(),
                 dose_number: 2,
                 dose_total: 2,
@@ -202,6 +223,7 @@ fn parse_vaccination_unprotected_kid() {
                 market_auth: "ORG-100030215".into(),
                 product: "EU/1/20/1528".into(),
                 prophylaxis_kind: "1119349007".into(),
+                extra: Default::default(),
             })],
         }],
         signature: Signature {
@@ -215,7 +237,9 @@ fn parse_vaccination_unprotected_kid() {
                 236, 78, 69, 63, 48, 108, 107, 77, 208, 186, 69, 144, 145, 214, 44, 80, 64, 171,
                 115, 247, 23, 119, 72, 219, 116, 165, 177, 147,
             ],
+            counter_signature: None,
         },
+        raw: Default::default(),
     };
 
     assert_eq!(
@@ -223,3 +247,1237 @@ fn parse_vaccination_unprotected_kid() {
         vac_hc
     );
 }
+
+#[test]
+fn parse_cose_bytes() {
+    let bytes = to_cbor(&Value::Array(decode_cose(VACCINE_SAMPLE_PAYLOAD)));
+
+    assert_eq!(
+        HealthCert::from_cose_bytes(&bytes).unwrap(),
+        greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap()
+    );
+}
+
+#[test]
+fn empty_hcert() {
+    let hc1 = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        for (k, v) in payload.iter_mut() {
+            if *k == Value::Integer((-260).into()) {
+                *v = Value::Map(vec![]);
+            }
+        }
+    });
+
+    assert!(matches!(
+        greenpass::parse(&hc1),
+        Err(Error::MissingKey(key)) if key == "hcert entries"
+    ));
+    assert!(matches!(
+        greenpass::parse_summary(&hc1),
+        Err(Error::MissingKey(key)) if key == "hcert entries"
+    ));
+}
+
+#[test]
+fn cose_sign1() {
+    let cose = decode_cose(VACCINE_SAMPLE_PAYLOAD_UNPROTECTED_KID);
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD_UNPROTECTED_KID).unwrap();
+
+    let sign1 = hc.cose_sign1();
+    assert_eq!(Value::Bytes(sign1.protected_raw.clone()), cose[0]);
+    assert_eq!(Value::Map(sign1.unprotected.clone()), cose[1]);
+    assert_eq!(Value::Bytes(sign1.payload.clone()), cose[2]);
+    assert_eq!(Value::Bytes(sign1.signature.clone()), cose[3]);
+
+    assert_eq!(sign1.to_cbor(), to_cbor(&Value::Array(cose)));
+    assert_eq!(HealthCert::from_cose_bytes(&sign1.to_cbor()).unwrap(), hc);
+}
+
+#[test]
+fn cose_headers() {
+    use greenpass::ContentType;
+
+    let int = |i: i64| Value::Integer(i.into());
+
+    let mut cose = decode_cose(VACCINE_SAMPLE_PAYLOAD);
+    cose[0] = Value::Bytes(to_cbor(&Value::Map(vec![
+        (int(1), int(-7)),
+        (int(2), Value::Array(vec![int(1)])),
+        (int(3), text("application/cwt")),
+        (int(4), Value::Bytes(vec![1, 2, 3])),
+    ])));
+    cose[1] = Value::Map(vec![
+        (int(3), int(61)),
+        (int(4), Value::Bytes(vec![4, 5, 6])),
+        (int(33), Value::Bytes(vec![7, 8, 9])),
+        (text("note"), text("ignored")),
+    ]);
+
+    let headers = greenpass::parse(&encode_cose(cose)).unwrap().headers();
+
+    // the protected bucket wins
+    assert_eq!(headers.alg(), Some(-7));
+    assert_eq!(headers.crit(), Some(&[int(1)][..]));
+    assert_eq!(
+        headers.content_type(),
+        Some(ContentType::Media("application/cwt".into()))
+    );
+    assert_eq!(headers.kid(), Some(&[1, 2, 3][..]));
+    assert_eq!(headers.rest.len(), 1);
+    assert_eq!(headers.rest[&33], Value::Bytes(vec![7, 8, 9]));
+
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD_UNPROTECTED_KID).unwrap();
+    let headers = hc.headers();
+
+    assert_eq!(headers.kid(), Some(&hc.signature.kid[..]));
+    assert_eq!(headers.alg(), Some(hc.signature.algorithm));
+    assert_eq!(headers.crit(), None);
+    assert_eq!(headers.content_type(), None);
+
+    assert_eq!(
+        HealthCert {
+            raw: Default::default(),
+            ..hc
+        }
+        .headers(),
+        Default::default()
+    );
+}
+
+#[test]
+fn critical_headers() {
+    let int = |i: i64| Value::Integer(i.into());
+
+    let with_crit = |crit: Value| {
+        let mut cose = decode_cose(VACCINE_SAMPLE_PAYLOAD);
+        cose[0] = Value::Bytes(to_cbor(&Value::Map(vec![
+            (int(1), int(-7)),
+            (int(2), crit),
+            (int(4), Value::Bytes(vec![1, 2, 3])),
+        ])));
+
+        greenpass::parse(&encode_cose(cose))
+    };
+
+    with_crit(Value::Array(vec![int(4), int(33)])).unwrap();
+
+    let err = with_crit(Value::Array(vec![int(1), int(-65537)])).unwrap_err();
+    assert!(matches!(err, Error::UnsupportedCriticalParam(-65537)));
+    assert_eq!(err.kind(), ErrorKind::MalformedStructure);
+
+    for crit in [
+        Value::Array(Vec::new()),
+        int(1),
+        Value::Array(vec![text("x")]),
+    ] {
+        assert!(matches!(
+            with_crit(crit),
+            Err(Error::InvalidFormatFor { key }) if key == "crit"
+        ));
+    }
+}
+
+#[test]
+fn unsupported_cose_structure() {
+    let cose = decode_cose(VACCINE_SAMPLE_PAYLOAD);
+
+    // COSE_Sign, with an array of signatures in place of the single signature
+    let mut multi = cose.clone();
+    multi[3] = Value::Array(vec![Value::Array(vec![
+        Value::Bytes(vec![]),
+        Value::Map(vec![]),
+        multi[3].clone(),
+    ])]);
+
+    let tagged = Value::Tag(98, Box::new(Value::Array(multi)));
+    let err = HealthCert::from_cose_bytes(&to_cbor(&tagged)).unwrap_err();
+    assert!(matches!(err, Error::UnsupportedCoseStructure { len: 4 }));
+    assert_eq!(err.kind(), ErrorKind::MalformedStructure);
+
+    let short = Value::Array(cose[..3].to_vec());
+    assert!(matches!(
+        HealthCert::from_cose_bytes(&to_cbor(&short)),
+        Err(Error::UnsupportedCoseStructure { len: 3 })
+    ));
+
+    let root = Value::Map(vec![(Value::Integer(1.into()), Value::Array(cose))]);
+    assert!(matches!(
+        HealthCert::from_cose_bytes(&to_cbor(&root)),
+        Err(Error::MalformedCWT)
+    ));
+}
+
+#[test]
+fn deeply_nested_cbor() {
+    // 100k nested single-element arrays, enough to overflow the stack of an unbounded recursive decoder
+    let mut nested = vec![0x81; 100_000];
+    nested.push(0x00);
+
+    let err = HealthCert::from_cose_bytes(&nested).unwrap_err();
+    assert!(matches!(err, Error::CborTooDeep));
+    assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+
+    // same, as the value of the issuer claim in the payload map
+    let mut payload = vec![0xa1, 0x01];
+    payload.extend(nested);
+
+    let mut cose = decode_cose(VACCINE_SAMPLE_PAYLOAD);
+    cose[2] = Value::Bytes(payload);
+
+    assert!(matches!(
+        greenpass::parse(&encode_cose(cose)),
+        Err(Error::CborTooDeep)
+    ));
+}
+
+#[test]
+fn malformed_cbor_position() {
+    // a break code where the third element of the COSE array should be
+    let err = HealthCert::from_cose_bytes(&[0x84, 0x40, 0xa0, 0xff, 0x40]).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::MalformedCBOR {
+            offset: 4,
+            len: 5,
+            ..
+        }
+    ));
+    assert_eq!(err.kind(), ErrorKind::InvalidEncoding);
+    assert_eq!(
+        err.to_string(),
+        "failed to parse a payload as CBOR at byte 4 of 5"
+    );
+
+    // truncated data fails at its end
+    let cose = greenpass::decode_payload(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    assert!(matches!(
+        HealthCert::from_cose_bytes(&cose[..100]),
+        Err(Error::MalformedCBOR {
+            offset: 100,
+            len: 100,
+            ..
+        })
+    ));
+
+    // positions are relative to the payload that failed, not to the whole certificate
+    let mut cose = decode_cose(VACCINE_SAMPLE_PAYLOAD);
+    cose[2] = Value::Bytes(vec![0xa1, 0x01, 0xff]);
+
+    assert!(matches!(
+        greenpass::parse(&encode_cose(cose)),
+        Err(Error::MalformedCBOR {
+            offset: 3,
+            len: 3,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn out_of_range_timestamp() {
+    // replaces the timestamp with the given CWT claim key (4 is `exp`, 6 is `iat`)
+    let with_ts = |key: i64, ts: Value| {
+        greenpass::parse(&with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+            for (k, v) in payload.iter_mut() {
+                if *k == Value::Integer(key.into()) {
+                    *v = ts.clone();
+                }
+            }
+        }))
+    };
+
+    for key in [4, 6] {
+        for ts in [i64::MAX.into(), i64::MIN.into(), u64::MAX.into()] {
+            assert!(matches!(
+                with_ts(key, Value::Integer(ts)),
+                Err(Error::MalformedDate { cause: None, .. })
+            ));
+        }
+    }
+
+    let hc = with_ts(4, Value::Integer(0.into())).unwrap();
+    assert_eq!(hc.expires, Utc.ymd(1970, 1, 1).and_hms(0, 0, 0));
+
+    let hc = with_ts(6, Value::Integer(0.into())).unwrap();
+    assert_eq!(hc.created, Utc.ymd(1970, 1, 1).and_hms(0, 0, 0));
+}
+
+#[test]
+fn parse_lenient() {
+    // adds a second pass, lacking the date of birth, to the hcert map
+    let hc1 = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        for (k, v) in payload.iter_mut() {
+            if let (Value::Integer(k), Value::Map(hcert)) = (k, v) {
+                if i128::from(*k) == -260 {
+                    let mut broken = match &hcert[0].1 {
+                        Value::Map(pass) => pass.clone(),
+                        _ => panic!("invalid pass"),
+                    };
+                    broken.retain(|(k, _)| *k != text("dob"));
+
+                    hcert.push((Value::Integer(2.into()), Value::Map(broken)));
+                }
+            }
+        }
+    });
+
+    assert!(matches!(greenpass::parse(&hc1), Err(Error::MissingKey(_))));
+
+    let (hc, errors) = greenpass::parse_lenient(&hc1).unwrap();
+
+    assert_eq!(hc, greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap());
+    assert!(matches!(&errors[..], [Error::MissingKey(k)] if k == "dob"));
+}
+
+#[test]
+fn clone_and_hash() {
+    use std::collections::HashSet;
+
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let copy = hc.clone();
+
+    assert_eq!(hc, copy);
+    assert_eq!(copy.raw.payload, hc.raw.payload);
+
+    let passes: HashSet<_> = [hc, copy, greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap()]
+        .into_iter()
+        .flat_map(|hc| hc.passes)
+        .collect();
+
+    assert_eq!(passes.len(), 2);
+}
+
+#[test]
+fn invalid_base45() {
+    use greenpass::Base45Error;
+
+    let mut pasted = VACCINE_SAMPLE_PAYLOAD.to_string();
+    pasted.insert(10, '~');
+
+    assert!(matches!(
+        greenpass::parse(&pasted),
+        Err(Error::InvalidBase45(Base45Error::InvalidCharacter {
+            position: 10,
+            found: '~'
+        }))
+    ));
+
+    let lowercase = VACCINE_SAMPLE_PAYLOAD.replacen("HC1:NCF", "HC1: ncf", 1);
+
+    assert!(matches!(
+        greenpass::parse(&lowercase),
+        Err(Error::InvalidBase45(Base45Error::InvalidCharacter {
+            position: 5,
+            found: 'n'
+        }))
+    ));
+
+    let payload_len = VACCINE_SAMPLE_PAYLOAD.len() - 4;
+    let truncated = &VACCINE_SAMPLE_PAYLOAD[..4 + payload_len - payload_len % 3 - 2];
+
+    assert!(matches!(
+        greenpass::parse(truncated),
+        Err(Error::InvalidBase45(Base45Error::Truncated { .. }))
+    ));
+
+    // valid characters and length, but out of range value
+    assert!(matches!(
+        greenpass::parse("HC1::::"),
+        Err(Error::InvalidBase45(Base45Error::Decode(_)))
+    ));
+}
+
+#[test]
+fn parse_raw_deflate() {
+    use flate2::{write::DeflateEncoder, Compression};
+    use std::io::Write;
+
+    let cose = to_cbor(&Value::Array(decode_cose(VACCINE_SAMPLE_PAYLOAD)));
+
+    let mut enc = DeflateEncoder::new(Vec::new(), Compression::default());
+    enc.write_all(&cose).unwrap();
+
+    let hc1 = format!("HC1:{}", base45::encode_from_buffer(enc.finish().unwrap()));
+
+    assert_eq!(
+        greenpass::parse(&hc1).unwrap(),
+        greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap()
+    );
+}
+
+#[test]
+fn supported_keys() {
+    use greenpass::{NAME_KEYS, RECOVERY_KEYS, TEST_KEYS, VACCINE_KEYS};
+    use std::collections::BTreeSet;
+
+    fn get<'a>(map: &'a Value, key: &Value) -> &'a Value {
+        match map {
+            Value::Map(entries) => &entries.iter().find(|(k, _)| k == key).unwrap().1,
+            _ => panic!("not a map"),
+        }
+    }
+
+    fn map_keys(map: &Value) -> BTreeSet<String> {
+        match map {
+            Value::Map(entries) => entries
+                .iter()
+                .map(|(k, _)| match k {
+                    Value::Text(k) => k.clone(),
+                    _ => panic!("not a string key"),
+                })
+                .collect(),
+            _ => panic!("not a map"),
+        }
+    }
+
+    // keys of the name map and of the first entry of type `kind` in the first pass of a sample
+    let keys = |hc1: &str, kind: &str| {
+        let payload: Value = match &decode_cose(hc1)[2] {
+            Value::Bytes(bys) => ciborium::de::from_reader(&bys[..]).unwrap(),
+            _ => panic!("invalid payload"),
+        };
+
+        let pass = get(
+            get(&payload, &Value::Integer((-260).into())),
+            &Value::Integer(1.into()),
+        );
+
+        let entry = match get(pass, &text(kind)) {
+            Value::Array(entries) => map_keys(&entries[0]),
+            _ => panic!("invalid entries"),
+        };
+
+        (map_keys(get(pass, &text("nam"))), entry)
+    };
+
+    let set = |keys: &[&str]| -> BTreeSet<String> { keys.iter().map(|k| k.to_string()).collect() };
+
+    let (name, vaccine) = keys(VACCINE_SAMPLE_PAYLOAD, "v");
+    assert_eq!(name, set(NAME_KEYS));
+    assert_eq!(vaccine, set(VACCINE_KEYS));
+
+    let (_, recovery) = keys(RECOVERY_SAMPLE_PAYLOAD, "r");
+    assert_eq!(recovery, set(RECOVERY_KEYS));
+
+    let (_, pcr) = keys(PCR_TEST_SAMPLE_PAYLOAD, "t");
+    let (_, antigen) = keys(ANTIGEN_TEST_SAMPLE_PAYLOAD, "t");
+    assert_eq!(&pcr | &antigen, set(TEST_KEYS));
+}
+
+#[test]
+fn parse_many() {
+    let input = format!(
+        "{}\r\n\n   \n  {}  \nHC1:nope\n",
+        VACCINE_SAMPLE_PAYLOAD, RECOVERY_SAMPLE_PAYLOAD
+    );
+
+    let results = greenpass::parse_many(&input);
+    let lines: Vec<_> = results.iter().map(|(n, _)| *n).collect();
+
+    assert_eq!(lines, [1, 4, 5]);
+
+    assert_eq!(
+        results[0].1.as_ref().unwrap(),
+        &greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap()
+    );
+    assert_eq!(
+        results[1].1.as_ref().unwrap(),
+        &greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap()
+    );
+    assert!(results[2].1.is_err());
+
+    assert!(greenpass::parse_many("\n \n").is_empty());
+}
+
+#[test]
+fn error_source_and_kind() {
+    use std::error::Error as _;
+
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dt", text("18/02/2021"))
+    });
+
+    let err = greenpass::parse(&hc1).unwrap_err();
+
+    assert!(matches!(&err, Error::MalformedDate { value, .. } if value == "18/02/2021"));
+    assert!(err.source().unwrap().is::<chrono::ParseError>());
+    assert_eq!(err.kind(), ErrorKind::MalformedStructure);
+
+    assert_eq!(
+        greenpass::parse("nope").unwrap_err().kind(),
+        ErrorKind::InvalidEncoding
+    );
+    assert_eq!(
+        greenpass::parse("HC1:NCF").unwrap_err().kind(),
+        ErrorKind::InvalidEncoding
+    );
+    assert_eq!(
+        Error::MissingKey("dob".into()).kind(),
+        ErrorKind::MissingField
+    );
+    assert_eq!(Error::InvalidSignature.kind(), ErrorKind::Signature);
+}
+
+#[test]
+fn dates_keep_raw_form() {
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dt", text("2021-2-18"))
+    });
+
+    let hc = greenpass::parse(&hc1).unwrap();
+    let vaccine = match &hc.passes[0].entries[0] {
+        CertInfo::Vaccine(v) => v,
+        _ => panic!("not a vaccine"),
+    };
+
+    assert_eq!(vaccine.date, NaiveDate::from_ymd(2021, 2, 18));
+    assert_eq!(vaccine.date.raw, "2021-2-18");
+    assert!(!vaccine.date.is_canonical());
+    assert_eq!(vaccine.date.to_string(), "2021-02-18");
+
+    let json = serde_json::to_value(vaccine).unwrap();
+    assert_eq!(json["date"], "2021-2-18");
+
+    let canonical = Dated::from(NaiveDate::from_ymd(2021, 2, 18));
+    assert!(canonical.is_canonical());
+    assert_eq!(canonical.raw, "2021-02-18");
+}
+
+#[test]
+fn normalize() {
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dt", text("2021-2-18"));
+        set_key(vaccine, "co", text("at"));
+        set_key(vaccine, "is", text(" Ministry of Health, Austria\n"));
+    });
+
+    let mut hc = greenpass::parse(&hc1).unwrap();
+    hc.passes[0].surname = Some(" Musterfrau-Gößinger ".into());
+    hc.passes[0].std_surname = "MUSTERFRAU<GOESSINGER\t".into();
+    hc.passes[0].date_of_birth = " 1998-02-26".into();
+
+    let raw = hc.raw.clone();
+    hc.normalize();
+
+    let pass = &hc.passes[0];
+    assert_eq!(pass.surname.as_deref(), Some("Musterfrau-Gößinger"));
+    assert_eq!(pass.std_surname, "MUSTERFRAU<GOESSINGER");
+    assert_eq!(pass.date_of_birth, "1998-02-26");
+
+    let vaccine = match &pass.entries[0] {
+        CertInfo::Vaccine(v) => v,
+        _ => panic!("not a vaccine"),
+    };
+
+    assert_eq!(vaccine.country, "AT");
+    assert_eq!(vaccine.issuer, "Ministry of Health, Austria");
+    assert_eq!(vaccine.date.raw, "2021-02-18");
+    assert!(vaccine.date.is_canonical());
+    assert_eq!(hc.raw.payload, raw.payload);
+
+    // partial dates of birth are kept as they are
+    hc.passes[0].date_of_birth = "1998 ".into();
+    hc.normalize();
+    assert_eq!(hc.passes[0].date_of_birth, "1998");
+}
+
+#[test]
+fn padded_input() {
+    use greenpass::Base45Error;
+
+    let expected = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let padded = format!("\u{feff} \n{}\0\0", VACCINE_SAMPLE_PAYLOAD);
+    assert_eq!(greenpass::parse(&padded).unwrap(), expected);
+
+    let mut bytes = b"\xef\xbb\xbf\r\n".to_vec();
+    bytes.extend_from_slice(VACCINE_SAMPLE_PAYLOAD.as_bytes());
+    bytes.extend_from_slice(b"\0\0\0");
+
+    assert_eq!(greenpass::parse_bytes(&bytes).unwrap(), expected);
+    assert_eq!(HealthCert::try_from(&bytes[..]).unwrap(), expected);
+
+    // invalid UTF-8 within the payload
+    bytes[10] = 0xff;
+
+    assert!(matches!(
+        greenpass::parse_bytes(&bytes),
+        Err(Error::InvalidBase45(Base45Error::InvalidCharacter {
+            position: 5,
+            found: '\u{fffd}'
+        }))
+    ));
+
+    assert!(matches!(
+        greenpass::parse_bytes(b"\xffHC1:NCF"),
+        Err(Error::MissingHCID)
+    ));
+}
+
+#[test]
+fn repeated_prefix() {
+    let expected = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    for prefix in ["HC1:", "HC1:HC1:", "HC1: HC1:"] {
+        let doubled = format!("{}{}", prefix, VACCINE_SAMPLE_PAYLOAD);
+
+        assert_eq!(greenpass::parse(&doubled).unwrap(), expected);
+        assert_eq!(
+            greenpass::parse_bytes(doubled.as_bytes()).unwrap(),
+            expected
+        );
+    }
+
+    // positions in errors still refer to the original input
+    let doubled = format!("HC1:{}", VACCINE_SAMPLE_PAYLOAD.replacen('N', "a", 1));
+
+    assert!(matches!(
+        greenpass::parse(&doubled),
+        Err(Error::InvalidBase45(
+            greenpass::Base45Error::InvalidCharacter {
+                position: 8,
+                found: 'a'
+            }
+        ))
+    ));
+}
+
+#[test]
+fn trailing_control_characters() {
+    let expected = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let terminated = format!("{}\u{1d}", VACCINE_SAMPLE_PAYLOAD);
+    assert_eq!(greenpass::parse(&terminated).unwrap(), expected);
+
+    let terminated = format!("{}\u{1d}\u{4}\r\n", VACCINE_SAMPLE_PAYLOAD);
+    assert_eq!(greenpass::parse(&terminated).unwrap(), expected);
+
+    let mut bytes = VACCINE_SAMPLE_PAYLOAD.as_bytes().to_vec();
+    bytes.extend_from_slice(b"\x1d\xff\0");
+
+    assert_eq!(greenpass::parse_bytes(&bytes).unwrap(), expected);
+}
+
+#[test]
+fn mangled_input() {
+    let expected = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let wrapped = VACCINE_SAMPLE_PAYLOAD
+        .as_bytes()
+        .chunks(60)
+        .map(|line| std::str::from_utf8(line).unwrap())
+        .collect::<Vec<_>>()
+        .join("\r\n\t");
+
+    assert_eq!(greenpass::parse(&wrapped).unwrap(), expected);
+    assert_eq!(
+        greenpass::parse_bytes(wrapped.as_bytes()).unwrap(),
+        expected
+    );
+
+    let encoded: String = VACCINE_SAMPLE_PAYLOAD
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+
+    assert!(encoded.starts_with("HC1%3A"));
+    assert_eq!(greenpass::parse(&encoded).unwrap(), expected);
+    assert_eq!(
+        greenpass::parse_bytes(encoded.as_bytes()).unwrap(),
+        expected
+    );
+
+    // framing bytes after a percent-encoded payload
+    let mut framed = encoded.clone().into_bytes();
+    framed.extend_from_slice(b"\x1d\xff");
+
+    assert_eq!(HealthCert::try_from(&framed[..]).unwrap(), expected);
+    assert_eq!(
+        greenpass::parse(&encoded.replacen("%3A", "%3a", 1)).unwrap(),
+        expected
+    );
+
+    // `%` is a Base45 character, so payloads starting with a plain prefix are never decoded
+    assert!(VACCINE_SAMPLE_PAYLOAD.contains('%'));
+    assert_eq!(
+        HealthCert::try_from(VACCINE_SAMPLE_PAYLOAD).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn entry_error_paths() {
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "ci", Value::Integer(42.into()))
+    });
+
+    assert!(matches!(
+        greenpass::parse(&hc1),
+        Err(Error::InvalidFormatFor { key }) if key == "v[0].ci"
+    ));
+
+    let hc1 = with_entry(RECOVERY_SAMPLE_PAYLOAD, "r", |recovery| {
+        recovery.retain(|(k, _)| *k != text("fr"))
+    });
+
+    assert!(matches!(
+        greenpass::parse(&hc1),
+        Err(Error::MissingKey(key)) if key == "r[0].fr"
+    ));
+
+    let hc1 = with_payload(PCR_TEST_SAMPLE_PAYLOAD, |payload| {
+        for (k, v) in payload.iter_mut() {
+            if let (Value::Integer(k), Value::Map(hcert)) = (k, v) {
+                if i128::from(*k) == -260 {
+                    if let Value::Map(pass) = &mut hcert[0].1 {
+                        set_key(pass, "t", Value::Array(vec![text("nope")]));
+                    }
+                }
+            }
+        }
+    });
+
+    assert!(matches!(
+        greenpass::parse(&hc1),
+        Err(Error::InvalidFormatFor { key }) if key == "t[0]"
+    ));
+}
+
+#[test]
+fn duplicate_keys() {
+    use greenpass::ParseOptions;
+
+    let strict = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    // a second date of birth, after the original one
+    let hc1 = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        for (k, v) in payload.iter_mut() {
+            if let (Value::Integer(k), Value::Map(hcert)) = (k, v) {
+                if i128::from(*k) == -260 {
+                    if let Value::Map(pass) = &mut hcert[0].1 {
+                        pass.push((text("dob"), text("2001-01-01")));
+                    }
+                }
+            }
+        }
+    });
+
+    assert_eq!(
+        greenpass::parse(&hc1).unwrap().passes[0].date_of_birth,
+        "2001-01-01"
+    );
+    assert!(matches!(
+        greenpass::parse_with(&hc1, &strict),
+        Err(Error::DuplicateKey(key)) if key == "-260.1.dob"
+    ));
+
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        vaccine.push((text("ci"), text("URN:UVCI:01:AT:FORGED")))
+    });
+
+    assert!(matches!(
+        greenpass::parse_with(&hc1, &strict),
+        Err(Error::DuplicateKey(key)) if key == "-260.1.v[0].ci"
+    ));
+
+    greenpass::parse_with(VACCINE_SAMPLE_PAYLOAD, &strict).unwrap();
+}
+
+#[test]
+fn from_str() {
+    let hc: HealthCert = VACCINE_SAMPLE_PAYLOAD.parse().unwrap();
+
+    assert_eq!(hc, greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap());
+    assert!(matches!(
+        "nope".parse::<HealthCert>(),
+        Err(Error::MissingHCID)
+    ));
+}
+
+#[test]
+fn extract_hc1() {
+    let mut framed = b"\x02\xff\xfe".to_vec();
+    framed.extend_from_slice(VACCINE_SAMPLE_PAYLOAD.as_bytes());
+    framed.extend_from_slice(b"  \r\n\x03\xff");
+
+    let hc1 = greenpass::extract_hc1(&framed).unwrap();
+
+    assert_eq!(hc1, VACCINE_SAMPLE_PAYLOAD);
+    greenpass::parse(hc1).unwrap();
+
+    assert_eq!(greenpass::extract_hc1(b"\xffHC1:\xff"), Some("HC1:"));
+    assert_eq!(greenpass::extract_hc1(b"\xff HC1 \xff"), None);
+}
+
+#[test]
+fn negative_doses() {
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dn", Value::Integer((-1).into()))
+    });
+
+    assert!(matches!(
+        greenpass::parse(&hc1),
+        Err(Error::InvalidFormatFor { key }) if key == "v[0].dn"
+    ));
+
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "sd", Value::Integer(i64::MIN.into()))
+    });
+
+    assert!(matches!(
+        greenpass::parse(&hc1),
+        Err(Error::InvalidFormatFor { key }) if key == "v[0].sd"
+    ));
+}
+
+#[test]
+fn version_dependent_fields() {
+    let with_version = |ver: &str, f: fn(&mut Vec<(Value, Value)>)| {
+        let hc1 = with_entry(PCR_TEST_SAMPLE_PAYLOAD, "t", f);
+
+        with_pass(&hc1, |pass| set_key(pass, "ver", text(ver)))
+    };
+
+    let without_tc = |test: &mut Vec<(Value, Value)>| test.retain(|(k, _)| *k != text("tc"));
+    let with_dr =
+        |test: &mut Vec<(Value, Value)>| test.push((text("dr"), text("2021-02-20T14:30:00Z")));
+
+    // the testing centre is only optional since 1.3.0
+    let hc = greenpass::parse(&with_version("1.3.0", without_tc)).unwrap();
+    let test = hc.passes[0].entries[0].as_test().unwrap();
+    assert_eq!(test.testing_centre, None);
+    assert!(!hc.to_string().contains("Conducted by"));
+
+    assert!(matches!(
+        greenpass::parse(&with_version("1.2.1", without_tc)),
+        Err(Error::MissingKey(key)) if key == "t[0].tc"
+    ));
+
+    // the result date was removed in 1.3.0
+    let hc = greenpass::parse(&with_version("1.0.0", with_dr)).unwrap();
+    let test = hc.passes[0].entries[0].as_test().unwrap();
+    assert_eq!(
+        test.result_ts,
+        Some(FixedOffset::east(0).ymd(2021, 2, 20).and_hms(14, 30, 0))
+    );
+    assert!(hc.passes[0].present_keys().contains("t.dr"));
+
+    assert!(matches!(
+        greenpass::parse(&with_version("1.3.0", with_dr)),
+        Err(Error::SpuriousData(_))
+    ));
+
+    // unparseable versions follow the latest schema
+    assert!(greenpass::parse(&with_version("latest", without_tc)).is_ok());
+}
+
+#[test]
+fn partial_names() {
+    let hc1 = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        for (k, v) in payload.iter_mut() {
+            if let (Value::Integer(k), Value::Map(hcert)) = (k, v) {
+                if i128::from(*k) == -260 {
+                    if let Value::Map(pass) = &mut hcert[0].1 {
+                        set_key(
+                            pass,
+                            "nam",
+                            cbor_map(&[
+                                ("fn", text("Musterfrau-Gößinger")),
+                                ("fnt", text("MUSTERFRAU<GOESSINGER")),
+                            ]),
+                        );
+                    }
+                }
+            }
+        }
+    });
+
+    let hc = greenpass::parse(&hc1).unwrap();
+    let pass = &hc.passes[0];
+
+    assert_eq!(pass.surname.as_deref(), Some("Musterfrau-Gößinger"));
+    assert_eq!(pass.givenname, None);
+    assert_eq!(pass.std_givenname, None);
+    assert_eq!(pass.std_surname, "MUSTERFRAU<GOESSINGER");
+
+    let dump = hc.to_string();
+    assert!(dump.contains("Emitted to: Musterfrau-Gößinger\n"));
+    assert!(dump.contains("Standardized Name: MUSTERFRAU<GOESSINGER\n"));
+}
+
+#[test]
+fn collection_timestamp_offsets() {
+    let parse_sc = |sc: &str| {
+        let hc1 = with_entry(PCR_TEST_SAMPLE_PAYLOAD, "t", |test| {
+            set_key(test, "sc", text(sc))
+        });
+
+        greenpass::parse(&hc1).unwrap()
+    };
+
+    let zulu = parse_sc("2021-02-20T04:34:56Z");
+    let explicit = parse_sc("2021-02-20T04:34:56+00:00");
+    let local = parse_sc("2021-02-20T06:34:56+02:00");
+
+    let test = |hc: &HealthCert| hc.passes[0].entries[0].as_test().unwrap().clone();
+
+    assert_eq!(test(&zulu), test(&explicit));
+    assert_eq!(
+        test(&zulu).collect_ts.offset(),
+        test(&explicit).collect_ts.offset()
+    );
+
+    let local_test = test(&local);
+    assert_eq!(local_test.collect_ts.offset().local_minus_utc(), 7200);
+    assert_eq!(
+        local_test.collect_ts_utc(),
+        Utc.ymd(2021, 2, 20).and_hms(4, 34, 56)
+    );
+    assert_eq!(local_test.collect_ts_utc(), test(&zulu).collect_ts_utc());
+
+    assert!(local
+        .to_string()
+        .contains("Samples collected at: 2021-02-20 06:34:56 +02:00 (2021-02-20 04:34:56 UTC)\n"));
+    assert!(zulu
+        .to_string()
+        .contains("Samples collected at: 2021-02-20 04:34:56 +00:00\n"));
+}
+
+#[test]
+fn loose_collection_timestamps() {
+    let collect_ts = |sc: &str| {
+        let hc1 = with_entry(PCR_TEST_SAMPLE_PAYLOAD, "t", |test| {
+            set_key(test, "sc", text(sc))
+        });
+
+        greenpass::parse(&hc1).unwrap().passes[0].entries[0]
+            .as_test()
+            .unwrap()
+            .collect_ts
+    };
+
+    let expected = FixedOffset::east(3600).ymd(2021, 2, 20).and_hms(13, 34, 0);
+
+    for sc in [
+        "2021-02-20T12:34:00Z",
+        "2021-02-20T12:34Z",
+        "2021-02-20T13:34:00+01:00",
+        "2021-02-20T13:34:00+0100",
+        "2021-02-20T13:34:00+01",
+        "2021-02-20T13:34+01:00",
+        "2021-02-20T13:34+0100",
+        "2021-02-20T13:34:00.000+0100",
+    ] {
+        assert_eq!(collect_ts(sc), expected, "{}", sc);
+    }
+
+    assert_eq!(
+        collect_ts("2021-02-20T13:34+0100")
+            .offset()
+            .local_minus_utc(),
+        3600
+    );
+    assert_eq!(
+        collect_ts("2021-02-20T12:34Z").offset().local_minus_utc(),
+        0
+    );
+
+    let hc1 = with_entry(PCR_TEST_SAMPLE_PAYLOAD, "t", |test| {
+        set_key(test, "sc", text("2021-02-20T12Z"))
+    });
+
+    assert!(matches!(
+        greenpass::parse(&hc1),
+        Err(Error::MalformedDate { value, .. }) if value == "2021-02-20T12Z"
+    ));
+}
+
+#[test]
+fn to_hc1() {
+    for hc1 in [
+        VACCINE_SAMPLE_PAYLOAD,
+        VACCINE_SAMPLE_PAYLOAD_UNPROTECTED_KID,
+        RECOVERY_SAMPLE_PAYLOAD,
+    ] {
+        let hc = greenpass::parse(hc1).unwrap();
+        let encoded = hc.to_hc1();
+
+        assert!(encoded.starts_with("HC1:"));
+
+        let reparsed = greenpass::parse(&encoded).unwrap();
+        assert_eq!(reparsed, hc);
+        assert_eq!(reparsed.signed_data(), hc.signed_data());
+    }
+}
+
+#[test]
+fn warnings() {
+    for hc1 in [
+        VACCINE_SAMPLE_PAYLOAD,
+        PCR_TEST_SAMPLE_PAYLOAD,
+        ANTIGEN_TEST_SAMPLE_PAYLOAD,
+        RECOVERY_SAMPLE_PAYLOAD,
+    ] {
+        let (hc, warnings) = greenpass::parse_with_warnings(hc1).unwrap();
+
+        assert_eq!(hc, greenpass::parse(hc1).unwrap());
+        assert_eq!(warnings, []);
+    }
+
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dn", Value::Integer(0.into()));
+        set_key(vaccine, "mp", text("EU/1/99/0000"));
+        set_key(vaccine, "co", text("ZZ"));
+    });
+
+    let (_, warnings) = greenpass::parse_with_warnings(&hc1).unwrap();
+    assert_eq!(
+        warnings,
+        [
+            Warning::UnknownCountry {
+                path: "v[0].co".into(),
+                country: "ZZ".into()
+            },
+            Warning::InvalidDoses {
+                path: "v[0].dn".into(),
+                number: 0,
+                total: 2
+            },
+            Warning::UnknownVaccine {
+                path: "v[0].mp".into(),
+                product: "EU/1/99/0000".into()
+            },
+        ]
+    );
+    assert_eq!(warnings[1].to_string(), "v[0].dn: invalid dose count 0/2");
+
+    let hc1 = with_entry(RECOVERY_SAMPLE_PAYLOAD, "r", |recovery| {
+        set_key(recovery, "co", text("DE"))
+    });
+
+    let (_, warnings) = greenpass::parse_with_warnings(&hc1).unwrap();
+    assert!(matches!(
+        &warnings[..],
+        [Warning::IssuerCountryMismatch { path, .. }] if path == "r[0].is"
+    ));
+
+    // passes are told apart by their index when there is more than one
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "co", text("ZZ"))
+    });
+    let hc1 = with_payload(&hc1, |payload| {
+        for (k, v) in payload.iter_mut() {
+            if let (Value::Integer(k), Value::Map(hcert)) = (k, v) {
+                if i128::from(*k) == -260 {
+                    hcert.push((Value::Integer(2.into()), hcert[0].1.clone()));
+                }
+            }
+        }
+    });
+
+    let (_, warnings) = greenpass::parse_with_warnings(&hc1).unwrap();
+    assert_eq!(
+        warnings,
+        ["passes[0].v[0].co", "passes[1].v[0].co"]
+            .iter()
+            .map(|path| Warning::UnknownCountry {
+                path: path.to_string(),
+                country: "ZZ".into()
+            })
+            .collect::<Vec<_>>()
+    );
+
+    // hard errors are still errors
+    assert!(greenpass::parse_with_warnings("HC1:NCFOXN%TS3DH").is_err());
+}
+
+#[test]
+fn test_timestamp_plausibility() {
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+    let mut test = hc.tests().next().unwrap().clone();
+    let collected = test.collect_ts_utc();
+
+    assert!(test.is_timestamp_plausible(hc.created));
+    assert!(test.is_timestamp_plausible(collected));
+    assert!(!test.is_timestamp_plausible(collected - chrono::Duration::seconds(1)));
+
+    // results come after the samples are collected, and before the certificate is issued
+    test.result_ts = Some((collected - chrono::Duration::hours(1)).into());
+    assert!(!test.is_timestamp_plausible(hc.created));
+
+    test.result_ts = Some((collected + chrono::Duration::hours(1)).into());
+    assert!(test.is_timestamp_plausible(collected + chrono::Duration::hours(1)));
+    assert!(!test.is_timestamp_plausible(collected));
+
+    let hc1 = with_entry(PCR_TEST_SAMPLE_PAYLOAD, "t", |test| {
+        set_key(test, "sc", text("2030-01-01T00:00:00Z"))
+    });
+
+    let (_, warnings) = greenpass::parse_with_warnings(&hc1).unwrap();
+    assert_eq!(
+        warnings,
+        [Warning::ImplausibleTimestamp {
+            path: "t[0].sc".into()
+        }]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "t[0].sc: test timestamps do not fit the issue time"
+    );
+
+    // a result produced before the samples were collected
+    let hc1 = with_entry(PCR_TEST_SAMPLE_PAYLOAD, "t", |test| {
+        test.push((text("dr"), text("2021-01-01T00:00:00Z")))
+    });
+
+    let (_, warnings) = greenpass::parse_with_warnings(&hc1).unwrap();
+    assert_eq!(
+        warnings,
+        [Warning::ImplausibleTimestamp {
+            path: "t[0].dr".into()
+        }]
+    );
+}
+
+#[test]
+fn decode_payload() {
+    let cose = greenpass::decode_payload(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    // COSE_Sign1 tag, followed by the four-element array
+    assert_eq!(cose[..2], [0xd2, 0x84]);
+    assert_eq!(
+        HealthCert::from_cose_bytes(&cose).unwrap(),
+        greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap()
+    );
+
+    assert!(matches!(
+        greenpass::decode_payload("NCFOXN%TS3DH"),
+        Err(Error::MissingHCID)
+    ));
+}
+
+#[test]
+fn missing_names() {
+    use greenpass::ParseOptions;
+
+    // a minimal certificate, holding no `nam` map
+    let hc1 = with_pass(RECOVERY_SAMPLE_PAYLOAD, |pass| {
+        pass.retain(|(k, _)| *k != text("nam"))
+    });
+
+    let hc = greenpass::parse(&hc1).unwrap();
+    let pass = &hc.passes[0];
+
+    assert_eq!(pass.surname, None);
+    assert_eq!(pass.givenname, None);
+    assert_eq!(pass.std_surname, "");
+    assert_eq!(pass.std_givenname, None);
+    assert_eq!(pass.date_of_birth, "1998-02-26");
+    assert!(!pass.present_keys().contains("nam"));
+    assert!(hc.to_string().contains("Date of birth: 1998-02-26"));
+
+    assert_eq!(
+        greenpass::parse_summary(&hc1).unwrap().passes[0].std_surname,
+        ""
+    );
+
+    let strict = ParseOptions {
+        strict: true,
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        greenpass::parse_with(&hc1, &strict),
+        Err(Error::MissingKey(key)) if key == "nam"
+    ));
+}
+
+#[test]
+fn allowed_diseases() {
+    use greenpass::ParseOptions;
+
+    let covid_only = ParseOptions {
+        allowed_diseases: Some([greenpass::COVID_19.to_string()].into()),
+        ..Default::default()
+    };
+
+    for hc1 in [
+        VACCINE_SAMPLE_PAYLOAD,
+        PCR_TEST_SAMPLE_PAYLOAD,
+        RECOVERY_SAMPLE_PAYLOAD,
+    ] {
+        greenpass::parse_with(hc1, &covid_only).unwrap();
+    }
+
+    // any disease is accepted by default
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "tg", text("6142004"))
+    });
+    greenpass::parse(&hc1).unwrap();
+
+    let err = greenpass::parse_with(&hc1, &covid_only).unwrap_err();
+    assert!(matches!(&err, Error::DiseaseNotAllowed(tg) if tg == "6142004"));
+    assert_eq!(err.kind(), ErrorKind::MalformedStructure);
+
+    let nothing = ParseOptions {
+        allowed_diseases: Some(Default::default()),
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        greenpass::parse_with(RECOVERY_SAMPLE_PAYLOAD, &nothing),
+        Err(Error::DiseaseNotAllowed(tg)) if tg == greenpass::COVID_19
+    ));
+}
+
+#[test]
+fn standardized_names() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let mut pass = hc.passes[0].clone();
+    assert!(pass.standardized_names_valid());
+
+    pass.std_givenname = Some("GABRIELE<2".into());
+    assert!(pass.standardized_names_valid());
+
+    pass.std_givenname = None;
+    pass.std_surname.clear();
+    assert!(pass.standardized_names_valid());
+
+    let hc1 = with_pass(VACCINE_SAMPLE_PAYLOAD, |pass| {
+        set_key(
+            pass,
+            "nam",
+            cbor_map(&[
+                ("fn", text("Musterfrau-Gößinger")),
+                ("fnt", text("MUSTERFRAU<GÖSSINGER")),
+                ("gnt", text("Gabriele")),
+            ]),
+        )
+    });
+
+    let (hc, warnings) = greenpass::parse_with_warnings(&hc1).unwrap();
+    assert!(!hc.passes[0].standardized_names_valid());
+    assert_eq!(
+        warnings,
+        [
+            Warning::InvalidStandardizedName {
+                path: "nam.fnt".into(),
+                name: "MUSTERFRAU<GÖSSINGER".into()
+            },
+            Warning::InvalidStandardizedName {
+                path: "nam.gnt".into(),
+                name: "Gabriele".into()
+            },
+        ]
+    );
+    assert_eq!(
+        warnings[1].to_string(),
+        "nam.gnt: invalid standardized name \"Gabriele\""
+    );
+}
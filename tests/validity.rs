@@ -0,0 +1,216 @@
+use chrono::prelude::*;
+use ciborium::value::Value;
+use greenpass::{CertInfo, Clock, Error, FixedClock, SystemClock, ValidityStatus};
+
+mod common;
+use common::*;
+
+#[test]
+fn health_cert_validity() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let before = Utc.ymd(2021, 7, 1).and_hms(0, 0, 0);
+    let during = Utc.ymd(2021, 12, 25).and_hms(12, 0, 0);
+    let after = Utc.ymd(2022, 7, 3).and_hms(0, 0, 0);
+
+    assert!(!hc.is_expired(during));
+    assert!(hc.is_expired(after));
+    assert!(hc.is_expired(hc.expires));
+
+    assert_eq!(hc.validity_status(before), ValidityStatus::NotYetValid);
+    assert_eq!(hc.validity_status(hc.created), ValidityStatus::Valid);
+    assert_eq!(hc.validity_status(during), ValidityStatus::Valid);
+    assert_eq!(hc.validity_status(after), ValidityStatus::Expired);
+}
+
+#[test]
+fn clocks() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    let during = FixedClock(Utc.ymd(2021, 12, 25).and_hms(12, 0, 0));
+    let after = FixedClock(Utc.ymd(2022, 7, 3).and_hms(0, 0, 0));
+
+    assert_eq!(during.now(), Utc.ymd(2021, 12, 25).and_hms(12, 0, 0));
+    assert!(!hc.is_expired_with(&during));
+    assert!(hc.is_expired_with(&after));
+    assert_eq!(hc.validity_status_with(&during), ValidityStatus::Valid);
+    assert_eq!(hc.validity_status_with(&after), ValidityStatus::Expired);
+
+    let recovery = greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap();
+    let window = recovery.passes[0].entries[0]
+        .validity_window(&Default::default())
+        .unwrap();
+    assert!(window.is_current(&FixedClock(window.from)));
+    assert!(!window.is_current(&FixedClock(window.from - chrono::Duration::seconds(1))));
+
+    // the samples expired long ago
+    assert!(SystemClock.now() > Utc.ymd(2024, 1, 1).and_hms(0, 0, 0));
+    assert_eq!(
+        hc.validity_status_with(&SystemClock),
+        ValidityStatus::Expired
+    );
+}
+
+#[test]
+fn recovery_validity() {
+    let hc = greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap();
+
+    let recovery = match &hc.passes[0].entries[0] {
+        CertInfo::Recovery(r) => r,
+        _ => panic!("expected a recovery entry"),
+    };
+
+    assert!(!recovery.is_valid_on(NaiveDate::from_ymd(2021, 4, 3)));
+    assert!(recovery.is_valid_on(NaiveDate::from_ymd(2021, 4, 4)));
+    assert!(recovery.is_valid_on(NaiveDate::from_ymd(2021, 10, 4)));
+    assert!(!recovery.is_valid_on(NaiveDate::from_ymd(2021, 10, 5)));
+}
+
+#[test]
+fn not_before_and_cwt_id() {
+    let nbf = Utc.ymd(2021, 8, 1).and_hms(0, 0, 0);
+
+    let hc1 = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        payload.push((
+            Value::Integer(5.into()),
+            Value::Integer(nbf.timestamp().into()),
+        ));
+        payload.push((Value::Integer(7.into()), Value::Bytes(vec![0xCA, 0xFE])));
+    });
+
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    assert_eq!(hc.not_before, Some(nbf));
+    assert_eq!(hc.cwt_id.as_deref(), Some(&[0xCA, 0xFE][..]));
+
+    assert_eq!(hc.validity_status(hc.created), ValidityStatus::NotYetValid);
+    assert_eq!(hc.validity_status(nbf), ValidityStatus::Valid);
+
+    let json = serde_json::to_value(&hc).unwrap();
+    assert_eq!(json["cwt_id"], "cafe");
+
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    assert_eq!(hc.not_before, None);
+    assert_eq!(hc.cwt_id, None);
+
+    let hc1 = with_payload(VACCINE_SAMPLE_PAYLOAD, |payload| {
+        payload.push((Value::Integer(7.into()), Value::Text("id".into())));
+    });
+
+    assert!(matches!(
+        greenpass::parse(&hc1),
+        Err(Error::InvalidFormatFor { key }) if key == "CWT ID"
+    ));
+}
+
+#[test]
+fn entry_validity_windows() {
+    use greenpass::{ValidityPolicy, ValidityWindow};
+
+    let policy = ValidityPolicy::default();
+
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let mut vaccine = hc.passes[0].entries[0].as_vaccine().unwrap().clone();
+
+    // dose 1/2, vaccinated on 2021-02-18
+    assert_eq!(
+        vaccine.default_validity(),
+        (
+            NaiveDate::from_ymd(2021, 3, 4),
+            Some(NaiveDate::from_ymd(2021, 11, 15))
+        )
+    );
+    assert_eq!(
+        CertInfo::Vaccine(vaccine.clone()).validity_window(&policy),
+        None
+    );
+
+    vaccine.dose_number = 2;
+
+    let window = CertInfo::Vaccine(vaccine.clone())
+        .validity_window(&policy)
+        .unwrap();
+
+    assert!(!window.contains(Utc.ymd(2021, 3, 3).and_hms(23, 59, 59)));
+    assert!(window.contains(Utc.ymd(2021, 3, 4).and_hms(0, 0, 0)));
+    assert!(window.contains(Utc.ymd(2021, 11, 15).and_hms(23, 59, 59)));
+    assert!(!window.contains(Utc.ymd(2021, 11, 16).and_hms(0, 0, 0)));
+
+    vaccine.dose_number = 3;
+    assert_eq!(
+        vaccine.default_validity(),
+        (NaiveDate::from_ymd(2021, 2, 18), None)
+    );
+
+    let lenient = ValidityPolicy {
+        vaccine_delay_days: 0,
+        vaccine_validity_days: None,
+        ..Default::default()
+    };
+
+    vaccine.dose_number = 2;
+    assert_eq!(
+        vaccine.validity(&lenient),
+        (NaiveDate::from_ymd(2021, 2, 18), None)
+    );
+
+    let hc = greenpass::parse(PCR_TEST_SAMPLE_PAYLOAD).unwrap();
+    let collected = Utc.ymd(2021, 2, 20).and_hms(4, 34, 56);
+
+    assert_eq!(
+        hc.passes[0].entries[0].validity_window(&policy),
+        Some(ValidityWindow {
+            from: collected,
+            until: Some(collected + chrono::Duration::hours(72)),
+        })
+    );
+
+    let hc = greenpass::parse(ANTIGEN_TEST_SAMPLE_PAYLOAD).unwrap();
+    let window = hc.passes[0].entries[0].validity_window(&policy).unwrap();
+
+    assert_eq!(
+        window.until.unwrap() - window.from,
+        chrono::Duration::hours(48)
+    );
+
+    let hc = greenpass::parse(RECOVERY_SAMPLE_PAYLOAD).unwrap();
+    let window = hc.passes[0].entries[0].validity_window(&policy).unwrap();
+
+    assert!(window.contains(Utc.ymd(2021, 10, 4).and_hms(12, 0, 0)));
+    assert!(!window.contains(Utc.ymd(2021, 10, 5).and_hms(0, 0, 0)));
+}
+
+#[test]
+fn validity_at_the_end_of_time() {
+    use greenpass::ValidityPolicy;
+
+    let policy = ValidityPolicy::default();
+
+    let hc1 = with_entry(VACCINE_SAMPLE_PAYLOAD, "v", |vaccine| {
+        set_key(vaccine, "dt", text("+262143-12-20"));
+        set_key(vaccine, "dn", Value::Integer(2.into()));
+    });
+    let hc = greenpass::parse(&hc1).unwrap();
+    let vaccine = &hc.passes[0].entries[0];
+
+    let last = NaiveDate::from_ymd(262143, 12, 31);
+
+    assert_eq!(
+        hc.vaccines().next().unwrap().validity(&policy),
+        (last, None)
+    );
+
+    let window = vaccine.validity_window(&policy).unwrap();
+    assert_eq!(window.until, None);
+    assert!(!window.contains(Utc.ymd(2022, 1, 1).and_hms(0, 0, 0)));
+    assert!(hc.passes[0].summary_line().ends_with("no exp"));
+
+    let hc1 = with_entry(RECOVERY_SAMPLE_PAYLOAD, "r", |recovery| {
+        set_key(recovery, "du", text("+262143-12-31"));
+    });
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    let window = hc.passes[0].entries[0].validity_window(&policy).unwrap();
+    assert_eq!(window.until, None);
+}
@@ -0,0 +1,445 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use ciborium::value::Value;
+use greenpass::{
+    kid_from_der, kid_from_pem, CoseAlgorithm, Error, ErrorKind, KeyFamily, TrustList,
+    VerificationKey,
+};
+use p256::{ecdsa::signature::Signer, pkcs8::DecodePrivateKey};
+use rsa::signature::RandomizedSigner;
+use sha2::Sha256;
+
+mod common;
+use common::*;
+
+const EC_KEY: &str = include_str!("data/dsc_ec.key");
+const EC_CERT: &str = include_str!("data/dsc_ec.pem");
+const P384_KEY: &str = include_str!("data/dsc_p384.key");
+const P384_CERT: &str = include_str!("data/dsc_p384.pem");
+const P521_KEY: &str = include_str!("data/dsc_p521.key");
+const P521_CERT: &str = include_str!("data/dsc_p521.pem");
+const RSA_KEY: &str = include_str!("data/dsc_rsa.key");
+const RSA_CERT: &str = include_str!("data/dsc_rsa.pem");
+
+// DSC issued by an RSA intermediate, in turn issued by a P-384 CSCA
+const CHAIN_KEY: &str = include_str!("data/dsc_chain.key");
+const CHAIN_CERT: &str = include_str!("data/dsc_chain.pem");
+const INTERMEDIATE_CERT: &str = include_str!("data/intermediate.pem");
+const CSCA_CERT: &str = include_str!("data/csca.pem");
+
+const KID: [u8; 8] = [217, 25, 55, 95, 193, 231, 182, 178];
+
+fn sign_es256(data: &[u8]) -> Vec<u8> {
+    let key = p256::ecdsa::SigningKey::from_pkcs8_pem(EC_KEY).unwrap();
+    let sig: p256::ecdsa::Signature = key.sign(data);
+
+    sig.to_vec()
+}
+
+fn sign_es384(data: &[u8]) -> Vec<u8> {
+    let key = p384::ecdsa::SigningKey::from_pkcs8_pem(P384_KEY).unwrap();
+    let sig: p384::ecdsa::Signature = key.sign(data);
+
+    sig.to_vec()
+}
+
+fn sign_es512(data: &[u8]) -> Vec<u8> {
+    let secret = p521::SecretKey::from_pkcs8_pem(P521_KEY).unwrap();
+    let key = p521::ecdsa::SigningKey::from_bytes(&secret.to_bytes()).unwrap();
+    let sig: p521::ecdsa::Signature = key.sign_with_rng(&mut rand::thread_rng(), data);
+
+    sig.to_vec()
+}
+
+fn sign_ps256(data: &[u8]) -> Vec<u8> {
+    let key = rsa::RsaPrivateKey::from_pkcs8_pem(RSA_KEY).unwrap();
+    let key = rsa::pss::SigningKey::<Sha256>::new(key);
+
+    Box::<[u8]>::from(key.sign_with_rng(&mut rand::thread_rng(), data)).into()
+}
+
+fn pem_body(pem: &str) -> String {
+    pem.lines().filter(|l| !l.starts_with("-----")).collect()
+}
+
+fn trust_list(cert: &str) -> TrustList {
+    let mut trust = TrustList::new();
+    trust.insert(KID, VerificationKey::from_pem(cert).unwrap());
+
+    trust
+}
+
+#[test]
+fn verify_es256() {
+    let hc1 = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-7, &KID),
+        sign_es256,
+    );
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    let info = hc.verify_with(&trust_list(EC_CERT)).unwrap();
+    assert_eq!(info.kid, KID);
+    assert_eq!(info.algorithm, CoseAlgorithm::ES256);
+
+    // keys inserted directly carry no country unless one is given
+    assert_eq!(info.signer_country, None);
+
+    let mut trust = TrustList::new();
+    trust.insert_with_country(KID, VerificationKey::from_pem(EC_CERT).unwrap(), "DE");
+
+    let info = hc.verify_with(&trust).unwrap();
+    assert_eq!(info.signer_country, Some("DE".into()));
+    assert_ne!(info.signer_country, hc.some_issuer);
+}
+
+#[test]
+fn verify_ps256() {
+    let hc1 = resign(
+        RECOVERY_SAMPLE_PAYLOAD,
+        protected_header(-37, &KID),
+        sign_ps256,
+    );
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    hc.verify_with(&trust_list(RSA_CERT)).unwrap();
+}
+
+#[test]
+fn verify_es384() {
+    let hc1 = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-35, &KID),
+        sign_es384,
+    );
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    hc.verify_with(&trust_list(P384_CERT)).unwrap();
+}
+
+#[test]
+fn verify_es512() {
+    let hc1 = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-36, &KID),
+        sign_es512,
+    );
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    hc.verify_with(&trust_list(P521_CERT)).unwrap();
+}
+
+#[test]
+fn verify_curve_mismatch() {
+    // a valid P-384 signature, but advertised as ES256
+    let hc1 = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-7, &KID),
+        sign_es384,
+    );
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    assert!(matches!(
+        hc.verify_with(&trust_list(P384_CERT)),
+        Err(Error::InvalidSignature)
+    ));
+
+    // ES384 with a P-256 key
+    let hc1 = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-35, &KID),
+        sign_es256,
+    );
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    assert!(matches!(
+        hc.verify_with(&trust_list(EC_CERT)),
+        Err(Error::InvalidSignature)
+    ));
+}
+
+#[test]
+fn verify_tampered_signature() {
+    let hc1 = resign(VACCINE_SAMPLE_PAYLOAD, protected_header(-7, &KID), |data| {
+        let mut sig = sign_es256(data);
+        sig[0] ^= 0xFF;
+
+        sig
+    });
+
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    assert!(matches!(
+        hc.verify_with(&trust_list(EC_CERT)),
+        Err(Error::InvalidSignature)
+    ));
+}
+
+#[test]
+fn verify_wrong_key() {
+    let hc1 = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-7, &KID),
+        sign_es256,
+    );
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    assert!(matches!(
+        hc.verify_with(&trust_list(RSA_CERT)),
+        Err(Error::AlgorithmKeyMismatch {
+            algorithm: CoseAlgorithm::ES256,
+            key: KeyFamily::Rsa
+        })
+    ));
+
+    let hc1 = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-37, &KID),
+        sign_ps256,
+    );
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    let err = hc.verify_with(&trust_list(EC_CERT)).unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::AlgorithmKeyMismatch {
+            algorithm: CoseAlgorithm::PS256,
+            key: KeyFamily::Ecdsa
+        }
+    ));
+    assert_eq!(err.kind(), ErrorKind::Signature);
+}
+
+#[test]
+fn verify_unknown_kid() {
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD_UNPROTECTED_KID).unwrap();
+
+    assert!(matches!(
+        hc.verify_with(&trust_list(EC_CERT)),
+        Err(Error::KeyNotFound(_))
+    ));
+}
+
+#[test]
+fn trust_list_from_json() {
+    let der_b64 = pem_body(EC_CERT);
+
+    let json = format!(
+        r#"{{ "2Rk3X8HntrI=": "{}", "AQIDBAUGBwg=": {:?} }}"#,
+        der_b64, RSA_CERT
+    );
+
+    let trust = TrustList::from_json(&json).unwrap();
+    assert_eq!(trust.len(), 2);
+
+    let hc1 = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-7, &KID),
+        sign_es256,
+    );
+    let hc = greenpass::parse(&hc1).unwrap();
+
+    let info = hc.verify_with(&trust).unwrap();
+    assert_eq!(info.signer_country, Some("AT".into()));
+    assert_eq!(info.signer_country, hc.some_issuer);
+    assert_eq!(trust.country(&[1, 2, 3, 4, 5, 6, 7, 8]), Some(&"AT".into()));
+}
+
+#[test]
+fn trust_list_from_dgc_cbor() {
+    let entry = |kid: &[u8], cert: &str| {
+        Value::Map(vec![
+            (text("i"), Value::Bytes(kid.to_vec())),
+            (
+                text("c"),
+                Value::Bytes(BASE64.decode(pem_body(cert)).unwrap()),
+            ),
+        ])
+    };
+
+    let cbor = to_cbor(&cbor_map(&[(
+        "c",
+        Value::Array(vec![entry(&KID, EC_CERT), entry(&[1, 2, 3, 4], RSA_CERT)]),
+    )]));
+
+    let trust = TrustList::from_dgc_cbor(&cbor).unwrap();
+    assert_eq!(trust.len(), 2);
+
+    let hc1 = resign(
+        VACCINE_SAMPLE_PAYLOAD,
+        protected_header(-7, &KID),
+        sign_es256,
+    );
+
+    let info = greenpass::parse(&hc1).unwrap().verify_with(&trust).unwrap();
+    assert_eq!(info.signer_country, Some("AT".into()));
+
+    assert!(matches!(
+        TrustList::from_dgc_cbor(&to_cbor(&cbor_map(&[("c", text("nope"))]))),
+        Err(Error::InvalidCertificate(_))
+    ));
+    assert!(matches!(
+        TrustList::from_dgc_cbor(b"not cbor"),
+        Err(Error::InvalidCertificate(_))
+    ));
+
+    // 100k nested single-element arrays
+    let mut nested = vec![0x81; 100_000];
+    nested.push(0x00);
+
+    assert!(matches!(
+        TrustList::from_dgc_cbor(&nested),
+        Err(Error::CborTooDeep)
+    ));
+}
+
+#[test]
+fn kid_from_certificate() {
+    // SHA-256 of the DER certificate is 8096c165448b5429...
+    let kid = [0x80, 0x96, 0xc1, 0x65, 0x44, 0x8b, 0x54, 0x29];
+
+    assert_eq!(kid_from_pem(EC_CERT).unwrap(), kid);
+    assert_eq!(
+        kid_from_der(&BASE64.decode(pem_body(EC_CERT)).unwrap()),
+        kid
+    );
+
+    assert!(matches!(
+        kid_from_pem(EC_KEY),
+        Err(Error::InvalidCertificate(_))
+    ));
+}
+
+#[test]
+fn counter_signature() {
+    let mut cose = decode_cose(VACCINE_SAMPLE_PAYLOAD);
+
+    let counter = Value::Array(vec![
+        Value::Bytes(to_cbor(&Value::Map(vec![(
+            Value::Integer(1.into()),
+            Value::Integer((-35).into()),
+        )]))),
+        Value::Map(vec![(
+            Value::Integer(4.into()),
+            Value::Bytes(vec![1, 2, 3]),
+        )]),
+        Value::Bytes(vec![0xAB; 96]),
+    ]);
+
+    match &mut cose[1] {
+        Value::Map(unprotected) => unprotected.push((Value::Integer(7.into()), counter)),
+        _ => panic!("invalid unprotected header"),
+    }
+
+    let hc = greenpass::parse(&encode_cose(cose)).unwrap();
+    let cs = hc.signature.counter_signature.as_ref().unwrap();
+
+    assert_eq!(cs.algorithm, -35);
+    assert_eq!(cs.kid, [1, 2, 3]);
+    assert_eq!(cs.signature, [0xAB; 96]);
+    assert!(hc
+        .to_string()
+        .contains("Countersigned by KID 010203 (algorithm -35)"));
+
+    assert!(greenpass::parse(VACCINE_SAMPLE_PAYLOAD)
+        .unwrap()
+        .signature
+        .counter_signature
+        .is_none());
+
+    let mut cose = decode_cose(VACCINE_SAMPLE_PAYLOAD);
+
+    match &mut cose[1] {
+        Value::Map(unprotected) => unprotected.push((Value::Integer(7.into()), text("nope"))),
+        _ => panic!("invalid unprotected header"),
+    }
+
+    assert!(matches!(
+        greenpass::parse(&encode_cose(cose)),
+        Err(Error::InvalidFormatFor { key }) if key == "countersignature"
+    ));
+}
+
+#[test]
+fn signed_data() {
+    let signed = std::cell::RefCell::new(Vec::new());
+    let hc1 = resign(VACCINE_SAMPLE_PAYLOAD, protected_header(-7, &KID), |data| {
+        *signed.borrow_mut() = data.to_vec();
+        sign_es256(data)
+    });
+
+    let hc = greenpass::parse(&hc1).unwrap();
+    assert_eq!(hc.signed_data(), signed.into_inner());
+    assert_eq!(
+        hc.signed_data(),
+        sig_structure(&hc.raw.protected, &hc.raw.payload)
+    );
+}
+
+#[test]
+fn verify_x5chain() {
+    let der = |pem: &str| BASE64.decode(pem_body(pem)).unwrap();
+    let sign = |data: &[u8]| {
+        let key = p256::ecdsa::SigningKey::from_pkcs8_pem(CHAIN_KEY).unwrap();
+        let sig: p256::ecdsa::Signature = key.sign(data);
+
+        sig.to_vec()
+    };
+
+    let with_x5chain = |x5chain: Value| {
+        let mut cose = decode_cose(&resign(
+            VACCINE_SAMPLE_PAYLOAD,
+            protected_header(-7, &KID),
+            sign,
+        ));
+
+        match &mut cose[1] {
+            Value::Map(unprotected) => unprotected.push((Value::Integer(33.into()), x5chain)),
+            _ => panic!("invalid unprotected header"),
+        }
+
+        greenpass::parse(&encode_cose(cose)).unwrap()
+    };
+
+    let hc = with_x5chain(Value::Array(vec![
+        Value::Bytes(der(CHAIN_CERT)),
+        Value::Bytes(der(INTERMEDIATE_CERT)),
+    ]));
+
+    let csca = VerificationKey::from_pem(CSCA_CERT).unwrap();
+
+    let info = hc
+        .verify_with_x5chain(Some(std::slice::from_ref(&csca)))
+        .unwrap();
+    assert_eq!(info.algorithm, CoseAlgorithm::ES256);
+    assert_eq!(info.kid, kid_from_der(&der(CHAIN_CERT)));
+    assert_eq!(info.signer_country, Some("AT".into()));
+
+    // without roots only the signature is checked
+    hc.verify_with_x5chain(None).unwrap();
+
+    let wrong_root = VerificationKey::from_pem(P384_CERT).unwrap();
+    assert!(matches!(
+        hc.verify_with_x5chain(Some(&[wrong_root])),
+        Err(Error::UntrustedChain(_))
+    ));
+
+    // the intermediate is missing, so the leaf cannot be tied to the CSCA
+    let hc = with_x5chain(Value::Bytes(der(CHAIN_CERT)));
+    hc.verify_with_x5chain(None).unwrap();
+    assert!(matches!(
+        hc.verify_with_x5chain(Some(&[csca])),
+        Err(Error::UntrustedChain(_))
+    ));
+
+    // the leaf did not sign the certificate
+    let hc = with_x5chain(Value::Bytes(der(EC_CERT)));
+    assert!(hc.verify_with_x5chain(None).is_err());
+
+    assert!(matches!(
+        greenpass::parse(VACCINE_SAMPLE_PAYLOAD)
+            .unwrap()
+            .verify_with_x5chain(None),
+        Err(Error::MissingKey(key)) if key == "x5chain"
+    ));
+}
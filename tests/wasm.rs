@@ -0,0 +1,13 @@
+#![cfg(feature = "wasm")]
+
+mod common;
+use common::*;
+
+// Only the success path can run outside of a WebAssembly host, as errors are JavaScript objects
+#[test]
+fn parse_to_json() {
+    let json = greenpass::parse_to_json(VACCINE_SAMPLE_PAYLOAD).unwrap();
+    let hc = greenpass::parse(VACCINE_SAMPLE_PAYLOAD).unwrap();
+
+    assert_eq!(json, serde_json::to_string(&hc).unwrap());
+}